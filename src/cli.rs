@@ -1,8 +1,16 @@
-use clap::{ArgAction, Args, Parser, Subcommand};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand};
 use clap_complete::Shell;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 
 #[derive(Parser)]
-#[command(name = "gw", version, about = "git worktree helper")]
+// Repeating a flag is not an error, so `[command_defaults]` can be restated
+#[command(
+    name = "gw",
+    version,
+    about = "git worktree helper",
+    args_override_self = true
+)]
 pub struct Cli {
     #[arg(short = 'v', long = "verbose", action = ArgAction::SetTrue)]
     pub verbose: bool,
@@ -12,6 +20,21 @@ pub struct Cli {
     pub color: String,
     #[arg(long = "json", action = ArgAction::SetTrue)]
     pub json: bool,
+    /// Answer yes to every confirmation prompt
+    #[arg(short = 'y', long = "yes", global = true, action = ArgAction::SetTrue)]
+    pub yes: bool,
+    /// Never prompt, even on a terminal; checks that would ask refuse instead
+    #[arg(long = "no-input", global = true, action = ArgAction::SetTrue)]
+    pub no_input: bool,
+    /// Skip the commands configured in [hooks]
+    #[arg(long = "no-hooks", global = true, action = ArgAction::SetTrue)]
+    pub no_hooks: bool,
+    /// Run as if gw was started in <path>; repeatable like `git -C`
+    #[arg(short = 'C', value_name = "path", action = ArgAction::Append)]
+    pub chdir: Vec<String>,
+    /// Run in a repository registered in ~/.gw/workspaces.toml
+    #[arg(long = "repo", conflicts_with = "chdir")]
+    pub repo: Option<String>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -22,6 +45,8 @@ pub enum Commands {
     Add(AddArgs),
     #[command(visible_aliases = ["rm", "d"])]
     Del(DelArgs),
+    #[command(visible_alias = "mv")]
+    Rename(RenameArgs),
     #[command(visible_alias = "ls")]
     List(ListArgs),
     #[command(visible_alias = "st")]
@@ -30,10 +55,13 @@ pub enum Commands {
     Apply(ApplyArgs),
     #[command(visible_aliases = ["sy"])]
     Sync(SyncArgs),
+    Train(TrainArgs),
+    Conflicts(ConflictsArgs),
     #[command(visible_aliases = ["v"])]
     Verify(VerifyArgs),
     #[command(visible_aliases = ["n"])]
     Note(NoteArgs),
+    Meta(MetaArgs),
     #[command(visible_aliases = ["show", "i"])]
     Info(InfoArgs),
     #[command(visible_alias = "lk")]
@@ -46,48 +74,207 @@ pub enum Commands {
     Cd(CdArgs),
     #[command(visible_alias = "x")]
     Exec(ExecArgs),
+    Watch(WatchArgs),
+    Git(GitArgs),
     Subdir(SubdirArgs),
+    Stats(StatsArgs),
+    Summary(SummaryArgs),
+    Log(LogArgs),
+    Review(ReviewArgs),
+    Search(SearchArgs),
+    Scan(ScanArgs),
+    #[command(visible_alias = "cp")]
+    Copy(CopyArgs),
+    Transfer(TransferArgs),
+    Stash(StashArgs),
+    Backup(BackupArgs),
+    Restore(RestoreArgs),
+    Assign(AssignArgs),
+    Daemon(DaemonArgs),
+    Serve(ServeArgs),
+    Code(CodeArgs),
+    Env(EnvArgs),
+    Recent(RecentArgs),
+    Touch(TouchArgs),
+    Root(RootArgs),
+    Browse(BrowseArgs),
+    Switch(SwitchArgs),
     Config(ConfigArgs),
     Completion(CompletionArgs),
     ShellInit(ShellInitArgs),
     #[command(name = "_complete", hide = true)]
     Complete_(CompleteTypeArgs),
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
-#[derive(Args)]
+impl Commands {
+    /// Commands that change worktree state or meta.json and must not run concurrently.
+    pub fn takes_repo_lock(&self) -> Option<String> {
+        let operation = match self {
+            Commands::Add(_) => "add",
+            Commands::Del(_) => "del",
+            Commands::Rename(_) => "rename",
+            // Long runs say what they cover, as other terminals see it in `gw lock --list`
+            Commands::Gc(args) if args.prune => "gc --prune",
+            Commands::Gc(_) => "gc",
+            Commands::Apply(_) => "apply",
+            Commands::Train(args) if !args.names.is_empty() => {
+                return Some(format!("train {}", args.names.join(" ")))
+            }
+            Commands::Train(_) => "train",
+            Commands::Sync(args) if args.all => "sync --all",
+            Commands::Sync(SyncArgs {
+                name: Some(name), ..
+            }) => return Some(format!("sync {}", name)),
+            Commands::Sync(_) => "sync",
+            Commands::Copy(_) => "copy",
+            Commands::Transfer(_) => "transfer",
+            Commands::Restore(_) => "restore",
+            // Everything else that writes worktree metadata, so saves don't race
+            Commands::Note(_) => "note",
+            Commands::Meta(MetaArgs {
+                action:
+                    MetaAction::Set { .. }
+                    | MetaAction::Unset { .. }
+                    | MetaAction::Pull { .. }
+                    | MetaAction::Migrate { .. },
+            }) => "meta",
+            Commands::Assign(_) => "assign",
+            Commands::Subdir(_) => "subdir",
+            Commands::Touch(_) => "touch",
+            Commands::Scan(_) => "scan",
+            Commands::Verify(_) => "verify",
+            Commands::Cd(_) => "cd",
+            Commands::Switch(_) => "switch",
+            _ => return None,
+        };
+        Some(operation.to_string())
+    }
+
+    /// `--workspace` selection for commands that can aggregate across repos.
+    pub fn workspace(&self) -> Option<&str> {
+        match self {
+            Commands::List(args) => args.workspace.as_deref(),
+            Commands::Status(args) => args.workspace.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Args, Default)]
 pub struct AddArgs {
-    pub name: String,
+    #[arg(required_unless_present_any = ["branch", "from_issue"])]
+    pub name: Option<String>,
     #[arg(short = 'b', long = "base")]
     pub base: Option<String>,
     #[arg(short = 'B', long = "branch")]
     pub branch: Option<String>,
     #[arg(long = "path")]
     pub path: Option<String>,
+    /// Create it under the scratch dir ([defaults] tmp_dir) as an ephemeral worktree
+    #[arg(long = "tmp", action = ArgAction::SetTrue, conflicts_with = "path")]
+    pub tmp: bool,
     #[arg(long = "subdir")]
     pub subdir: Option<String>,
+    #[arg(long = "ephemeral", action = ArgAction::SetTrue)]
+    pub ephemeral: bool,
+    #[arg(long = "ttl")]
+    pub ttl: Option<String>,
+    #[arg(long = "slug", action = ArgAction::SetTrue)]
+    pub slug: bool,
+    #[arg(long = "fetch", action = ArgAction::SetTrue)]
+    pub fetch: bool,
+    #[arg(long = "detach", action = ArgAction::SetTrue, conflicts_with = "branch")]
+    pub detach: bool,
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Check out the branch even though another worktree already has it
+    #[arg(short = 'f', long = "force", action = ArgAction::SetTrue)]
+    pub force: bool,
+    /// Take the branch over: detach the worktree that has it checked out
+    #[arg(long = "move", action = ArgAction::SetTrue, conflicts_with = "force")]
+    pub move_branch: bool,
+    /// Copy files matching <glob> from the repo root (repeatable; adds to [defaults] copy_files)
+    #[arg(long = "copy", value_name = "glob")]
+    pub copy: Vec<String>,
+    /// Name the worktree after an issue and link it ([issue] provider)
+    #[arg(long = "from-issue", value_name = "ID")]
+    pub from_issue: Option<String>,
+    /// Apply a [template.<name>] preset (base, branch prefix, subdir, tags, copy, bootstrap)
+    #[arg(long = "template", value_name = "name")]
+    pub template: Option<String>,
 }
 
-#[derive(Args)]
+#[derive(Args, Default)]
 pub struct DelArgs {
     pub name: String,
     #[arg(short = 'f', long = "force", action = ArgAction::SetTrue)]
     pub force: bool,
     #[arg(short = 'D', long = "delete-branch", action = ArgAction::SetTrue)]
     pub delete_branch: bool,
+    #[arg(long = "force-unpushed", action = ArgAction::SetTrue)]
+    pub force_unpushed: bool,
+}
+
+#[derive(Args)]
+pub struct RenameArgs {
+    pub name: String,
+    pub new_name: String,
+    /// New branch name (default: renamed only when it is <branch_prefix><name>)
+    #[arg(short = 'B', long = "branch", conflicts_with = "keep_branch")]
+    pub branch: Option<String>,
+    /// Leave the branch name as it is
+    #[arg(long = "keep-branch", action = ArgAction::SetTrue)]
+    pub keep_branch: bool,
+}
+
+#[derive(Args, Default, Clone)]
+pub struct OwnerFilterArgs {
+    /// Only worktrees assigned to you, or unassigned ones you created
+    #[arg(long = "mine", action = ArgAction::SetTrue)]
+    pub mine: bool,
+    #[arg(long = "owner")]
+    pub owner: Option<String>,
+    /// Only worktrees created by <user> (`user` or `user@host`)
+    #[arg(long = "user")]
+    pub user: Option<String>,
 }
 
 #[derive(Args, Default)]
 pub struct ListArgs {
     #[arg(short = 'v', long = "verbose", action = ArgAction::SetTrue)]
     pub verbose: bool,
+    #[arg(long = "group-by", value_parser = ["tag", "owner"])]
+    pub group_by: Option<String>,
+    #[arg(long = "fetch", action = ArgAction::SetTrue)]
+    pub fetch: bool,
+    #[arg(long = "porcelain", action = ArgAction::SetTrue)]
+    pub porcelain: bool,
+    #[arg(long = "workspace", conflicts_with = "porcelain")]
+    pub workspace: Option<String>,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
 }
 
 #[derive(Args, Default)]
 pub struct StatusArgs {
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
     #[arg(long = "changes-detail", action = ArgAction::SetTrue)]
     pub changes_detail: bool,
-    #[arg(long = "recent", default_value_t = 3)]
-    pub recent: usize,
+    #[arg(long = "recent")]
+    pub recent: Option<usize>,
+    #[arg(long = "cached", action = ArgAction::SetTrue)]
+    pub cached: bool,
+    #[arg(long = "fetch", action = ArgAction::SetTrue)]
+    pub fetch: bool,
+    #[arg(long = "porcelain", action = ArgAction::SetTrue)]
+    pub porcelain: bool,
+    #[arg(long = "workspace", conflicts_with = "porcelain")]
+    pub workspace: Option<String>,
+    #[arg(short = 's', long = "short", action = ArgAction::SetTrue, conflicts_with = "porcelain")]
+    pub short: bool,
 }
 
 #[derive(Args)]
@@ -103,6 +290,48 @@ pub struct ApplyArgs {
     pub rebase: bool,
     #[arg(short = 'c', long = "cleanup", action = ArgAction::SetTrue)]
     pub cleanup: bool,
+    /// Message for the merge or squash commit (skips the squash editor)
+    #[arg(short = 'm', long = "message")]
+    pub message: Option<String>,
+    /// Apply into a branch listed in [apply] protected
+    #[arg(long = "allow-protected", action = ArgAction::SetTrue)]
+    pub allow_protected: bool,
+}
+
+#[derive(Args)]
+pub struct TrainArgs {
+    /// Worktrees to land, in order
+    #[arg(required_unless_present_any = ["resume", "abort"])]
+    pub names: Vec<String>,
+    #[arg(short = 't', long = "target")]
+    pub target: Option<String>,
+    #[arg(long = "merge", action = ArgAction::SetTrue)]
+    pub merge: bool,
+    #[arg(long = "squash", action = ArgAction::SetTrue)]
+    pub squash: bool,
+    #[arg(long = "rebase", action = ArgAction::SetTrue)]
+    pub rebase: bool,
+    /// Land into a branch listed in [apply] protected
+    #[arg(long = "allow-protected", action = ArgAction::SetTrue)]
+    pub allow_protected: bool,
+    /// Resume a stopped train after fixing the failed step
+    #[arg(long = "continue", action = ArgAction::SetTrue, conflicts_with_all = ["names", "abort"])]
+    pub resume: bool,
+    /// Forget a stopped train; already landed worktrees stay applied
+    #[arg(long = "abort", action = ArgAction::SetTrue, conflicts_with = "names")]
+    pub abort: bool,
+}
+
+#[derive(Args)]
+pub struct ConflictsArgs {
+    /// Branch to check against (default: the base branch)
+    #[arg(short = 't', long = "target")]
+    pub target: Option<String>,
+    /// Also check every pair of worktrees against each other
+    #[arg(long = "pairwise", action = ArgAction::SetTrue)]
+    pub pairwise: bool,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
 }
 
 #[derive(Args)]
@@ -116,6 +345,8 @@ pub struct SyncArgs {
     pub merge: bool,
     #[arg(short = 'A', long = "all", action = ArgAction::SetTrue)]
     pub all: bool,
+    #[arg(long = "fetch", action = ArgAction::SetTrue)]
+    pub fetch: bool,
 }
 
 #[derive(Args)]
@@ -125,6 +356,14 @@ pub struct VerifyArgs {
     pub subdir: Option<String>,
     #[arg(long = "root", action = ArgAction::SetTrue)]
     pub root: bool,
+    #[arg(long = "changed-only", action = ArgAction::SetTrue, conflicts_with_all = ["subdir", "root"])]
+    pub changed_only: bool,
+    /// Run the configured [verify] fix_* commands before verifying
+    #[arg(long = "fix", action = ArgAction::SetTrue)]
+    pub fix: bool,
+    /// Commit the fixes (also enabled by [verify] fix_commit)
+    #[arg(long = "commit", action = ArgAction::SetTrue, requires = "fix")]
+    pub commit: bool,
 }
 
 #[derive(Args)]
@@ -133,14 +372,70 @@ pub struct NoteArgs {
     pub text: String,
 }
 
+#[derive(Args)]
+pub struct MetaArgs {
+    #[command(subcommand)]
+    pub action: MetaAction,
+}
+
+/// Custom key-value fields on a worktree (ticket, reviewer, environment...).
+#[derive(Subcommand)]
+pub enum MetaAction {
+    Set {
+        name: String,
+        key: String,
+        value: String,
+    },
+    /// Print one field, or every field as `key=value` without <key>
+    Get {
+        name: String,
+        key: Option<String>,
+    },
+    Unset {
+        name: String,
+        key: String,
+    },
+    /// Past versions of worktree metadata, newest first (sqlite backend)
+    History {
+        name: Option<String>,
+        #[arg(short = 'n', long = "limit", default_value_t = 20)]
+        limit: usize,
+    },
+    /// Share notes, tags, subdir, owner and fields on refs/gw/meta
+    Push {
+        /// Remote to share with (default: [meta] remote, or origin)
+        #[arg(long = "remote")]
+        remote: Option<String>,
+    },
+    /// Merge teammates' annotations from refs/gw/meta into matching worktrees
+    Pull {
+        #[arg(long = "remote")]
+        remote: Option<String>,
+    },
+    /// Copy metadata from the other backend into this one
+    Migrate {
+        #[arg(long = "to", value_parser = ["sqlite", "json"], default_value = "sqlite")]
+        to: String,
+    },
+}
+
 #[derive(Args)]
 pub struct InfoArgs {
     pub name: String,
+    #[arg(long = "porcelain", action = ArgAction::SetTrue)]
+    pub porcelain: bool,
 }
 
 #[derive(Args)]
 pub struct LockArgs {
-    pub name: String,
+    #[arg(required_unless_present = "list")]
+    pub name: Option<String>,
+    /// Why the worktree is locked; shown when an operation refuses it
+    #[arg(short = 'r', long = "reason", conflicts_with = "list")]
+    pub reason: Option<String>,
+    /// Show locked worktrees and the gw operation in progress, if any
+    #[arg(short = 'l', long = "list", action = ArgAction::SetTrue, conflicts_with = "name")]
+    pub list: bool,
 }
 
 #[derive(Args)]
@@ -152,6 +447,13 @@ pub struct UnlockArgs {
 pub struct GcArgs {
     #[arg(long = "prune", action = ArgAction::SetTrue)]
     pub prune: bool,
+    #[arg(long = "delete-branches", action = ArgAction::SetTrue, requires = "prune")]
+    pub delete_branches: bool,
+    /// Also propose worktrees that belong to other users
+    #[arg(long = "all-users", action = ArgAction::SetTrue)]
+    pub all_users: bool,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
 }
 
 #[derive(Args)]
@@ -159,6 +461,9 @@ pub struct CdArgs {
     pub name: Option<String>,
     #[arg(long = "shell", action = ArgAction::SetTrue)]
     pub shell: bool,
+    /// With the cd command, export the worktree environment (see `gw env`)
+    #[arg(long = "print-env", action = ArgAction::SetTrue)]
+    pub print_env: bool,
     #[arg(long = "subdir")]
     pub subdir: Option<String>,
     #[arg(long = "root", action = ArgAction::SetTrue)]
@@ -175,17 +480,276 @@ pub struct ExecArgs {
     pub parallel: bool,
     #[arg(long = "fail-fast", action = ArgAction::SetTrue)]
     pub fail_fast: bool,
+    /// Run a failed command up to N more times
+    #[arg(long = "retry", value_name = "N", default_value_t = 0)]
+    pub retry: u32,
+    /// Seconds to wait before each retry
+    #[arg(long = "retry-delay", value_name = "SECS", default_value_t = 1)]
+    pub retry_delay: u64,
+    /// Also run in locked worktrees when targeting all of them
+    #[arg(long = "include-locked", action = ArgAction::SetTrue)]
+    pub include_locked: bool,
+    /// Show targets, directories, env and the command without running anything
+    #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+    /// Target order: name, activity (most recent first), or reverse
+    #[arg(long = "order", value_parser = ["name", "activity", "reverse"])]
+    pub order: Option<String>,
     #[arg(long = "subdir")]
     pub subdir: Option<String>,
     #[arg(long = "root", action = ArgAction::SetTrue)]
     pub root: bool,
-    #[arg(required = true, trailing_var_arg = true)]
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
+    /// Run this script file in each worktree; trailing arguments are passed to it
+    #[arg(long = "script", value_name = "path")]
+    pub script: Option<String>,
+    #[arg(required_unless_present = "script", trailing_var_arg = true)]
     pub cmd: Vec<String>,
 }
 
+#[derive(Args)]
+pub struct WatchArgs {
+    #[arg(required_unless_present = "all")]
+    pub name: Option<String>,
+    #[arg(short = 'A', long = "all", action = ArgAction::SetTrue)]
+    pub all: bool,
+    /// Quiet period after the last change before rerunning, in milliseconds
+    #[arg(long = "debounce", value_name = "MS", default_value_t = 300)]
+    pub debounce: u64,
+    /// How often to look for changes, in milliseconds
+    #[arg(long = "interval", value_name = "MS", default_value_t = 500)]
+    pub interval: u64,
+    #[arg(long = "subdir")]
+    pub subdir: Option<String>,
+    #[arg(long = "root", action = ArgAction::SetTrue)]
+    pub root: bool,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
+    #[arg(required = true, last = true)]
+    pub cmd: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct GitArgs {
+    /// Worktree to run git in (`root` for the main checkout)
+    pub name: String,
+    #[arg(long = "subdir")]
+    pub subdir: Option<String>,
+    /// Run at the worktree root instead of its subdir
+    #[arg(long = "root", action = ArgAction::SetTrue)]
+    pub root: bool,
+    #[arg(required = true, last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct SummaryArgs {
+    #[arg(required_unless_present = "all")]
+    pub name: Option<String>,
+    /// One digest covering every worktree
+    #[arg(short = 'A', long = "all", action = ArgAction::SetTrue)]
+    pub all: bool,
+    /// Save to .gw/summaries/<name>.md (digest.md with --all) instead of printing
+    #[arg(long = "write", action = ArgAction::SetTrue)]
+    pub write: bool,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
+}
+
+#[derive(Args)]
+pub struct ScanArgs {
+    /// Show what would be registered without saving
+    #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {}
+
+#[derive(Args)]
+pub struct LogArgs {
+    #[arg(required_unless_present = "applied")]
+    pub name: Option<String>,
+    /// List commits made by `gw apply` (optionally only those from <name>)
+    #[arg(long = "applied", action = ArgAction::SetTrue, conflicts_with_all = ["patch", "stat", "oneline"])]
+    pub applied: bool,
+    #[arg(short = 'p', long = "patch", action = ArgAction::SetTrue)]
+    pub patch: bool,
+    #[arg(long = "stat", action = ArgAction::SetTrue)]
+    pub stat: bool,
+    #[arg(long = "oneline", action = ArgAction::SetTrue)]
+    pub oneline: bool,
+}
+
+#[derive(Args)]
+pub struct ReviewArgs {
+    pub name: String,
+    /// Include uncommitted changes to tracked files
+    #[arg(short = 'u', long = "uncommitted", action = ArgAction::SetTrue)]
+    pub uncommitted: bool,
+    /// List changed files with their status
+    #[arg(long = "files", action = ArgAction::SetTrue, conflicts_with = "stat")]
+    pub files: bool,
+    #[arg(long = "stat", action = ArgAction::SetTrue)]
+    pub stat: bool,
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    pub query: String,
+    /// Treat the query as a regular expression
+    #[arg(short = 'E', long = "regex", action = ArgAction::SetTrue)]
+    pub regex: bool,
+    #[command(flatten)]
+    pub filter: OwnerFilterArgs,
+}
+
+#[derive(Args)]
+pub struct CopyArgs {
+    pub src: String,
+    pub name: String,
+    #[arg(short = 'B', long = "branch")]
+    pub branch: Option<String>,
+}
+
+#[derive(Args)]
+pub struct TransferArgs {
+    pub from: String,
+    pub to: String,
+    #[arg(short = 'u', long = "include-untracked", action = ArgAction::SetTrue)]
+    pub include_untracked: bool,
+    #[arg(long = "keep", action = ArgAction::SetTrue)]
+    pub keep: bool,
+}
+
+/// Archive `.gw/` state (config, meta, locks) to a `.tar.gz`
+#[derive(Args)]
+pub struct BackupArgs {
+    /// Archive path (default: ~/.gw/backups/<repo>/<timestamp>.tar.gz)
+    #[arg(long = "to")]
+    pub to: Option<String>,
+}
+
+/// Restore `.gw/` state from a backup
+#[derive(Args)]
+pub struct RestoreArgs {
+    /// Backup archive (default: this repository's newest backup)
+    pub path: Option<String>,
+    /// Overwrite existing config and meta
+    #[arg(short = 'f', long = "force", action = ArgAction::SetTrue)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct StashArgs {
+    #[command(subcommand)]
+    pub action: StashAction,
+}
+
+#[derive(Subcommand)]
+pub enum StashAction {
+    Push {
+        name: String,
+        #[arg(short = 'm', long = "message")]
+        message: Option<String>,
+        #[arg(short = 'u', long = "include-untracked", action = ArgAction::SetTrue)]
+        include_untracked: bool,
+    },
+    Pop {
+        name: String,
+        #[arg(default_value_t = 0)]
+        index: usize,
+    },
+    #[command(visible_alias = "ls")]
+    List { name: Option<String> },
+}
+
+#[derive(Args)]
+pub struct AssignArgs {
+    pub name: String,
+    pub owner: Option<String>,
+    #[arg(long = "unset", action = ArgAction::SetTrue)]
+    pub unset: bool,
+}
+
+#[derive(Args)]
+pub struct DaemonArgs {
+    #[arg(long = "interval", default_value_t = 10)]
+    pub interval: u64,
+    #[arg(long = "once", action = ArgAction::SetTrue)]
+    pub once: bool,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    #[arg(long = "socket")]
+    pub socket: String,
+}
+
+#[derive(Args)]
+pub struct CodeArgs {
+    pub names: Vec<String>,
+    #[arg(short = 'A', long = "all", action = ArgAction::SetTrue)]
+    pub all: bool,
+    #[arg(long = "subdir")]
+    pub subdir: Option<String>,
+    #[arg(long = "root", action = ArgAction::SetTrue)]
+    pub root: bool,
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<String>,
+    #[arg(long = "no-open", action = ArgAction::SetTrue)]
+    pub no_open: bool,
+}
+
+#[derive(Args)]
+pub struct EnvArgs {
+    pub name: Option<String>,
+    #[arg(long = "format", default_value = "sh", value_parser = ["sh", "fish", "json"])]
+    pub format: String,
+}
+
+#[derive(Args)]
+pub struct RootArgs {
+    /// Print the git directory shared by all worktrees instead
+    #[arg(long = "git-dir", action = ArgAction::SetTrue)]
+    pub git_dir: bool,
+}
+
+#[derive(Args)]
+pub struct BrowseArgs {
+    /// Worktree whose issue to open; defaults to the current one
+    pub name: Option<String>,
+    /// Print the issue URL instead of opening it
+    #[arg(long = "print", action = ArgAction::SetTrue)]
+    pub print: bool,
+}
+
+#[derive(Args)]
+pub struct TouchArgs {
+    /// Worktree to mark as active; defaults to the one containing the current directory
+    pub name: Option<String>,
+}
+
+#[derive(Args)]
+pub struct RecentArgs {
+    #[arg(short = 'n', long = "count", default_value_t = 5)]
+    pub count: usize,
+}
+
+#[derive(Args)]
+pub struct SwitchArgs {
+    /// Print the preview pane for <name> (used by the fzf picker)
+    #[arg(long = "preview", hide = true)]
+    pub preview: Option<String>,
+}
+
 #[derive(Args)]
 pub struct CompletionArgs {
-    pub shell: Shell,
+    #[arg(required_unless_present = "install")]
+    pub shell: Option<Shell>,
+    #[arg(long = "install", action = ArgAction::SetTrue)]
+    pub install: bool,
 }
 
 #[derive(Args)]
@@ -194,6 +758,8 @@ pub struct SubdirArgs {
     pub path: Option<String>,
     #[arg(long = "unset", action = ArgAction::SetTrue)]
     pub unset: bool,
+    #[arg(long = "auto", action = ArgAction::SetTrue, conflicts_with_all = ["path", "unset"])]
+    pub auto: bool,
 }
 
 #[derive(Args)]
@@ -204,8 +770,14 @@ pub struct ConfigArgs {
 
 #[derive(Args)]
 pub struct CompleteTypeArgs {
-    #[arg(value_parser = ["worktrees"])]
+    #[arg(value_parser = ["worktrees", "branches", "tags", "subdirs", "config-keys"])]
     pub comp_type: String,
+    /// Worktree whose directories `subdirs` completes (default: current)
+    #[arg(long = "worktree", allow_hyphen_values = true)]
+    pub worktree: Option<String>,
+    /// Word being completed
+    #[arg(long = "prefix", default_value = "", allow_hyphen_values = true)]
+    pub prefix: String,
 }
 
 #[derive(Args)]
@@ -216,3 +788,95 @@ pub struct ShellInitArgs {
     #[arg(long = "apply", action = ArgAction::SetTrue)]
     pub apply: bool,
 }
+
+/// `args` with the `[command_defaults]` flags of the chosen subcommand inserted
+/// right after its name, so arguments given on the command line come later and
+/// win. `None` when there is nothing to insert.
+pub fn with_command_defaults(
+    mut args: Vec<OsString>,
+    defaults: &BTreeMap<String, Vec<String>>,
+) -> Option<Vec<OsString>> {
+    let command = Cli::command();
+    let mut i = 1;
+    while i < args.len() {
+        let token = args[i].to_string_lossy().to_string();
+        if token == "--" {
+            return None;
+        }
+        if token.starts_with('-') {
+            // A top-level option; skip its value too when it is a separate word
+            let takes_value = command.get_arguments().any(|arg| {
+                arg.get_action().takes_values()
+                    && (arg.get_long().is_some_and(|l| token == format!("--{}", l))
+                        || arg.get_short().is_some_and(|s| token == format!("-{}", s)))
+            });
+            i += if takes_value { 2 } else { 1 };
+            continue;
+        }
+        let sub = command
+            .get_subcommands()
+            .find(|s| s.get_name() == token || s.get_all_aliases().any(|a| a == token))?;
+        let extra = defaults
+            .get(sub.get_name())
+            .or_else(|| sub.get_all_aliases().find_map(|alias| defaults.get(alias)))?;
+        if extra.is_empty() {
+            return None;
+        }
+        args.splice(i + 1..i + 1, extra.iter().map(OsString::from));
+        return Some(args);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<OsString> {
+        line.split_whitespace().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn command_defaults_go_after_the_subcommand() {
+        let defaults: BTreeMap<String, Vec<String>> = [
+            (
+                "status".to_string(),
+                vec!["--recent".to_string(), "5".to_string()],
+            ),
+            ("del".to_string(), vec!["--delete-branch".to_string()]),
+        ]
+        .into();
+        assert_eq!(
+            with_command_defaults(args("gw -C status st --short"), &defaults),
+            Some(args("gw -C status st --recent 5 --short"))
+        );
+        assert_eq!(
+            with_command_defaults(args("gw -q rm foo"), &defaults),
+            Some(args("gw -q rm --delete-branch foo"))
+        );
+        assert_eq!(with_command_defaults(args("gw list"), &defaults), None);
+        let by_alias: BTreeMap<String, Vec<String>> =
+            [("ls".to_string(), vec!["--porcelain".to_string()])].into();
+        assert_eq!(
+            with_command_defaults(args("gw list"), &by_alias),
+            Some(args("gw list --porcelain"))
+        );
+
+        let cli = Cli::parse_from(args("gw st --recent 5 --recent 2"));
+        assert!(matches!(cli.command, Commands::Status(ref s) if s.recent == Some(2)));
+        let cli = Cli::parse_from(args("gw ls --porcelain --porcelain"));
+        assert!(matches!(cli.command, Commands::List(ref l) if l.porcelain));
+        let cli = Cli::parse_from(args("gw add x --tag a --tag b"));
+        assert!(matches!(cli.command, Commands::Add(ref a) if a.tags == ["a", "b"]));
+    }
+
+    #[test]
+    fn repo_lock_names_the_operation() {
+        let lock = |line: &str| Cli::parse_from(args(line)).command.takes_repo_lock();
+        assert_eq!(lock("gw sync -A").as_deref(), Some("sync --all"));
+        assert_eq!(lock("gw sync foo").as_deref(), Some("sync foo"));
+        assert_eq!(lock("gw gc --prune").as_deref(), Some("gc --prune"));
+        assert_eq!(lock("gw train a b").as_deref(), Some("train a b"));
+        assert_eq!(lock("gw list"), None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::git::git_error;
+use crate::{Context, Result};
+use chrono::Utc;
+
+const ARCHIVE_PREFIX: &str = "refs/gw/archive/";
+
+/// Save the tip of `branch` as `refs/gw/archive/<name>/<unix ts>` before it is
+/// deleted, then drop archives older than the configured retention.
+/// No-op unless `[archive] enabled = true`.
+pub(crate) fn archive_branch(ctx: &Context, name: &str, branch: &str) -> Result<()> {
+    if !ctx.config.archive_enabled() {
+        return Ok(());
+    }
+    let reference = format!("{}{}/{}", ARCHIVE_PREFIX, name, Utc::now().timestamp());
+    let target = format!("refs/heads/{}", branch);
+    ctx.git
+        .run(&["update-ref", &reference, &target])
+        .map_err(git_error)?;
+    if !ctx.quiet {
+        eprintln!("archived: {} -> {}", branch, reference);
+    }
+    prune_archives(ctx);
+    Ok(())
+}
+
+/// Delete archive refs past `[archive] retention_days`. Failures are ignored;
+/// the refs are simply retried on the next archive.
+fn prune_archives(ctx: &Context) {
+    let Some(days) = ctx.config.archive_retention_days() else {
+        return;
+    };
+    let cutoff = Utc::now().timestamp() - days * 24 * 60 * 60;
+    let refs = match ctx
+        .git
+        .run(&["for-each-ref", "--format=%(refname)", ARCHIVE_PREFIX])
+    {
+        Ok(out) => out,
+        Err(_) => return,
+    };
+    for reference in refs.lines() {
+        if archive_time(reference)
+            .map(|ts| ts < cutoff)
+            .unwrap_or(false)
+        {
+            let _ = ctx.git.run(&["update-ref", "-d", reference]);
+        }
+    }
+}
+
+/// Archive timestamp encoded as the last component of an archive ref.
+fn archive_time(reference: &str) -> Option<i64> {
+    reference
+        .strip_prefix(ARCHIVE_PREFIX)?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_time_from_ref() {
+        assert_eq!(
+            archive_time("refs/gw/archive/foo/1700000000"),
+            Some(1700000000)
+        );
+        assert_eq!(archive_time("refs/gw/archive/foo"), None);
+        assert_eq!(archive_time("refs/heads/foo/1700000000"), None);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::cli::{BackupArgs, RestoreArgs};
+use crate::{Context, ErrorKind, GwError, Result};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runtime files under `.gw/` that only make sense for the process that wrote them.
+const TRANSIENT: &[&str] = &[
+    ".gw/gw.lock",
+    ".gw/daemon.pid",
+    ".gw/tmp",
+    ".gw/cache",
+    ".gw/status-cache.json",
+    ".gw/auto-gc-count",
+];
+
+/// Archive `.gw/` (config, meta, locks, train state...) as a `.tar.gz`.
+/// Defaults to `~/.gw/backups/<repo>/<timestamp>.tar.gz`, outside the repository.
+pub fn backup(ctx: &Context, args: BackupArgs) -> Result<()> {
+    let path = match args.to {
+        Some(ref to) => PathBuf::from(to),
+        None => backup_dir(ctx)?.join(format!("{}.tar.gz", Local::now().format("%Y%m%d-%H%M%S"))),
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    let mut cmd = Command::new("tar");
+    cmd.arg("-czf").arg(&path).arg("-C").arg(&ctx.repo_root);
+    for pattern in TRANSIENT {
+        cmd.arg(format!("--exclude={}", pattern));
+    }
+    cmd.arg(".gw");
+    run_tar(cmd)?;
+    if ctx.json {
+        println!("{}", serde_json::json!({ "path": path }));
+    } else if !ctx.quiet {
+        println!("backup: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Unpack a backup into the repository root, replacing files in `.gw/`.
+/// Without a path, the newest backup of this repository is used.
+pub fn restore(ctx: &Context, args: RestoreArgs) -> Result<()> {
+    let path = match args.path {
+        Some(ref p) => PathBuf::from(p),
+        None => latest_backup(ctx)?,
+    };
+    if !path.is_file() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            format!("backup not found: {}", path.display()),
+        ));
+    }
+    let gw_dir = ctx.repo_root.join(".gw");
+    let has_state = ["config.toml", "meta.json", "meta.db"]
+        .iter()
+        .any(|f| gw_dir.join(f).exists());
+    if has_state && !args.force {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "{} already has gw state; pass --force to overwrite it from {}",
+                gw_dir.display(),
+                path.display()
+            ),
+        ));
+    }
+    check_entries(&path)?;
+    let mut cmd = Command::new("tar");
+    cmd.arg("-xzf").arg(&path).arg("-C").arg(&ctx.repo_root);
+    run_tar(cmd)?;
+    if !ctx.quiet {
+        println!("restored .gw from {}", path.display());
+    }
+    Ok(())
+}
+
+fn backup_dir(ctx: &Context) -> Result<PathBuf> {
+    let home = crate::config::gw_home()
+        .ok_or_else(|| GwError::new(ErrorKind::General, "cannot determine home directory"))?;
+    let repo = ctx
+        .repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    Ok(home.join("backups").join(repo))
+}
+
+fn latest_backup(ctx: &Context) -> Result<PathBuf> {
+    let dir = backup_dir(ctx)?;
+    // Timestamped names sort chronologically
+    fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| p.to_string_lossy().ends_with(".tar.gz"))
+        .max()
+        .ok_or_else(|| {
+            GwError::new(
+                ErrorKind::NotFound,
+                format!("no backups in {}", dir.display()),
+            )
+        })
+}
+
+/// Refuse archives that would write anywhere but `.gw/`.
+fn check_entries(path: &Path) -> Result<()> {
+    let out = Command::new("tar")
+        .arg("-tzf")
+        .arg(path)
+        .output()
+        .map_err(|e| GwError::new(ErrorKind::General, format!("failed to run tar: {}", e)))?;
+    if !out.status.success() {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "not a gw backup: {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        ));
+    }
+    let listing = String::from_utf8_lossy(&out.stdout);
+    if let Some(bad) = listing.lines().find(|entry| !is_gw_entry(entry)) {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!("not a gw backup: unexpected entry '{}'", bad),
+        ));
+    }
+    Ok(())
+}
+
+fn is_gw_entry(entry: &str) -> bool {
+    let entry = entry.strip_prefix("./").unwrap_or(entry);
+    (entry == ".gw" || entry.starts_with(".gw/")) && !entry.split('/').any(|part| part == "..")
+}
+
+fn run_tar(mut cmd: Command) -> Result<()> {
+    let out = cmd
+        .output()
+        .map_err(|e| GwError::new(ErrorKind::General, format!("failed to run tar: {}", e)))?;
+    if !out.status.success() {
+        return Err(GwError::new(
+            ErrorKind::General,
+            format!(
+                "tar failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gw_entries_stay_inside_gw_dir() {
+        assert!(is_gw_entry(".gw/"));
+        assert!(is_gw_entry("./.gw/meta.json"));
+        assert!(is_gw_entry(".gw/locks/foo.lock"));
+        assert!(!is_gw_entry(".gwx/meta.json"));
+        assert!(!is_gw_entry(".gw/../src/main.rs"));
+        assert!(!is_gw_entry("/etc/passwd"));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::git::{git_error, Git};
+use crate::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Files a worktree changed relative to its base: committed since the merge
+/// base, plus uncommitted and untracked files. Paths are relative to the worktree.
+pub(crate) fn changed_files(ctx: &Context, name: &str, path: &Path) -> Result<Vec<String>> {
+    let base = super::worktree_base(ctx, name)?;
+    diff_against(&ctx.git, path, &base).map_err(git_error)
+}
+
+fn diff_against(git: &Git, path: &Path, base: &str) -> std::result::Result<Vec<String>, String> {
+    let merge_base = git.run_in(path, &["merge-base", "HEAD", base])?;
+    let mut files: BTreeSet<String> = BTreeSet::new();
+    let diff = git.run_in(path, &["diff", "--name-only", merge_base.trim()])?;
+    let untracked = git.run_in(path, &["ls-files", "--others", "--exclude-standard"])?;
+    for line in diff.lines().chain(untracked.lines()) {
+        let line = line.trim();
+        if !line.is_empty() {
+            files.insert(line.to_string());
+        }
+    }
+    Ok(files.into_iter().collect())
+}
+
+/// The nearest enclosing project directory of each file, deduplicated.
+/// `is_project` is asked about `/`-separated relative dirs; "" is the worktree root.
+pub(crate) fn project_dirs(files: &[String], is_project: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut dirs = BTreeSet::new();
+    for file in files {
+        let mut dir = parent_dir(file);
+        loop {
+            if is_project(dir) {
+                dirs.insert(dir.to_string());
+                break;
+            }
+            if dir.is_empty() {
+                break;
+            }
+            dir = parent_dir(dir);
+        }
+    }
+    dirs.into_iter().collect()
+}
+
+/// Deepest directory containing every file, or "" when they only share the root.
+pub(crate) fn common_dir(files: &[String]) -> String {
+    let mut common: Option<Vec<&str>> = None;
+    for file in files {
+        let dir: Vec<&str> = parent_dir(file)
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        common = Some(match common {
+            None => dir,
+            Some(prev) => prev
+                .iter()
+                .zip(dir.iter())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| *a)
+                .collect(),
+        });
+    }
+    common.unwrap_or_default().join("/")
+}
+
+/// Subdir inferred from the worktree's changes vs base; `None` if they span the root.
+pub(crate) fn auto_subdir(ctx: &Context, name: &str, path: &Path) -> Option<String> {
+    let files = changed_files(ctx, name, path).ok()?;
+    let dir = common_dir(&files);
+    if dir.is_empty() {
+        None
+    } else {
+        Some(dir)
+    }
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rfind('/').map(|i| &path[..i]).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_dirs_picks_nearest_manifest() {
+        let files = vec![
+            "crates/core/src/lib.rs".to_string(),
+            "crates/core/Cargo.toml".to_string(),
+            "web/app/src/index.ts".to_string(),
+            "docs/guide.md".to_string(),
+            "README.md".to_string(),
+        ];
+        let projects = ["crates/core", "web/app", "web"];
+        let dirs = project_dirs(&files, |dir| projects.contains(&dir));
+        assert_eq!(dirs, vec!["crates/core".to_string(), "web/app".to_string()]);
+
+        let with_root = project_dirs(&files, |dir| dir.is_empty() || dir == "web/app");
+        assert_eq!(with_root, vec!["".to_string(), "web/app".to_string()]);
+    }
+
+    #[test]
+    fn common_dir_is_deepest_shared_parent() {
+        let files = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            common_dir(&files(&["svc/api/src/a.rs", "svc/api/tests/b.rs"])),
+            "svc/api"
+        );
+        assert_eq!(common_dir(&files(&["svc/api/src/a.rs"])), "svc/api/src");
+        assert_eq!(
+            common_dir(&files(&["svc/api/a.rs", "svc/apiv2/b.rs"])),
+            "svc"
+        );
+        assert_eq!(common_dir(&files(&["svc/a.rs", "README.md"])), "");
+        assert_eq!(common_dir(&[]), "");
+    }
+}
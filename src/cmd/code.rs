@@ -0,0 +1,62 @@
+use crate::cli::CodeArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::process::Command;
+
+/// Generate (or update) a VS Code multi-root workspace with the selected worktrees.
+/// Existing settings in the workspace file are preserved; only `folders` is rewritten.
+pub fn code(ctx: &Context, args: CodeArgs) -> Result<()> {
+    let mut folders = Vec::new();
+    if args.names.is_empty() || args.all {
+        let root = crate::paths::canonical(&ctx.repo_root);
+        for wt in ctx.worktrees()? {
+            let name = super::worktree_display_name(ctx, &wt.path, &root);
+            let dir = if name == "root" {
+                super::resolve_cd_target(ctx, None, args.root, args.subdir.as_deref())?
+            } else if super::worktree_name_with_config(ctx, &wt.path).is_some() {
+                super::resolve_worktree_dir(ctx, &wt.path, &name, args.root, args.subdir.as_deref())
+            } else {
+                continue;
+            };
+            folders.push(json!({ "name": name, "path": dir }));
+        }
+    } else {
+        for name in &args.names {
+            let dir = super::resolve_cd_target(ctx, Some(name), args.root, args.subdir.as_deref())?;
+            folders.push(json!({ "name": name, "path": dir }));
+        }
+    }
+
+    let path = match args.output {
+        Some(ref p) => std::path::PathBuf::from(p),
+        None => ctx.repo_root.join(".gw").join("gw.code-workspace"),
+    };
+    let mut workspace: Value = fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .filter(|v: &Value| v.is_object())
+        .unwrap_or_else(|| json!({}));
+    workspace["folders"] = Value::Array(folders);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    let data = serde_json::to_string_pretty(&workspace)
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    fs::write(&path, data).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+
+    if !ctx.quiet {
+        println!("workspace: {}", path.display());
+    }
+    if args.no_open {
+        return Ok(());
+    }
+    let editor = std::env::var("GW_CODE").unwrap_or_else(|_| "code".to_string());
+    Command::new(&editor).arg(&path).status().map_err(|e| {
+        GwError::new(
+            ErrorKind::General,
+            format!("failed to open '{}': {}", editor, e),
+        )
+    })?;
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::cli::ConflictsArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+
+/// A worktree branch and the paths it conflicts on.
+struct Row {
+    name: String,
+    branch: String,
+    /// Conflicting paths when merged into the target
+    target: Vec<String>,
+    /// Conflicting paths against each other row, by index (`--pairwise` only)
+    pairs: Vec<Vec<String>>,
+}
+
+/// Check every worktree branch against the target with `git merge-tree`, and
+/// with `--pairwise` against each other, to help order landings.
+pub fn conflicts(ctx: &Context, args: ConflictsArgs) -> Result<()> {
+    let target = match args.target {
+        Some(target) => target,
+        None => ctx
+            .git
+            .resolve_base(&ctx.repo_root, ctx.config.default_base())
+            .map_err(git_error)?,
+    };
+
+    let mut branches: Vec<(String, String)> = Vec::new();
+    for wt in ctx.worktrees()? {
+        let Some(name) = super::worktree_name_with_config(ctx, &wt.path) else {
+            continue;
+        };
+        let Some(branch) = wt.branch.as_deref().map(super::short_branch) else {
+            continue;
+        };
+        if super::is_detached(ctx, &name) || !super::owner_matches(ctx, &name, &args.filter) {
+            continue;
+        }
+        branches.push((name, branch));
+    }
+
+    let check = |ours: &str, theirs: &str| {
+        ctx.git
+            .merge_conflicts(&ctx.repo_root, ours, theirs)
+            .map_err(|e| GwError::new(ErrorKind::Git, format!("merge-tree failed: {}", e)))
+    };
+    let against_target = super::parallel_map(&branches, |(_, branch)| check(&target, branch));
+    let mut rows = Vec::new();
+    for ((name, branch), conflicts) in branches.iter().zip(against_target) {
+        rows.push(Row {
+            name: name.clone(),
+            branch: branch.clone(),
+            target: conflicts?,
+            pairs: vec![Vec::new(); branches.len()],
+        });
+    }
+    if args.pairwise {
+        let pairs: Vec<(usize, usize)> = (0..rows.len())
+            .flat_map(|i| (i + 1..rows.len()).map(move |j| (i, j)))
+            .collect();
+        let results =
+            super::parallel_map(&pairs, |&(i, j)| check(&rows[i].branch, &rows[j].branch));
+        for ((i, j), conflicts) in pairs.into_iter().zip(results) {
+            let conflicts = conflicts?;
+            rows[j].pairs[i] = conflicts.clone();
+            rows[i].pairs[j] = conflicts;
+        }
+    }
+
+    if ctx.json {
+        let out: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                let mut entry = serde_json::json!({
+                    "name": row.name,
+                    "branch": row.branch,
+                    "target": target,
+                    "conflicts": row.target,
+                });
+                if args.pairwise {
+                    let pairs: serde_json::Map<_, _> = rows
+                        .iter()
+                        .zip(&row.pairs)
+                        .filter(|(other, files)| other.name != row.name && !files.is_empty())
+                        .map(|(other, files)| (other.name.clone(), serde_json::json!(files)))
+                        .collect();
+                    entry["pairs"] = serde_json::Value::Object(pairs);
+                }
+                entry
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    if rows.is_empty() {
+        if !ctx.quiet {
+            println!("conflicts: no worktrees");
+        }
+        return Ok(());
+    }
+    if args.pairwise {
+        print_matrix(&rows, &target);
+    } else {
+        let headers = vec![
+            "NAME".to_string(),
+            "BRANCH".to_string(),
+            format!("INTO {}", target),
+            "FILES".to_string(),
+        ];
+        let table: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| {
+                vec![
+                    row.name.clone(),
+                    row.branch.clone(),
+                    if row.target.is_empty() {
+                        "clean"
+                    } else {
+                        "conflict"
+                    }
+                    .to_string(),
+                    row.target.join(", "),
+                ]
+            })
+            .collect();
+        super::print_table(&headers, &table);
+    }
+    Ok(())
+}
+
+/// One row and column per worktree plus a target column; a cell shows how
+/// many paths conflict, `.` for a clean merge.
+fn print_matrix(rows: &[Row], target: &str) {
+    let cell = |files: &[String]| {
+        if files.is_empty() {
+            ".".to_string()
+        } else {
+            files.len().to_string()
+        }
+    };
+    let mut headers = vec![String::new(), target.to_string()];
+    headers.extend(rows.iter().map(|r| r.name.clone()));
+    let table: Vec<Vec<String>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![row.name.clone(), cell(&row.target)];
+            line.extend(row.pairs.iter().enumerate().map(|(j, files)| {
+                if i == j {
+                    "-".to_string()
+                } else {
+                    cell(files)
+                }
+            }));
+            line
+        })
+        .collect();
+    super::print_table(&headers, &table);
+}
@@ -0,0 +1,90 @@
+use crate::cli::CopyArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+
+pub fn copy(ctx: &Context, args: CopyArgs) -> Result<()> {
+    let src = super::require_worktree(ctx, &args.src)?;
+    crate::names::validate_name(&args.name)?;
+
+    let worktrees_dir = ctx.repo_root.join(ctx.config.worktrees_dir());
+    let path = worktrees_dir.join(&args.name);
+    if path.exists() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            "worktree path already exists",
+        ));
+    }
+    let branch = args
+        .branch
+        .unwrap_or_else(|| format!("{}{}", ctx.config.branch_prefix(), args.name));
+    if ctx.git.branch_exists(&branch) {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!("branch already exists: {}", branch),
+        ));
+    }
+
+    let head = ctx
+        .git
+        .run_in(&src.path, &["rev-parse", "HEAD"])
+        .map_err(git_error)?;
+    let head = head.trim().to_string();
+
+    // Capture the source changes before creating anything
+    let patch = super::patch::scratch_path(ctx, "copy.patch")?;
+    let has_changes = super::patch::write_uncommitted_patch(ctx, &src.path, true, &patch);
+    let has_changes = match has_changes {
+        Ok(v) => v,
+        Err(err) => {
+            let _ = fs::remove_file(&patch);
+            return Err(err);
+        }
+    };
+
+    let path_str = path.to_string_lossy().to_string();
+    let created = ctx
+        .git
+        .run(&["worktree", "add", "-b", &branch, &path_str, &head])
+        .map_err(git_error);
+    if let Err(err) = created {
+        let _ = fs::remove_file(&patch);
+        return Err(err);
+    }
+
+    let applied = if has_changes {
+        super::patch::apply_patch(ctx, &path, &patch)
+    } else {
+        Ok(())
+    };
+    let _ = fs::remove_file(&patch);
+
+    let mut meta = ctx.meta.clone();
+    meta.set_created(&args.name);
+    let src_meta = meta.get(&args.src).cloned().unwrap_or_default();
+    let base = match src_meta.base {
+        Some(base) => base,
+        None => super::worktree_base(ctx, &args.src)?,
+    };
+    meta.set_base(&args.name, Some(base.clone()));
+    meta.set_subdir(&args.name, src_meta.subdir);
+    meta.ensure(&args.name).tags = src_meta.tags;
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+
+    applied?;
+
+    if !ctx.quiet {
+        println!(
+            "copied: {} -> {} (branch: {}, base: {})",
+            args.src,
+            path.display(),
+            branch,
+            base
+        );
+    }
+
+    super::propagate_files(ctx, &path, &[]);
+
+    Ok(())
+}
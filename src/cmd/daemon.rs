@@ -0,0 +1,101 @@
+use crate::cli::DaemonArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Number of recent files kept per worktree in the cache (status truncates further).
+const CACHED_RECENT_FILES: usize = 10;
+
+/// Set by SIGINT/SIGTERM; the refresh loop returns so the pid file is removed.
+static STOP: AtomicBool = AtomicBool::new(false);
+
+/// Periodically refresh `.gw/status-cache.json` so `gw status --cached` and prompt
+/// integrations can read worktree state without running git.
+pub fn daemon(ctx: &Context, args: DaemonArgs) -> Result<()> {
+    if args.once {
+        return refresh(ctx);
+    }
+
+    let pid_path = pid_file(ctx);
+    if let Some(pid) = running_pid(&pid_path) {
+        return Err(GwError::new(
+            ErrorKind::Locked,
+            format!("daemon already running (pid {})", pid),
+        ));
+    }
+    fs::write(&pid_path, std::process::id().to_string())
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let _pid_file = PidFile(pid_path);
+    stop_on_signals();
+
+    if !ctx.quiet {
+        eprintln!(
+            "gw daemon: refreshing status cache every {}s (pid {})",
+            args.interval,
+            std::process::id()
+        );
+    }
+    let interval = Duration::from_secs(args.interval.max(1));
+    while !STOP.load(Ordering::SeqCst) {
+        if let Err(err) = refresh(ctx) {
+            eprintln!("gw daemon: refresh failed: {}", err.message);
+        }
+        let started = Instant::now();
+        while started.elapsed() < interval && !STOP.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+    Ok(())
+}
+
+/// Removes `.gw/daemon.pid` when the daemon returns, unless another daemon
+/// has replaced it since.
+struct PidFile(PathBuf);
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let ours = fs::read_to_string(&self.0)
+            .is_ok_and(|pid| pid.trim() == std::process::id().to_string());
+        if ours {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn stop_on_signals() {
+    extern "C" fn on_signal(_: libc::c_int) {
+        STOP.store(true, Ordering::SeqCst);
+    }
+    let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+#[cfg(not(unix))]
+fn stop_on_signals() {}
+
+fn refresh(ctx: &Context) -> Result<()> {
+    // Other processes change the repository while the daemon runs
+    ctx.git.clear_cache();
+    let entries = super::status::collect_status(ctx, CACHED_RECENT_FILES)?;
+    super::status::save_cache(ctx, entries)
+}
+
+fn pid_file(ctx: &Context) -> PathBuf {
+    ctx.repo_root.join(".gw").join("daemon.pid")
+}
+
+fn running_pid(path: &Path) -> Option<u32> {
+    let pid: u32 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    match crate::oplock::pid_alive(pid) {
+        Some(false) => None,
+        _ => Some(pid),
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+/// Bytes used by files under `path`. Symlinks are not followed and unreadable
+/// entries are skipped, so the result is a lower bound.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(_) => return 0,
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| dir_size(&e.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// `1536` -> `1.5K`, using powers of 1024 like `du -h`.
+pub(crate) fn format_size(bytes: u64) -> String {
+    let units = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.1}{}", value, units[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("a"), vec![0u8; 100]).unwrap();
+        fs::write(dir.path().join("sub/b"), vec![0u8; 50]).unwrap();
+        assert_eq!(dir_size(dir.path()), 150);
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(50 * 1024 * 1024 * 1024), "50.0G");
+    }
+}
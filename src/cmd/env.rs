@@ -0,0 +1,180 @@
+use crate::cli::EnvArgs;
+use crate::git::{git_error, Worktree};
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+
+/// Print the environment of a worktree (the current one by default) for `eval`.
+pub fn env(ctx: &Context, args: EnvArgs) -> Result<()> {
+    let (name, wt) = match args.name {
+        Some(name) if name != "root" => {
+            let wt = super::require_worktree(ctx, &name)?;
+            (name, wt)
+        }
+        _ => current_worktree(ctx, args.name.is_some())?,
+    };
+    let vars = worktree_env(ctx, &name, &wt);
+    let format = if ctx.json {
+        "json"
+    } else {
+        args.format.as_str()
+    };
+    match format {
+        "json" => {
+            let map: serde_json::Map<String, serde_json::Value> = vars
+                .into_iter()
+                .map(|(k, v)| (k, serde_json::Value::String(v)))
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&map).unwrap_or("{}".to_string())
+            );
+        }
+        format => {
+            for (key, value) in vars {
+                println!("{}", export_line(&key, &value, format));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One variable assignment for `eval` in a `sh`, `fish` or `powershell` session.
+pub(crate) fn export_line(key: &str, value: &str, format: &str) -> String {
+    match format {
+        "fish" => format!("set -gx {} {}", key, fish_quote(value)),
+        "powershell" => format!("$env:{} = '{}'", key, value.replace('\'', "''")),
+        _ => format!("export {}={}", key, sh_quote(value)),
+    }
+}
+
+/// The worktree containing the current directory, or the repo root when `root`
+/// was requested explicitly.
+fn current_worktree(ctx: &Context, root: bool) -> Result<(String, Worktree)> {
+    let target = if root {
+        ctx.repo_root.clone()
+    } else {
+        ctx.git.current_toplevel().map_err(git_error)?
+    };
+    let repo_root = crate::paths::canonical(&ctx.repo_root);
+    for wt in ctx.worktrees()? {
+        if crate::paths::same_path(&wt.path, &target) {
+            let name = super::worktree_display_name(ctx, &wt.path, &repo_root);
+            return Ok((name, wt));
+        }
+    }
+    Err(GwError::new(
+        ErrorKind::NotFound,
+        format!("not inside a gw worktree: {}", target.display()),
+    ))
+}
+
+/// `GW_*` variables describing a worktree followed by the entries of `.gw/env` and
+/// `.gw/worktrees/<name>.env` (later files win). Exported to every command gw
+/// spawns for the worktree (exec, verify) and printed by `gw env`.
+pub(crate) fn worktree_env(ctx: &Context, name: &str, wt: &Worktree) -> Vec<(String, String)> {
+    let branch = wt
+        .branch
+        .as_ref()
+        .map(|b| super::short_branch(b))
+        .unwrap_or_default();
+    let base = super::worktree_base(ctx, name).unwrap_or_default();
+    let subdir = ctx
+        .meta
+        .get(name)
+        .and_then(|m| m.subdir.clone())
+        .or_else(|| ctx.config.default_subdir())
+        .unwrap_or_default();
+    let mut vars = vec![
+        ("GW_WORKTREE_NAME".to_string(), name.to_string()),
+        (
+            "GW_WORKTREE_PATH".to_string(),
+            wt.path.to_string_lossy().to_string(),
+        ),
+        ("GW_BRANCH".to_string(), branch),
+        ("GW_BASE".to_string(), base),
+        (
+            "GW_REPO_ROOT".to_string(),
+            ctx.repo_root.to_string_lossy().to_string(),
+        ),
+        ("GW_SUBDIR".to_string(), subdir),
+    ];
+    let gw_dir = ctx.repo_root.join(".gw");
+    for file in [
+        gw_dir.join("env"),
+        gw_dir.join("worktrees").join(format!("{}.env", name)),
+    ] {
+        if let Ok(content) = fs::read_to_string(&file) {
+            for (key, value) in parse_env_file(&content) {
+                vars.retain(|(k, _)| *k != key);
+                vars.push((key, value));
+            }
+        }
+    }
+    vars
+}
+
+/// Parse `KEY=VALUE` lines. Blank lines and `#` comments are skipped, an
+/// `export ` prefix is allowed, and matching surrounding quotes are removed.
+fn parse_env_file(content: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            continue;
+        }
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+            .unwrap_or(value);
+        vars.push((key.to_string(), value.to_string()));
+    }
+    vars
+}
+
+pub(crate) fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(sh_quote("it's"), r"'it'\''s'");
+        assert_eq!(fish_quote(r"a\b'c"), r"'a\\b\'c'");
+        assert_eq!(sh_quote(""), "''");
+        assert_eq!(export_line("A", "it's", "powershell"), "$env:A = 'it''s'");
+    }
+
+    #[test]
+    fn parse_env_file_lines() {
+        let content = "# db\nDATABASE_URL=postgres://localhost/app_1\n\nexport PORT = 3001\nNAME=\"a b\"\nQ='x'\nbad line\n=nokey\n";
+        let vars = parse_env_file(content);
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost/app_1".to_string()
+                ),
+                ("PORT".to_string(), "3001".to_string()),
+                ("NAME".to_string(), "a b".to_string()),
+                ("Q".to_string(), "x".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::git::git_error;
+use crate::meta::{MetaStore, WorktreeMeta};
+use crate::{Context, ErrorKind, GwError, Result};
+use chrono::{DateTime, Utc};
+
+/// Parse a TTL like `90s`, `30m`, `4h`, `2d`, or `1w` into seconds.
+pub(crate) fn parse_ttl(input: &str) -> Result<i64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (num, unit) = input.split_at(split);
+    let value: i64 = num.parse().map_err(|_| {
+        GwError::new(
+            ErrorKind::InvalidInput,
+            format!("invalid ttl '{}' (e.g. 30m, 4h, 2d)", input),
+        )
+    })?;
+    let scale = match unit {
+        "s" => 1,
+        "m" | "" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                format!("invalid ttl unit '{}' (use s, m, h, d, or w)", unit),
+            ))
+        }
+    };
+    if value <= 0 {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            "ttl must be positive",
+        ));
+    }
+    Ok(value * scale)
+}
+
+/// Remove ephemeral worktrees that have expired or whose branch has been merged.
+/// Runs opportunistically before commands; failures are reported but never fatal.
+/// Merges are only checked when `check_merged` is set (`gw list`, `gw gc`); other
+/// commands skip the pass entirely unless an ephemeral worktree newly expired.
+pub fn reap_ephemeral(ctx: &mut Context, check_merged: bool) {
+    let now = Utc::now().timestamp();
+    let pending = ctx
+        .meta
+        .all()
+        .values()
+        .any(|m| m.ephemeral && (check_merged || (!m.expiry_warned && expired(m, now))));
+    if !pending {
+        return;
+    }
+    match reap(ctx, check_merged) {
+        Ok(Some(meta)) => ctx.meta = meta,
+        Ok(None) => {}
+        Err(err) => {
+            if ctx.verbose {
+                eprintln!("warn: ephemeral cleanup failed: {}", err.message);
+            }
+        }
+    }
+}
+
+fn expired(meta: &WorktreeMeta, now: i64) -> bool {
+    meta.expires_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|dt| dt.timestamp() <= now)
+        .unwrap_or(false)
+}
+
+fn reap(ctx: &Context, check_merged: bool) -> Result<Option<MetaStore>> {
+    let worktrees = ctx.worktrees()?;
+    let current = ctx.git.current_toplevel().ok();
+    let now = Utc::now().timestamp();
+    let mut meta = ctx.meta.clone();
+    let mut changed = false;
+
+    for wt in worktrees {
+        let name = match super::worktree_name_with_config(ctx, &wt.path) {
+            Some(n) => n,
+            None => continue,
+        };
+        let wt_meta = match ctx.meta.get(&name) {
+            Some(m) if m.ephemeral => m,
+            _ => continue,
+        };
+        let expired = expired(wt_meta, now);
+        // Kept expired worktrees were already reported; only a merge can reap them now
+        if !check_merged && (!expired || wt_meta.expiry_warned) {
+            continue;
+        }
+        if super::is_locked(&ctx.repo_root, &name) {
+            continue;
+        }
+        // Never pull the directory out from under the caller
+        if current
+            .as_ref()
+            .map(|c| crate::paths::same_path(&wt.path, c))
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        // A fresh branch is trivially "merged"; require it to have moved first
+        let moved = wt.head.is_some() && wt.head != wt_meta.base_commit;
+        let merged = !expired
+            && moved
+            && super::dirty_files(&ctx.git, &wt.path)
+                .map(|d| d.total == 0)
+                .unwrap_or(false)
+            && super::branch_merged(&ctx.git, &wt, &ctx.repo_root);
+        if !expired && !merged {
+            continue;
+        }
+        // Expiry alone must not destroy work that exists nowhere else
+        if expired {
+            let dirty = super::dirty_files(&ctx.git, &wt.path)
+                .map(|d| d.total)
+                .unwrap_or(usize::MAX);
+            let unpushed = super::unpushed_commits(&ctx.git, &wt.path).len();
+            if dirty > 0 || unpushed > 0 {
+                let what = if dirty > 0 {
+                    "uncommitted changes"
+                } else {
+                    "unpushed commits"
+                };
+                if !wt_meta.expiry_warned {
+                    eprintln!(
+                        "warning: ephemeral worktree '{}' expired but has {}; keeping it (gw del {} to remove)",
+                        name, what, name
+                    );
+                    meta.ensure(&name).expiry_warned = true;
+                    changed = true;
+                }
+                continue;
+            }
+        }
+
+        let path = wt.path.to_string_lossy().to_string();
+        ctx.git
+            .run(&["worktree", "remove", "--force", &path])
+            .map_err(git_error)?;
+        if let Some(ref branch) = wt.branch {
+            // -d refuses unmerged branches, so expired work stays recoverable
+            let _ = ctx.git.run(&["branch", "-d", &super::short_branch(branch)]);
+        }
+        meta.remove(&name);
+        changed = true;
+        if !ctx.quiet {
+            let reason = if expired { "expired" } else { "merged" };
+            eprintln!("removed ephemeral worktree: {} ({})", name, reason);
+        }
+    }
+
+    if !changed {
+        return Ok(None);
+    }
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    Ok(Some(meta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ttl_units() {
+        assert_eq!(parse_ttl("90s").unwrap(), 90);
+        assert_eq!(parse_ttl("30m").unwrap(), 1800);
+        assert_eq!(parse_ttl("4h").unwrap(), 4 * 3600);
+        assert_eq!(parse_ttl("2d").unwrap(), 2 * 86400);
+        assert_eq!(parse_ttl("1w").unwrap(), 7 * 86400);
+        assert_eq!(parse_ttl("15").unwrap(), 900);
+    }
+
+    #[test]
+    fn parse_ttl_rejects_garbage() {
+        assert!(parse_ttl("h").is_err());
+        assert!(parse_ttl("3y").is_err());
+        assert!(parse_ttl("0h").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Keep gw's own directories out of the root worktree's `git status` by
+//! listing them in the shared `info/exclude`.
+
+use crate::Context;
+
+/// The `.gw` state (meta, locks, cache) is local; the project config stays
+/// visible so it can still be committed.
+const GW_PATTERNS: &[&str] = &["/.gw/*", "!/.gw/config.toml"];
+
+/// Exclude patterns for everything gw creates inside the repo root, paired
+/// with a path git should report as ignored once they are in place.
+fn wanted(ctx: &Context) -> Vec<(Vec<String>, String)> {
+    let mut wanted = Vec::new();
+    let worktrees_dir = ctx.repo_root.join(ctx.config.worktrees_dir());
+    if let Some(rel) =
+        crate::paths::relative_to(&super::resolve_lenient(&worktrees_dir), &ctx.repo_root)
+    {
+        wanted.push((vec![format!("/{}/", rel)], format!("{}/", rel)));
+    }
+    wanted.push((
+        GW_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        ".gw/meta.json".to_string(),
+    ));
+    wanted
+}
+
+fn is_ignored(ctx: &Context, path: &str) -> bool {
+    ctx.git
+        .run_in(&ctx.repo_root, &["check-ignore", "-q", "--", path])
+        .is_ok()
+}
+
+/// Paths gw writes that nothing (.gitignore or info/exclude) ignores yet.
+pub(crate) fn unexcluded(ctx: &Context) -> Vec<String> {
+    wanted(ctx)
+        .into_iter()
+        .map(|(_, probe)| probe)
+        .filter(|probe| !is_ignored(ctx, probe))
+        .collect()
+}
+
+/// Add the missing patterns to `info/exclude`. Failures only warn: a status
+/// cluttered by untracked dirs is not worth failing a command over.
+pub(crate) fn ensure_excluded(ctx: &Context) {
+    let patterns: Vec<String> = wanted(ctx)
+        .into_iter()
+        .filter(|(_, probe)| !is_ignored(ctx, probe))
+        .flat_map(|(patterns, _)| patterns)
+        .collect();
+    if patterns.is_empty() {
+        return;
+    }
+    match ctx.git.add_excludes(&ctx.repo_root, &patterns) {
+        Ok(()) if !ctx.quiet => {
+            eprintln!(
+                "excluded from git status (info/exclude): {}",
+                patterns.join(" ")
+            )
+        }
+        Ok(()) => {}
+        Err(e) => eprintln!("warning: could not update info/exclude: {}", e.trim()),
+    }
+}
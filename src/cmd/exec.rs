@@ -1,13 +1,24 @@
 use crate::cli::ExecArgs;
-use crate::{Context, GwError, Result};
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::thread;
+use std::time::Duration;
+
+/// (name, directory, injected env) for one exec target.
+type Target = (String, PathBuf, Vec<(String, String)>);
 
 pub fn exec_cmd(ctx: &Context, args: ExecArgs) -> Result<()> {
-    let cmd = args.cmd.join(" ");
+    let script = args.script.as_deref().map(script_source).transpose()?;
+    let cmd = match script {
+        Some(ref path) => script_cmd(path, &args.cmd),
+        None => args.cmd.join(" "),
+    };
     let target_all = args.all || args.worktrees.is_empty();
 
-    let worktrees = ctx.git.worktrees().map_err(crate::git::git_error)?;
-    let mut targets = Vec::new();
+    let worktrees = ctx.worktrees()?;
+    let mut targets: Vec<Target> = Vec::new();
 
     let cli_root = args.root;
     let cli_subdir = args.subdir.clone();
@@ -15,6 +26,18 @@ pub fn exec_cmd(ctx: &Context, args: ExecArgs) -> Result<()> {
     if target_all {
         for wt in worktrees {
             if let Some(name) = super::worktree_name_with_config(ctx, &wt.path) {
+                if !super::owner_matches(ctx, &name, &args.filter) {
+                    continue;
+                }
+                if !args.include_locked && super::is_locked(&ctx.repo_root, &name) {
+                    if !ctx.quiet {
+                        eprintln!(
+                            "exec: skipping {} (--include-locked to run there too)",
+                            super::locked_label(&ctx.repo_root, &name)
+                        );
+                    }
+                    continue;
+                }
                 let dir = super::resolve_worktree_dir(
                     ctx,
                     &wt.path,
@@ -22,61 +45,279 @@ pub fn exec_cmd(ctx: &Context, args: ExecArgs) -> Result<()> {
                     cli_root,
                     cli_subdir.as_deref(),
                 );
-                targets.push((name, dir));
+                let envs = super::env::worktree_env(ctx, &name, &wt);
+                targets.push((name, dir, envs));
             }
         }
     } else {
         for name in &args.worktrees {
-            let wt = super::find_worktree(ctx, name)?
-                .ok_or_else(|| GwError::new(1, "worktree not found"))?;
-            let dir = super::resolve_worktree_dir(
-                ctx,
-                &wt.path,
-                name,
-                cli_root,
-                cli_subdir.as_deref(),
-            );
-            targets.push((name.clone(), dir));
+            let wt = super::require_worktree(ctx, name)?;
+            let dir =
+                super::resolve_worktree_dir(ctx, &wt.path, name, cli_root, cli_subdir.as_deref());
+            let envs = super::env::worktree_env(ctx, name, &wt);
+            targets.push((name.clone(), dir, envs));
+        }
+    }
+
+    match args.order.as_deref() {
+        Some("name") => targets.sort_by(|a, b| a.0.cmp(&b.0)),
+        Some("activity") => {
+            let recent: Vec<String> = super::recent::recent_worktrees(ctx)?
+                .into_iter()
+                .map(|(name, _, _)| name)
+                .collect();
+            // Same order as `gw recent`; anything it skips goes last
+            targets.sort_by_key(|t| {
+                recent
+                    .iter()
+                    .position(|n| *n == t.0)
+                    .unwrap_or(recent.len())
+            });
         }
+        Some("reverse") => targets.reverse(),
+        _ => {}
     }
 
     let parallel = args.parallel && !args.fail_fast;
+    let shell = ctx.config.exec_shell();
+    if args.dry_run {
+        print_dry_run(ctx, &shell, &cmd, &targets);
+        return Ok(());
+    }
+    // A lone interactive target gets the terminal itself instead of a child
+    // process that gw waits on; retries, --json and --script (whose copy gw
+    // removes afterwards) need gw to stay around
+    if targets.len() == 1
+        && args.retry == 0
+        && !ctx.json
+        && script.is_none()
+        && std::io::stdout().is_terminal()
+    {
+        let (name, path, envs) = &targets[0];
+        let err = super::exec_shell(&shell, &target_cmd(ctx, name, &cmd), path, envs);
+        return Err(GwError::new(ErrorKind::General, err));
+    }
+    // Every target runs its own copy taken up front, so edits or checkouts
+    // touching the original mid-run cannot change what later targets execute
+    let copy = match script {
+        Some(ref path) => Some(copy_script(ctx, path)?),
+        None => None,
+    };
+    let cmd = match copy {
+        Some(ref copy) => script_cmd(copy, &args.cmd),
+        None => cmd,
+    };
+    let run = |(name, path, envs): &Target| {
+        super::run_shell(&shell, &target_cmd(ctx, name, &cmd), path, envs).unwrap_or(false)
+    };
+    // (succeeded, attempts) per target
+    let mut outcomes = vec![(false, 0u32); targets.len()];
+    let mut failed = false;
 
     if parallel {
-        let mut handles = Vec::new();
-        for (name, path) in targets {
-            let cmd = cmd.clone();
-            let handle = thread::spawn(move || {
-                let ok = super::run_shell(&cmd, &path).unwrap_or(false);
-                (name, ok)
+        // Each round reruns only the targets that failed in the previous one
+        let mut pending: Vec<usize> = (0..targets.len()).collect();
+        for round in 0..=args.retry {
+            if pending.is_empty() {
+                break;
+            }
+            if round > 0 {
+                let names: Vec<&str> = pending.iter().map(|&i| targets[i].0.as_str()).collect();
+                retry_wait(ctx, &names.join(", "), round, &args);
+            }
+            let results: Vec<bool> = thread::scope(|scope| {
+                let handles: Vec<_> = pending
+                    .iter()
+                    .map(|&i| {
+                        let target = &targets[i];
+                        scope.spawn(move || run(target))
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap_or(false))
+                    .collect()
             });
-            handles.push(handle);
+            for (&i, ok) in pending.iter().zip(results) {
+                outcomes[i] = (ok, round + 1);
+            }
+            pending.retain(|&i| !outcomes[i].0);
         }
-        let mut failed = false;
-        for handle in handles {
-            if let Ok((name, ok)) = handle.join() {
-                if !ok {
-                    failed = true;
-                    eprintln!("exec failed: {}", name);
-                }
-            } else {
+        for (i, (ok, _)) in outcomes.iter().enumerate() {
+            if !ok {
                 failed = true;
+                eprintln!("exec failed: {}", targets[i].0);
             }
         }
-        if failed {
-            return Err(GwError::new(1, "exec failed"));
-        }
     } else {
-        for (name, path) in targets {
-            let ok = super::run_shell(&cmd, &path).unwrap_or(false);
+        for (i, target) in targets.iter().enumerate() {
+            let mut attempt = 0;
+            let ok = loop {
+                if attempt > 0 {
+                    retry_wait(ctx, &target.0, attempt, &args);
+                }
+                attempt += 1;
+                let ok = run(target);
+                if ok || attempt > args.retry {
+                    break ok;
+                }
+            };
+            outcomes[i] = (ok, attempt);
             if !ok {
-                eprintln!("exec failed: {}", name);
+                failed = true;
+                eprintln!("exec failed: {}", target.0);
                 if args.fail_fast {
-                    return Err(GwError::new(1, "exec failed"));
+                    break;
                 }
             }
         }
     }
 
+    if ctx.json {
+        let out: Vec<_> = targets
+            .iter()
+            .zip(&outcomes)
+            .filter(|(_, (_, attempts))| *attempts > 0)
+            .map(|((name, path, _), (ok, attempts))| {
+                serde_json::json!({
+                    "name": name,
+                    "path": path.to_string_lossy(),
+                    "ok": ok,
+                    "attempts": attempts,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+    }
+    if let Some(copy) = copy {
+        let _ = fs::remove_file(copy);
+    }
+    if failed {
+        return Err(GwError::new(ErrorKind::General, "exec failed"));
+    }
     Ok(())
 }
+
+/// Announce and wait out the delay before retry number `retry` of `names`.
+fn retry_wait(ctx: &Context, names: &str, retry: u32, args: &ExecArgs) {
+    if !ctx.quiet {
+        eprintln!(
+            "exec: retrying {} in {}s ({}/{})",
+            names, args.retry_delay, retry, args.retry
+        );
+    }
+    thread::sleep(Duration::from_secs(args.retry_delay));
+}
+
+/// Absolute path of the `--script` file, resolved against the invoking directory.
+fn script_source(path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    if !path.is_file() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            format!("script not found: {}", path.display()),
+        ));
+    }
+    fs::canonicalize(path).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+}
+
+/// Snapshot the script into `.gw/tmp` and make it executable, so its shebang
+/// (or the shell's fallback for scripts without one) picks the interpreter.
+fn copy_script(ctx: &Context, source: &Path) -> Result<PathBuf> {
+    let copy = super::patch::scratch_path(ctx, "exec-script")?;
+    fs::copy(source, &copy).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&copy, fs::Permissions::from_mode(0o755))
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    Ok(copy)
+}
+
+/// Shell command running `script` with each of `args` as one argument.
+fn script_cmd(script: &Path, args: &[String]) -> String {
+    let quote = |value: &str| {
+        if cfg!(windows) {
+            format!("\"{}\"", value)
+        } else {
+            super::env::sh_quote(value)
+        }
+    };
+    std::iter::once(quote(&script.to_string_lossy()))
+        .chain(args.iter().map(|arg| quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `cmd` with `{meta.<key>}` placeholders filled from the target's custom fields.
+pub(crate) fn target_cmd(ctx: &Context, name: &str, cmd: &str) -> String {
+    match ctx.meta.get(name) {
+        Some(meta) => crate::meta::expand_fields(cmd, &meta.fields),
+        None => crate::meta::expand_fields(cmd, &Default::default()),
+    }
+}
+
+/// Print what `exec` would run for each target without running it.
+fn print_dry_run(ctx: &Context, shell: &[String], cmd: &str, targets: &[Target]) {
+    let argv = |cmd: String| {
+        let mut argv = shell.to_vec();
+        argv.push(cmd);
+        argv
+    };
+    if ctx.json {
+        let out: Vec<_> = targets
+            .iter()
+            .map(|(name, dir, envs)| {
+                let env: serde_json::Map<_, _> = envs
+                    .iter()
+                    .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                    .collect();
+                serde_json::json!({
+                    "name": name,
+                    "dir": dir.to_string_lossy(),
+                    "env": env,
+                    "command": argv(target_cmd(ctx, name, cmd)),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return;
+    }
+    println!("command: {}", argv(cmd.to_string()).join(" "));
+    for (name, dir, envs) in targets {
+        println!();
+        println!("{}  {}", name, dir.display());
+        let expanded = target_cmd(ctx, name, cmd);
+        if expanded != cmd {
+            println!("  command: {}", argv(expanded).join(" "));
+        }
+        for (key, value) in envs {
+            println!("  {}={}", key, value);
+        }
+    }
+    if targets.is_empty() {
+        println!("(no targets)");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn script_cmd_quotes_script_and_each_argument() {
+        let args = vec!["a".to_string(), "b c".to_string(), "it's".to_string()];
+        assert_eq!(
+            script_cmd(Path::new("/tmp/my script.sh"), &args),
+            r"'/tmp/my script.sh' 'a' 'b c' 'it'\''s'"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{Context, ErrorKind, GwError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How gw talks to the code host: its CLI (`gh`, `glab`) or the REST API
+/// directly through `curl`, authenticated with `GITHUB_TOKEN` / `GITLAB_TOKEN`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Forge {
+    Gh,
+    Glab,
+    GithubRest,
+    GitlabRest,
+}
+
+/// An issue as every forge reports it.
+pub(crate) struct ForgeIssue {
+    pub title: String,
+    pub url: String,
+}
+
+impl Forge {
+    /// The forge for `provider` (gh, glab or rest), or `[forge] provider`,
+    /// falling back to gh/glab picked from origin's host.
+    pub fn detect(ctx: &Context, provider: Option<&str>) -> Result<Forge> {
+        let gitlab = origin(ctx).is_some_and(|(host, _)| host.contains("gitlab"));
+        let provider = provider
+            .map(|p| p.to_string())
+            .or_else(|| ctx.config.forge_provider());
+        match provider.as_deref() {
+            None if gitlab => Ok(Forge::Glab),
+            None | Some("gh") => Ok(Forge::Gh),
+            Some("glab") => Ok(Forge::Glab),
+            Some("rest") if gitlab => Ok(Forge::GitlabRest),
+            Some("rest") => Ok(Forge::GithubRest),
+            Some(other) => Err(GwError::new(
+                ErrorKind::Config,
+                format!("unknown forge provider '{}' (use gh, glab or rest)", other),
+            )),
+        }
+    }
+
+    pub fn issue(self, ctx: &Context, id: &str) -> Result<ForgeIssue> {
+        let key = format!("{:?}-issue-{}", self, id).to_lowercase();
+        let json = cached_json(ctx, &key, || match self {
+            Forge::Gh => cli_json(ctx, "gh", &["issue", "view", id, "--json", "title,url"]),
+            Forge::Glab => cli_json(ctx, "glab", &["issue", "view", id, "--output", "json"]),
+            Forge::GithubRest => rest_json(ctx, self, &format!("issues/{}", id)),
+            Forge::GitlabRest => rest_json(ctx, self, &format!("issues/{}", id)),
+        })?;
+        let url_field = match self {
+            Forge::Gh => "url",
+            Forge::GithubRest => "html_url",
+            Forge::Glab | Forge::GitlabRest => "web_url",
+        };
+        Ok(ForgeIssue {
+            title: field(&json, &["title"]).unwrap_or_default(),
+            url: field(&json, &[url_field]).unwrap_or_default(),
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Forge::Gh => "gh",
+            Forge::Glab => "glab",
+            Forge::GithubRest | Forge::GitlabRest => "rest",
+        }
+    }
+}
+
+/// Host and project path of origin, e.g. `("github.com", "acme/api")`.
+fn origin(ctx: &Context) -> Option<(String, String)> {
+    let url = ctx.git.run(&["remote", "get-url", "origin"]).ok()?;
+    parse_remote(url.trim())
+}
+
+/// Split `git@host:group/repo.git`, `ssh://git@host/group/repo` or
+/// `https://host/group/repo.git` into host and project path.
+fn parse_remote(url: &str) -> Option<(String, String)> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        None => url.split_once(':')?,
+    };
+    let host = rest.0.rsplit('@').next()?.split(':').next()?;
+    let path = rest.1.trim_end_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), path.to_string()))
+}
+
+/// GET `endpoint` below the project's REST root with `curl`.
+fn rest_json(ctx: &Context, forge: Forge, endpoint: &str) -> Result<Value> {
+    let (host, project) = origin(ctx).ok_or_else(|| {
+        GwError::new(
+            ErrorKind::Config,
+            "cannot derive the forge API from origin's URL",
+        )
+    })?;
+    let (url, header) = if forge == Forge::GitlabRest {
+        let project = project.replace('/', "%2F");
+        let url = format!("https://{}/api/v4/projects/{}/{}", host, project, endpoint);
+        let token = std::env::var("GITLAB_TOKEN").ok();
+        (url, token.map(|t| format!("PRIVATE-TOKEN: {}", t)))
+    } else {
+        let api = if host == "github.com" {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{}/api/v3", host)
+        };
+        let url = format!("{}/repos/{}/{}", api, project, endpoint);
+        let token = std::env::var("GITHUB_TOKEN").ok();
+        (url, token.map(|t| format!("Authorization: Bearer {}", t)))
+    };
+    // The token goes through stdin (`-H @-`) so it never shows up in `ps`
+    match header {
+        Some(header) => run_json(ctx, "curl", &["-fsSL", "-H", "@-", &url], Some(&header)),
+        None => run_json(ctx, "curl", &["-fsSL", &url], None),
+    }
+}
+
+/// Run a forge CLI and parse its stdout as JSON.
+pub(crate) fn cli_json(ctx: &Context, program: &str, args: &[&str]) -> Result<Value> {
+    run_json(ctx, program, args, None)
+}
+
+/// `cli_json` with `input` written to the program's stdin.
+fn run_json(ctx: &Context, program: &str, args: &[&str], input: Option<&str>) -> Result<Value> {
+    let spawn_error = |e: std::io::Error| {
+        GwError::new(
+            ErrorKind::General,
+            format!("failed to run {}: {}", program, e),
+        )
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(&ctx.repo_root)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(format!("{}\n", input).as_bytes())
+            .map_err(spawn_error)?;
+    }
+    let out = child.wait_with_output().map_err(spawn_error)?;
+    if !out.status.success() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            format!(
+                "{} {}: {}",
+                program,
+                args.join(" "),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        ));
+    }
+    serde_json::from_slice(&out.stdout).map_err(|e| {
+        GwError::new(
+            ErrorKind::General,
+            format!("unexpected output from {}: {}", program, e),
+        )
+    })
+}
+
+/// Serve `key` from `.gw/cache/` while younger than `[forge] cache_ttl`,
+/// otherwise `fetch` it (paced by `throttled`). When fetching fails (offline,
+/// rate limited) an older cached response is used instead, with a warning.
+pub(crate) fn cached_json(
+    ctx: &Context,
+    key: &str,
+    fetch: impl FnOnce() -> Result<Value>,
+) -> Result<Value> {
+    let path = cache_path(ctx, key);
+    let cached = fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok());
+    let age = fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok());
+    if let (Some(value), Some(age)) = (&cached, age) {
+        if age < Duration::from_secs(ctx.config.forge_cache_ttl()) {
+            return Ok(value.clone());
+        }
+    }
+    match throttled(ctx, fetch) {
+        Ok(value) => {
+            let _ = fs::create_dir_all(path.parent().unwrap_or(&ctx.repo_root))
+                .and_then(|_| fs::write(&path, value.to_string()));
+            Ok(value)
+        }
+        Err(e) => match cached {
+            Some(value) => {
+                eprintln!(
+                    "warning: {}; using the cached response from {}s ago",
+                    e.message,
+                    age.map(|a| a.as_secs()).unwrap_or(0)
+                );
+                Ok(value)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// API pacing shared by every gw process in the repository, kept in
+/// `.gw/cache/rate-limit.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RateLimit {
+    /// When the last call started, in milliseconds since the epoch
+    #[serde(default)]
+    last_call_ms: u64,
+    /// Seconds of the current backoff; doubles while the host keeps refusing
+    #[serde(default)]
+    backoff: u64,
+    /// No calls are made before this time, in seconds since the epoch
+    #[serde(default)]
+    until: u64,
+}
+
+const MIN_BACKOFF_SECS: u64 = 60;
+const MAX_BACKOFF_SECS: u64 = 15 * 60;
+
+/// Run `fetch` at most once per `[forge] min_interval`, and not at all while
+/// backing off after the host answered 403 or 429.
+fn throttled(ctx: &Context, fetch: impl FnOnce() -> Result<Value>) -> Result<Value> {
+    let path = cache_path(ctx, "rate-limit");
+    let mut state: RateLimit = fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    let now = now_ms();
+    if state.until * 1000 > now {
+        return Err(GwError::new(
+            ErrorKind::General,
+            format!(
+                "forge API rate limited; retrying in {}s",
+                (state.until * 1000 - now).div_ceil(1000)
+            ),
+        ));
+    }
+    let wait = (state.last_call_ms + ctx.config.forge_min_interval() * 1000).saturating_sub(now);
+    if wait > 0 {
+        std::thread::sleep(Duration::from_millis(wait));
+    }
+    state.last_call_ms = now_ms();
+    let result = fetch();
+    match &result {
+        Ok(_) => {
+            state.backoff = 0;
+            state.until = 0;
+        }
+        Err(e) if rate_limited(&e.message) => {
+            state.backoff = next_backoff(state.backoff);
+            state.until = state.last_call_ms / 1000 + state.backoff;
+        }
+        Err(_) => {}
+    }
+    if let Ok(raw) = serde_json::to_string(&state) {
+        let _ = fs::create_dir_all(path.parent().unwrap_or(&ctx.repo_root))
+            .and_then(|_| fs::write(&path, raw));
+    }
+    result
+}
+
+fn next_backoff(previous: u64) -> u64 {
+    (previous * 2).clamp(MIN_BACKOFF_SECS, MAX_BACKOFF_SECS)
+}
+
+/// Whether a failed call was refused by the host: curl's `returned error: 429`,
+/// gh's `HTTP 403: API rate limit exceeded`, glab's `429 Too Many Requests`.
+fn rate_limited(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("rate limit")
+        || message.contains("too many requests")
+        || ["error: 403", "error: 429", "http 403", "http 429"]
+            .iter()
+            .any(|code| message.contains(code))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn cache_path(ctx: &Context, key: &str) -> PathBuf {
+    let file: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    ctx.repo_root
+        .join(".gw")
+        .join("cache")
+        .join(format!("{}.json", file))
+}
+
+pub(crate) fn field(json: &Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(json, |value, key| value.get(key))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_remote_handles_ssh_scp_and_https() {
+        let expect = Some(("github.com".to_string(), "acme/api".to_string()));
+        assert_eq!(parse_remote("git@github.com:acme/api.git"), expect);
+        assert_eq!(parse_remote("https://github.com/acme/api.git"), expect);
+        assert_eq!(parse_remote("ssh://git@github.com:22/acme/api"), expect);
+        assert_eq!(
+            parse_remote("https://gitlab.example.com/group/sub/app/"),
+            Some((
+                "gitlab.example.com".to_string(),
+                "group/sub/app".to_string()
+            ))
+        );
+        assert_eq!(parse_remote("/srv/git/api.git"), None);
+    }
+
+    #[test]
+    fn rate_limits_are_detected_and_backed_off() {
+        assert!(rate_limited(
+            "curl -fsSL https://api.github.com/repos/a/b/issues/1: curl: (22) The requested URL returned error: 403"
+        ));
+        assert!(rate_limited("gh issue view 1: HTTP 429: Too Many Requests"));
+        assert!(rate_limited("glab issue view 1: 429 Too Many Requests"));
+        assert!(!rate_limited(
+            "gh issue view 1: Could not resolve to an issue"
+        ));
+        assert!(!rate_limited(
+            "curl: (22) The requested URL returned error: 404"
+        ));
+
+        assert_eq!(next_backoff(0), 60);
+        assert_eq!(next_backoff(60), 120);
+        assert_eq!(next_backoff(600), 900);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Context;
+use std::fs;
+use std::path::Path;
+
+/// Title bar colors handed out by `{color}`; each worktree keeps the same one.
+const PALETTE: &[&str] = &[
+    "#1f6feb", "#8250df", "#bf3989", "#cf222e", "#bc4c00", "#9a6700", "#1a7f37", "#0969da",
+];
+
+/// Render `[open.generate]` templates into a new worktree, so editor windows
+/// of the same repository can be told apart. Files that already exist (e.g.
+/// tracked settings) are left alone, and generated ones are git-excluded.
+/// Like `propagate_files`, problems are reported but never fail `add`.
+pub(crate) fn generate_files(
+    ctx: &Context,
+    name: &str,
+    worktree: &Path,
+    branch: Option<&str>,
+    base: &str,
+) {
+    let templates = ctx.config.open_generate();
+    if templates.is_empty() {
+        return;
+    }
+    let repo = ctx
+        .repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut generated = Vec::new();
+    for (file, template) in templates {
+        let target = worktree.join(&file);
+        if target.exists() {
+            if ctx.verbose {
+                eprintln!("  generate: {} exists, skipped", file);
+            }
+            continue;
+        }
+        let template = match template.strip_prefix('@') {
+            Some(source) => match fs::read_to_string(ctx.repo_root.join(source)) {
+                Ok(text) => text,
+                Err(e) => {
+                    eprintln!("  warn: generate {}: cannot read {}: {}", file, source, e);
+                    continue;
+                }
+            },
+            None => template,
+        };
+        let vars = [
+            ("{name}", name),
+            ("{branch}", branch.unwrap_or("")),
+            ("{base}", base),
+            ("{repo}", repo.as_str()),
+            ("{path}", &worktree.to_string_lossy()),
+            ("{color}", color(name)),
+        ];
+        let text = render(&template, &vars);
+        let written = target
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&target, text));
+        match written {
+            Ok(()) => generated.push(format!("/{}", file.trim_start_matches('/'))),
+            Err(e) => eprintln!("  warn: generate {}: {}", file, e),
+        }
+    }
+    if generated.is_empty() {
+        return;
+    }
+    // Keep generated files from showing up as untracked changes
+    if let Err(e) = ctx.git.add_excludes(&ctx.repo_root, &generated) {
+        eprintln!("  warn: could not exclude generated files: {}", e.trim());
+    }
+    if !ctx.quiet {
+        println!("  generated: {}", generated.join(", "));
+    }
+}
+
+fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(key, value)
+        })
+}
+
+/// Stable palette entry for `name` (FNV-1a, so it survives toolchain upgrades).
+fn color(name: &str) -> &'static str {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    PALETTE[(hash % PALETTE.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_fills_placeholders_and_keeps_json_braces() {
+        let out = render(
+            r#"{"window.title": "{name} ({branch})", "color": "{color}"}"#,
+            &[
+                ("{name}", "api"),
+                ("{branch}", "wt/api"),
+                ("{color}", color("api")),
+            ],
+        );
+        assert_eq!(
+            out,
+            format!(
+                r#"{{"window.title": "api (wt/api)", "color": "{}"}}"#,
+                color("api")
+            )
+        );
+        assert_eq!(color("api"), color("api"));
+    }
+}
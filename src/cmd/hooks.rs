@@ -0,0 +1,78 @@
+use crate::git::Worktree;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::process::{Command, Stdio};
+
+/// Run the `[hooks]` command configured for `hook` (e.g. `post_add`) inside a
+/// worktree. Its output goes to stderr so gw's own stdout stays parseable;
+/// a failing hook is an `ErrorKind::Hook` error (exit 11).
+pub(crate) fn run_hook(ctx: &Context, hook: &str, name: &str, wt: &Worktree) -> Result<()> {
+    let Some(cmd) = ctx.config.hook(hook) else {
+        return Ok(());
+    };
+    run_in_worktree(ctx, hook, &cmd, name, wt)
+}
+
+/// Run a `[template.<name>]` bootstrap command in a freshly added worktree,
+/// the same way as a hook (and skipped by `--no-hooks` too).
+pub(crate) fn run_bootstrap(ctx: &Context, cmd: &str, name: &str, wt: &Worktree) -> Result<()> {
+    if cmd.trim().is_empty() {
+        return Ok(());
+    }
+    run_in_worktree(ctx, "bootstrap", cmd, name, wt)
+}
+
+fn run_in_worktree(ctx: &Context, hook: &str, cmd: &str, name: &str, wt: &Worktree) -> Result<()> {
+    if ctx.no_hooks {
+        if ctx.verbose {
+            eprintln!("{}: skipped (--no-hooks)", hook);
+        }
+        return Ok(());
+    }
+    let mut envs = super::env::worktree_env(ctx, name, wt);
+    envs.extend([
+        ("GW_NAME".to_string(), name.to_string()),
+        ("GW_PATH".to_string(), wt.path.to_string_lossy().to_string()),
+        (
+            "GW_BRANCH".to_string(),
+            wt.branch
+                .as_deref()
+                .map(super::short_branch)
+                .unwrap_or_default(),
+        ),
+        ("GW_HOOK".to_string(), hook.to_string()),
+    ]);
+    if ctx.verbose {
+        eprintln!("{}: {}", hook, cmd);
+    }
+
+    let shell = ctx.config.exec_shell();
+    let (program, shell_args) = shell
+        .split_first()
+        .ok_or_else(|| GwError::new(ErrorKind::Config, "empty [exec] shell"))?;
+    let status = Command::new(program)
+        .args(shell_args)
+        .arg(cmd)
+        .current_dir(&wt.path)
+        .envs(envs)
+        .stdout(Stdio::from(std::io::stderr()))
+        .status()
+        .map_err(|e| {
+            GwError::new(
+                ErrorKind::Hook,
+                format!("{} hook failed to start: {}: {}", hook, program, e),
+            )
+        })?;
+    if !status.success() {
+        return Err(GwError::new(
+            ErrorKind::Hook,
+            format!(
+                "{} hook failed for '{}' (exit {}): {}",
+                hook,
+                name,
+                status.code().unwrap_or(1),
+                cmd
+            ),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use super::forge::{self, Forge};
+use crate::cli::BrowseArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::process::Command;
+
+/// An issue a worktree was created for.
+pub(crate) struct Issue {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+impl Issue {
+    /// Worktree name for the issue: its id followed by the slugified title.
+    pub fn name(&self) -> String {
+        crate::names::slugify(&format!("{} {}", self.id, self.title))
+    }
+}
+
+/// Look `id` up with the `[issue] provider`: `jira` (jira-cli) or one of
+/// the forge providers, which default to `[forge] provider`.
+pub(crate) fn fetch_issue(ctx: &Context, id: &str) -> Result<Issue> {
+    let id = id.trim().trim_start_matches('#').to_string();
+    // The id ends up in CLI arguments, REST paths and cache file names
+    if id.is_empty() || id.starts_with('-') || id.contains(['/', '\\']) {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!("invalid issue id '{}'", id),
+        ));
+    }
+    let provider = ctx.config.issue_provider();
+    let (source, title, url) = if provider.as_deref() == Some("jira") {
+        let json = forge::cached_json(ctx, &format!("jira-issue-{}", id), || {
+            forge::cli_json(ctx, "jira", &["issue", "view", &id, "--raw"])
+        })?;
+        let base = ctx.config.issue_jira_url().ok_or_else(|| {
+            GwError::new(
+                ErrorKind::Config,
+                "[issue] jira_url is required for the jira provider",
+            )
+        })?;
+        let url = format!("{}/browse/{}", base.trim_end_matches('/'), id);
+        let title = forge::field(&json, &["fields", "summary"]).unwrap_or_default();
+        ("jira", title, url)
+    } else {
+        let forge = Forge::detect(ctx, provider.as_deref())?;
+        let issue = forge.issue(ctx, &id)?;
+        (forge.name(), issue.title, issue.url)
+    };
+    if title.trim().is_empty() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            format!("{} returned no title for issue {}", source, id),
+        ));
+    }
+    Ok(Issue { id, title, url })
+}
+
+/// Open the issue recorded for a worktree (the current one by default).
+pub fn browse(ctx: &Context, args: BrowseArgs) -> Result<()> {
+    let name = match args.name {
+        Some(name) => super::recent::resolve_ref(ctx, &name)?,
+        None => {
+            let current = ctx.git.current_toplevel().map_err(git_error)?;
+            super::worktree_name_with_config(ctx, &current).ok_or_else(|| {
+                GwError::new(
+                    ErrorKind::NotFound,
+                    format!("not inside a gw worktree: {}", current.display()),
+                )
+            })?
+        }
+    };
+    let url = ctx
+        .meta
+        .get(&name)
+        .and_then(|m| m.issue_url.clone())
+        .ok_or_else(|| {
+            GwError::new(
+                ErrorKind::NotFound,
+                format!(
+                    "no issue recorded for '{}' (create it with gw add --from-issue)",
+                    name
+                ),
+            )
+        })?;
+    if args.print {
+        println!("{}", url);
+        return Ok(());
+    }
+    let (program, extra): (&str, &[&str]) = if cfg!(windows) {
+        ("cmd", &["/C", "start", ""])
+    } else if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else {
+        ("xdg-open", &[])
+    };
+    let status = Command::new(program).args(extra).arg(&url).status();
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        _ => {
+            // No browser to hand it to (ssh session, container): show it instead
+            println!("{}", url);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_name_and_nested_fields() {
+        let issue = Issue {
+            id: "512".into(),
+            title: "Fix: payment retry (again)".into(),
+            url: String::new(),
+        };
+        assert_eq!(issue.name(), "512-fix-payment-retry-again");
+        let json = serde_json::json!({ "fields": { "summary": "Add SSO" } });
+        assert_eq!(
+            forge::field(&json, &["fields", "summary"]).as_deref(),
+            Some("Add SSO")
+        );
+        assert_eq!(forge::field(&json, &["title"]), None);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cli::LogArgs;
+use crate::git::git_error;
+use crate::{Context, Result};
+
+pub fn log(ctx: &Context, args: LogArgs) -> Result<()> {
+    if args.applied {
+        return applied(ctx, args.name.as_deref());
+    }
+    let name = args.name.unwrap_or_default();
+    let wt = super::require_worktree(ctx, &name)?;
+    let branch = wt
+        .branch
+        .as_ref()
+        .map(|b| super::short_branch(b))
+        .unwrap_or_else(|| "HEAD".to_string());
+    let base = super::worktree_base(ctx, &name)?;
+    let range = format!("{}..{}", base, branch);
+
+    let mut cmd_args = vec!["log"];
+    if args.patch {
+        cmd_args.push("--patch");
+    }
+    if args.stat {
+        cmd_args.push("--stat");
+    }
+    if args.oneline {
+        cmd_args.push("--oneline");
+    }
+    cmd_args.push(&range);
+
+    let ok = ctx
+        .git
+        .run_passthrough(&wt.path, &cmd_args)
+        .map_err(git_error)?;
+    if !ok {
+        return Err(git_error(format!("git log {} failed", range)));
+    }
+    Ok(())
+}
+
+/// Commits on the current branch carrying a `Gw-Worktree` trailer, newest first.
+fn applied(ctx: &Context, name: Option<&str>) -> Result<()> {
+    let format = format!(
+        "--format=%h%x1f%cI%x1f%(trailers:key={},valueonly,separator=%x2C)%x1f%(trailers:key={},valueonly,separator=%x2C)%x1f%s%x1e",
+        super::WORKTREE_TRAILER,
+        super::BASE_TRAILER
+    );
+    let grep = format!("--grep=^{}: ", super::WORKTREE_TRAILER);
+    let out = ctx
+        .git
+        .run_in(&ctx.repo_root, &["log", &format, &grep])
+        .map_err(git_error)?;
+
+    let entries: Vec<Vec<&str>> = out
+        .split('\x1e')
+        .map(|record| record.trim_matches('\n').split('\x1f').collect::<Vec<_>>())
+        .filter(|fields| fields.len() == 5 && !fields[2].is_empty())
+        .filter(|fields| name.is_none_or(|n| fields[2] == n))
+        .collect();
+
+    if ctx.json {
+        let out: Vec<_> = entries
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "commit": f[0],
+                    "date": f[1],
+                    "worktree": f[2],
+                    "base": f[3],
+                    "subject": f[4],
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        if !ctx.quiet {
+            println!("log: no applied commits");
+        }
+        return Ok(());
+    }
+    let headers = vec![
+        "COMMIT".to_string(),
+        "WORKTREE".to_string(),
+        "BASE".to_string(),
+        "SUBJECT".to_string(),
+    ];
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .map(|f| {
+            vec![
+                f[0].to_string(),
+                f[2].to_string(),
+                f[3].to_string(),
+                f[4].to_string(),
+            ]
+        })
+        .collect();
+    super::print_table(&headers, &rows);
+    Ok(())
+}
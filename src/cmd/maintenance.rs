@@ -0,0 +1,86 @@
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+const COUNTER_FILE: &str = "auto-gc-count";
+
+/// With `[gc] auto = true`, run light maintenance every `auto_interval`
+/// invocations. Like `reap_ephemeral`, failures are reported but never fatal.
+pub fn auto_maintenance(ctx: &mut Context) {
+    if !ctx.config.gc_auto() {
+        return;
+    }
+    let path = ctx.repo_root.join(".gw").join(COUNTER_FILE);
+    let count = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .unwrap_or(0)
+        + 1;
+    let due = count >= ctx.config.gc_auto_interval();
+    let next = if due { 0 } else { count };
+    if fs::create_dir_all(ctx.repo_root.join(".gw")).is_err()
+        || fs::write(&path, next.to_string()).is_err()
+    {
+        return;
+    }
+    if !due {
+        return;
+    }
+    if let Err(err) = maintain(ctx) {
+        if ctx.verbose {
+            eprintln!("warn: auto maintenance failed: {}", err.message);
+        }
+    }
+}
+
+fn maintain(ctx: &mut Context) -> Result<()> {
+    ctx.git.run(&["worktree", "prune"]).map_err(git_error)?;
+
+    // Drop metadata for worktrees whose directory is gone. Entries for names
+    // that were never added (`gw note`/`assign` ahead of `gw add`) stay.
+    let mut live: HashSet<String> = ctx
+        .worktrees()?
+        .iter()
+        .filter_map(|wt| super::worktree_name_with_config(ctx, &wt.path))
+        .collect();
+    live.insert("root".to_string());
+    let orphans: Vec<String> = ctx
+        .meta
+        .all()
+        .iter()
+        .filter(|(name, _)| !live.contains(*name))
+        .filter(|(name, entry)| match entry.path.as_deref() {
+            Some(path) => !Path::new(path).exists(),
+            // Recorded before `gw add` stored the path: its default location
+            None => {
+                entry.created_at.is_some()
+                    && !ctx
+                        .repo_root
+                        .join(ctx.config.worktrees_dir())
+                        .join(name)
+                        .exists()
+                    && !super::tmp_worktrees_dir(ctx).join(name).exists()
+            }
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    if orphans.is_empty() {
+        return Ok(());
+    }
+    let mut meta = ctx.meta.clone();
+    for name in &orphans {
+        meta.remove(name);
+    }
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    ctx.meta = meta;
+    if ctx.verbose {
+        eprintln!(
+            "auto maintenance: removed metadata for {}",
+            orphans.join(", ")
+        );
+    }
+    Ok(())
+}
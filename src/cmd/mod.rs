@@ -1,34 +1,165 @@
+mod archive;
+mod backup;
+mod changed;
+mod code;
+mod conflicts;
+mod copy;
+mod daemon;
+mod disk;
+mod env;
+mod ephemeral;
+mod exclude;
 mod exec;
+mod forge;
+mod generate;
+mod hooks;
+mod issue;
+mod log;
+mod maintenance;
+mod operation;
+mod passthrough;
+mod patch;
+mod plugin;
+mod recent;
+mod rename;
+mod review;
+mod scan;
+mod search;
+mod serve;
+mod share;
+mod stash;
+mod stats;
+mod status;
+mod summary;
+mod switch;
+mod train;
+mod transfer;
+mod watch;
+mod workspace;
 
 use crate::cli::{
-    AddArgs, ApplyArgs, CdArgs, ConfigArgs, DelArgs, GcArgs, InfoArgs, ListArgs, LockArgs,
-    NoteArgs, StatusArgs, SubdirArgs, SyncArgs, UnlockArgs, VerifyArgs,
+    AddArgs, ApplyArgs, AssignArgs, CdArgs, ConfigArgs, DelArgs, GcArgs, InfoArgs, ListArgs,
+    LockArgs, MetaAction, MetaArgs, NoteArgs, OwnerFilterArgs, RootArgs, SubdirArgs, SyncArgs,
+    TouchArgs, UnlockArgs, VerifyArgs,
 };
-use crate::{Context, GwError, Result};
 use crate::git::{git_error, Worktree};
+use crate::{Context, ErrorKind, GwError, Result};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+pub use backup::{backup, restore};
+pub use code::code;
+pub use conflicts::conflicts;
+pub use copy::copy;
+pub use daemon::daemon;
+pub use env::env;
+pub use ephemeral::reap_ephemeral;
 pub use exec::exec_cmd;
-
-pub fn add(ctx: &Context, args: AddArgs) -> Result<()> {
+pub use issue::browse;
+pub use log::log;
+pub use maintenance::auto_maintenance;
+pub use passthrough::git_cmd;
+pub use plugin::plugin;
+pub use recent::recent;
+pub use rename::rename;
+pub use review::review;
+pub use scan::scan;
+pub use search::search;
+pub use serve::serve;
+pub use stash::stash;
+pub use stats::stats;
+pub use status::status;
+pub use summary::summary;
+pub use switch::switch;
+pub use train::train;
+pub use transfer::transfer;
+pub use watch::watch;
+pub use workspace::{workspace, workspace_repo};
+
+pub fn add(ctx: &Context, mut args: AddArgs) -> Result<()> {
     let worktrees_dir = ctx.repo_root.join(ctx.config.worktrees_dir());
-    let name = args.name;
-    let path = args
-        .path
-        .map(PathBuf::from)
-        .unwrap_or_else(|| worktrees_dir.join(&name));
-
-    if path.exists() {
-        return Err(GwError::new(1, "worktree path already exists"));
+    let template = match args.template.as_deref() {
+        Some(name) => Some(ctx.config.template(name).cloned().ok_or_else(|| {
+            let known = ctx.config.template_names();
+            GwError::new(
+                ErrorKind::InvalidInput,
+                if known.is_empty() {
+                    format!(
+                        "unknown template '{}' (no [template.<name>] sections configured)",
+                        name
+                    )
+                } else {
+                    format!("unknown template '{}' (known: {})", name, known.join(", "))
+                },
+            )
+        })?),
+        None => None,
+    };
+    // Explicit flags win over the template; tags and copy globs add up
+    if let Some(ref template) = template {
+        args.base = args.base.or_else(|| template.base.clone());
+        args.subdir = args.subdir.or_else(|| template.subdir.clone());
+        for tag in &template.tags {
+            if !args.tags.contains(tag) {
+                args.tags.push(tag.clone());
+            }
+        }
+        args.copy.extend(template.copy_files.iter().cloned());
+    }
+    let branch_prefix = template
+        .as_ref()
+        .and_then(|t| t.branch_prefix.clone())
+        .unwrap_or_else(|| ctx.config.branch_prefix());
+    let issue = args
+        .from_issue
+        .as_deref()
+        .map(|id| issue::fetch_issue(ctx, id))
+        .transpose()?;
+    let name = match (
+        args.name.or_else(|| issue.as_ref().map(issue::Issue::name)),
+        args.branch.as_deref(),
+    ) {
+        (Some(name), _) if args.slug => {
+            let slug = crate::names::slugify(&name);
+            if slug.is_empty() {
+                return Err(GwError::new(
+                    ErrorKind::InvalidInput,
+                    format!("cannot derive a worktree name from '{}'", name),
+                ));
+            }
+            slug
+        }
+        (Some(name), _) => name,
+        (None, Some(branch)) => {
+            let derived = crate::names::name_from_branch(
+                branch,
+                &branch_prefix,
+                &ctx.config.branch_name_template(),
+            );
+            if derived.is_empty() {
+                return Err(GwError::new(
+                    ErrorKind::InvalidInput,
+                    format!("cannot derive a worktree name from branch '{}'", branch),
+                ));
+            }
+            derived
+        }
+        (None, None) => {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                "worktree name or --branch is required",
+            ))
+        }
+    };
+    let fetched = args.fetch || ctx.config.fetch_before_resolve();
+    if fetched {
+        refresh_origin(ctx)?;
     }
-
-    let branch = args
-        .branch
-        .unwrap_or_else(|| format!("{}{}", ctx.config.branch_prefix(), name));
     let base = if let Some(base) = args.base {
         base
     } else {
@@ -36,30 +167,134 @@ pub fn add(ctx: &Context, args: AddArgs) -> Result<()> {
             .resolve_base(&ctx.repo_root, ctx.config.default_base())
             .map_err(git_error)?
     };
+    // A fetch never moves the local base branch, so start from what was fetched
+    let fetched_start = fetched
+        .then(|| ctx.git.fetched_start_point(&base))
+        .flatten();
+    let start_point = fetched_start.clone().unwrap_or_else(|| base.clone());
+    let name = match ctx.config.name_template() {
+        Some(template) => crate::names::render_name_template(
+            &template,
+            &name,
+            &chrono::Local::now().format("%Y-%m-%d").to_string(),
+            &crate::meta::current_user(),
+            &base,
+        ),
+        None => name,
+    };
+    crate::names::validate_name(&name)?;
+    if let Some(pattern) = args.copy.iter().find(|p| !is_inside_pattern(p)) {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!("--copy '{}' must be relative to the repo root", pattern),
+        ));
+    }
+    let path = match args.path {
+        Some(path) => PathBuf::from(path),
+        None if args.tmp => tmp_worktrees_dir(ctx).join(&name),
+        None => worktrees_dir.join(&name),
+    };
+    // Scratch worktrees share the namespace of the regular ones
+    if args.tmp && find_worktree(ctx, &name)?.is_some() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!("worktree already exists: {}", name),
+        ));
+    }
+
+    check_add_target(ctx, &path, &worktrees_dir)?;
+    if path.exists() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            "worktree path already exists",
+        ));
+    }
+
+    let ttl = args.ttl.as_deref().map(ephemeral::parse_ttl).transpose()?;
+
+    let branch = args
+        .branch
+        .unwrap_or_else(|| format!("{}{}", branch_prefix, name));
 
     let mut cmd_args = vec!["worktree", "add"];
     let path_str = path.to_string_lossy().to_string();
-    if ctx.git.branch_exists(&branch) {
+    let mut released = None;
+    if !args.detach && ctx.git.branch_exists(&branch) {
+        if let Some(holder) = branch_holder(ctx, &branch)? {
+            if args.move_branch {
+                released = release_branch(ctx, &holder, &branch)?;
+            } else if args.force {
+                cmd_args.push("--force");
+            } else {
+                return Err(GwError::new(
+                    ErrorKind::AlreadyExists,
+                    format!(
+                        "branch '{}' is already checked out in {} ({}); pass --force to check it out twice or --move to detach it there",
+                        branch,
+                        holder_label(ctx, &holder),
+                        holder.path.display()
+                    ),
+                ));
+            }
+        }
+    }
+    if args.detach {
+        cmd_args.push("--detach");
+        cmd_args.push(&path_str);
+        cmd_args.push(&start_point);
+    } else if ctx.git.branch_exists(&branch) {
         cmd_args.push(&path_str);
         cmd_args.push(&branch);
     } else {
+        if fetched_start.is_some() {
+            // Don't make origin/<base> the new branch's upstream
+            cmd_args.push("--no-track");
+        }
         cmd_args.push("-b");
         cmd_args.push(&branch);
         cmd_args.push(&path_str);
-        cmd_args.push(&base);
+        cmd_args.push(&start_point);
     }
 
     ctx.git.run(&cmd_args).map_err(git_error)?;
 
     let mut meta = ctx.meta.clone();
+    if let Some(ref holder) = released {
+        meta.ensure(holder).detached = true;
+    }
     meta.set_created(&name);
+    meta.ensure(&name).path = Some(resolve_lenient(&path).to_string_lossy().to_string());
+    meta.set_base(&name, Some(base.clone()));
+    if args.detach {
+        meta.ensure(&name).detached = true;
+    } else {
+        meta.ensure(&name).branch = Some(branch.clone());
+    }
+    meta.ensure(&name).base_commit = ctx
+        .git
+        .run_in(&path, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|out| out.trim().to_string());
+    if args.ephemeral || args.tmp || ttl.is_some() {
+        meta.set_ephemeral(&name, ttl);
+    }
+    if !args.tags.is_empty() {
+        meta.ensure(&name).tags = args.tags;
+    }
+    if let Some(ref issue) = issue {
+        meta.ensure(&name).issue_url = Some(issue.url.clone()).filter(|url| !url.is_empty());
+    }
     if let Some(ref subdir) = args.subdir {
+        let subdir = ctx.config.expand_subdir(subdir);
         meta.set_subdir(&name, Some(subdir.trim_start_matches('/').to_string()));
     }
-    meta.save().map_err(|e| GwError::new(1, e.to_string()))?;
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
 
     if !ctx.quiet {
-        if let Some(ref subdir) = args.subdir {
+        if args.detach {
+            println!("created: {} (detached at {})", path.display(), base);
+        } else if let Some(ref subdir) = args.subdir {
             println!(
                 "created: {} (branch: {}, base: {}, subdir: {})",
                 path.display(),
@@ -77,14 +312,90 @@ pub fn add(ctx: &Context, args: AddArgs) -> Result<()> {
         }
     }
 
-    propagate_files(ctx, &path);
+    exclude::ensure_excluded(ctx);
+    propagate_files(ctx, &path, &args.copy);
+    let branch = (!args.detach).then_some(branch.as_str());
+    generate::generate_files(ctx, &name, &path, branch, &base);
+
+    let wt = require_worktree(ctx, &name)?;
+    if let Some(cmd) = template.and_then(|t| t.bootstrap) {
+        hooks::run_bootstrap(ctx, &cmd, &name, &wt)?;
+    }
+    hooks::run_hook(ctx, "post_add", &name, &wt)?;
+    Ok(())
+}
+
+/// The worktree that has `branch` checked out, if any.
+fn branch_holder(ctx: &Context, branch: &str) -> Result<Option<Worktree>> {
+    Ok(ctx
+        .worktrees()?
+        .into_iter()
+        .find(|wt| wt.branch.as_deref().map(short_branch).as_deref() == Some(branch)))
+}
+
+fn holder_label(ctx: &Context, wt: &Worktree) -> String {
+    let root = crate::paths::canonical(&ctx.repo_root);
+    match worktree_display_name(ctx, &wt.path, &root).as_str() {
+        "root" => "the repo root".to_string(),
+        name => format!("worktree '{}'", name),
+    }
+}
+
+/// `add --move`: detach `holder` at its current commit so `branch` is free.
+/// Uncommitted changes stay where they are. Returns the holder's gw name so
+/// its metadata can be marked detached.
+fn release_branch(ctx: &Context, holder: &Worktree, branch: &str) -> Result<Option<String>> {
+    let name = worktree_name_with_config(ctx, &holder.path);
+    if let Some(ref name) = name {
+        if is_locked(&ctx.repo_root, name) {
+            return Err(GwError::new(
+                ErrorKind::Locked,
+                locked_message(&ctx.repo_root, name),
+            ));
+        }
+    }
+    ctx.git
+        .run_in(&holder.path, &["checkout", "--detach"])
+        .map_err(git_error)?;
+    if !ctx.quiet {
+        eprintln!(
+            "moved: {} from {} (now detached)",
+            branch,
+            holder_label(ctx, holder)
+        );
+    }
+    Ok(name)
+}
+
+/// `--fetch` for read-only commands: a failed fetch is reported but not fatal.
+pub(crate) fn fetch_remotes(ctx: &Context) {
+    if let Err(err) = ctx.git.fetch_all() {
+        eprintln!("warning: fetch failed: {}", err.trim());
+    }
+}
 
+/// Fetch origin and refresh origin/HEAD, warning when the remote's default branch changed.
+fn refresh_origin(ctx: &Context) -> Result<()> {
+    if let Some((old, new)) = ctx.git.refresh_origin().map_err(git_error)? {
+        eprintln!(
+            "warning: origin's default branch changed: {} -> {} (set [defaults] base to pin it)",
+            old, new
+        );
+    }
     Ok(())
 }
 
-/// Propagate untracked files to a new worktree via copy (.worktreeinclude) and symlink ([worktree] link).
-fn propagate_files(ctx: &Context, worktree_path: &Path) {
-    let include_patterns = parse_worktreeinclude(&ctx.repo_root);
+/// Copy `.worktreeinclude`, `[defaults] copy_files` and `extra_copy` matches and
+/// symlink `[worktree] link` matches from the repo root into a new worktree.
+fn propagate_files(ctx: &Context, worktree_path: &Path, extra_copy: &[String]) {
+    let mut include_patterns = parse_worktreeinclude(&ctx.repo_root);
+    include_patterns.extend(
+        ctx.config
+            .copy_files()
+            .into_iter()
+            .filter(|p| is_inside_pattern(p)),
+    );
+    include_patterns.extend(extra_copy.iter().cloned());
     let link_patterns = ctx.config.worktree_link_patterns();
 
     if include_patterns.is_empty() && link_patterns.is_empty() {
@@ -99,12 +410,12 @@ fn propagate_files(ctx: &Context, worktree_path: &Path) {
     let mut copied = 0usize;
     let mut linked = 0usize;
 
-    // Copy files from .worktreeinclude (skip if also in link set)
+    // Copy the .worktreeinclude, copy_files and --copy matches; linking wins over copying
     for rel in &include_files {
         if link_set.contains(rel) {
             if !ctx.quiet {
                 eprintln!(
-                    "  warn: {} matched both .worktreeinclude and [worktree] link, using symlink",
+                    "  warn: {} matched both a copy pattern and [worktree] link, using symlink",
                     rel.display()
                 );
             }
@@ -134,6 +445,93 @@ fn propagate_files(ctx: &Context, worktree_path: &Path) {
     }
 }
 
+/// Whether a glob stays below the directory it is resolved against.
+fn is_inside_pattern(pattern: &str) -> bool {
+    !Path::new(pattern).is_absolute() && !pattern.split(['/', '\\']).any(|part| part == "..")
+}
+
+/// Catch target paths git accepts but that break later: a worktree nested in
+/// another worktree, a name that only differs in case from an existing one on
+/// a case-insensitive filesystem, and a worktrees_dir with tracked files.
+fn check_add_target(ctx: &Context, path: &Path, worktrees_dir: &Path) -> Result<()> {
+    let target = resolve_lenient(path);
+    let worktrees = ctx.worktrees()?;
+    for wt in &worktrees {
+        if crate::paths::same_path(&wt.path, &ctx.repo_root) {
+            continue;
+        }
+        if crate::paths::relative_to(&target, &wt.path).is_some() {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is inside worktree {}; nested worktrees confuse git, pick a path outside it with --path",
+                    path.display(),
+                    wt.path.display()
+                ),
+            ));
+        }
+    }
+
+    if ctx
+        .git
+        .run(&["config", "--bool", "core.ignorecase"])
+        .is_ok_and(|v| v.trim() == "true")
+    {
+        let file_name = target.file_name().map(|n| n.to_string_lossy().to_string());
+        let clash = worktrees.iter().find(|wt| {
+            let existing = wt.path.file_name().map(|n| n.to_string_lossy().to_string());
+            match (&existing, &file_name) {
+                (Some(existing), Some(new)) => {
+                    existing != new && existing.eq_ignore_ascii_case(new)
+                }
+                _ => false,
+            }
+        });
+        if let Some(wt) = clash {
+            return Err(GwError::new(
+                ErrorKind::AlreadyExists,
+                format!(
+                    "{} only differs in case from worktree {} and this filesystem ignores case; choose another name",
+                    path.display(),
+                    wt.path.display()
+                ),
+            ));
+        }
+    }
+
+    if let Some(rel) = crate::paths::relative_to(&resolve_lenient(worktrees_dir), &ctx.repo_root) {
+        let tracked = ctx
+            .git
+            .run_in(&ctx.repo_root, &["ls-files", "--", &rel])
+            .unwrap_or_default();
+        if !tracked.trim().is_empty() {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "worktrees_dir '{}' contains files tracked by git; untrack them with `git rm -r --cached {}` and ignore the directory, or set [defaults] worktrees_dir elsewhere",
+                    rel, rel
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Absolute, canonical form of a path that may not exist yet: the deepest
+/// existing ancestor is canonicalized and the rest appended.
+fn resolve_lenient(path: &Path) -> PathBuf {
+    let path = std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    for ancestor in path.ancestors() {
+        if ancestor.exists() {
+            let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return crate::paths::canonical(ancestor).join(rest);
+        }
+    }
+    path
+}
+
 /// Parse .worktreeinclude file (gitignore-style patterns, one per line).
 fn parse_worktreeinclude(repo_root: &Path) -> Vec<String> {
     let path = repo_root.join(".worktreeinclude");
@@ -275,24 +673,64 @@ fn symlink_file(repo_root: &Path, worktree: &Path, rel: &Path, verbose: bool) ->
 pub fn del(ctx: &Context, args: DelArgs) -> Result<()> {
     let name = args.name;
     if is_locked(&ctx.repo_root, &name) {
-        return Err(GwError::new(1, "worktree is locked"));
+        return Err(GwError::new(
+            ErrorKind::Locked,
+            locked_message(&ctx.repo_root, &name),
+        ));
     }
 
-    let worktree = find_worktree(ctx, &name)?;
-    if worktree.is_none() {
-        return Err(GwError::new(1, "worktree not found"));
-    }
-    let worktree = worktree.unwrap();
+    let worktree = require_worktree(ctx, &name)?;
 
-    if !args.force {
+    let mut force = args.force;
+    if !force {
         let dirty = dirty_files(&ctx.git, &worktree.path).map_err(git_error)?;
         if dirty.total > 0 {
-            return Err(GwError::new(1, "worktree is dirty (use --force)"));
+            if !can_confirm(ctx) {
+                return Err(GwError::new(
+                    ErrorKind::Dirty,
+                    "worktree is dirty (use --force)",
+                ));
+            }
+            let summary = format!(
+                "'{}' has {} uncommitted change(s) that will be lost; delete it anyway?",
+                name, dirty.total
+            );
+            if !confirm(ctx, &summary) {
+                return Err(cancelled());
+            }
+            force = true;
+        }
+    }
+
+    if !args.force_unpushed {
+        let unpushed = unpushed_commits(&ctx.git, &worktree.path);
+        if !unpushed.is_empty() {
+            let mut msg = format!(
+                "worktree has {} commit(s) not on any remote (use --force-unpushed):",
+                unpushed.len()
+            );
+            for line in unpushed.iter().take(10) {
+                msg.push_str("\n  ");
+                msg.push_str(line);
+            }
+            if unpushed.len() > 10 {
+                msg.push_str(&format!("\n  ... and {} more", unpushed.len() - 10));
+            }
+            if !can_confirm(ctx) {
+                return Err(GwError::new(ErrorKind::Dirty, msg));
+            }
+            eprintln!("{}", msg);
+            if !confirm(ctx, &format!("delete '{}' anyway?", name)) {
+                return Err(cancelled());
+            }
         }
     }
 
+    // Teardown runs while the worktree still exists; a failure keeps it
+    hooks::run_hook(ctx, "pre_del", &name, &worktree)?;
+
     let mut cmd_args = vec!["worktree", "remove"];
-    if args.force {
+    if force {
         cmd_args.push("--force");
     }
     let worktree_path = worktree.path.to_string_lossy().to_string();
@@ -302,149 +740,242 @@ pub fn del(ctx: &Context, args: DelArgs) -> Result<()> {
     if args.delete_branch {
         if let Some(branch) = worktree.branch {
             let branch = branch.trim_start_matches("refs/heads/");
+            archive::archive_branch(ctx, &name, branch)?;
             let _ = ctx.git.run(&["branch", "-D", branch]);
         }
     }
 
     let mut meta = ctx.meta.clone();
     meta.remove(&name);
-    meta.save().map_err(|e| GwError::new(1, e.to_string()))?;
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
 
     Ok(())
 }
 
-pub fn list(ctx: &Context, _args: ListArgs) -> Result<()> {
-    let worktrees = ctx.git.worktrees().map_err(git_error)?;
-    let root = ctx
-        .repo_root
-        .canonicalize()
-        .unwrap_or_else(|_| ctx.repo_root.clone());
-    let current = ctx
-        .git
-        .current_toplevel()
-        .map_err(git_error)?
-        .canonicalize()
-        .unwrap_or_else(|_| root.clone());
-    println!("CUR NAME     BRANCH     PATH");
+pub fn list(ctx: &Context, args: ListArgs) -> Result<()> {
+    if args.fetch {
+        fetch_remotes(ctx);
+    }
+    let worktrees = ctx.worktrees()?;
+    let root = crate::paths::canonical(&ctx.repo_root);
+    let current = ctx.git.current_toplevel().map_err(git_error)?;
+    let show_owner = has_owners(ctx);
+    let show_creator = has_other_creators(ctx);
+    let mut lines = Vec::new();
+    let mut broken = Vec::new();
+    let mut items = Vec::new();
     for wt in worktrees {
         let name = worktree_display_name(ctx, &wt.path, &root);
+        if !owner_matches(ctx, &name, &args.filter) {
+            continue;
+        }
         let branch = wt
             .branch
             .as_ref()
             .map(|b| short_branch(b))
             .unwrap_or_default();
         let path = wt.path.to_string_lossy().to_string();
-        let is_current = wt
-            .path
-            .canonicalize()
-            .map(|p| p == current)
-            .unwrap_or(false);
+        let is_current = crate::paths::same_path(&wt.path, &current);
+        let is_broken = wt.is_broken();
+        if args.porcelain {
+            porcelain_line(&[
+                &name,
+                &branch,
+                &path,
+                if is_current { "1" } else { "0" },
+                worktree_owner(ctx, &name).unwrap_or(""),
+                if is_broken { "1" } else { "0" },
+                worktree_creator(ctx, &name).unwrap_or(""),
+            ]);
+            continue;
+        }
+        if ctx.json {
+            // Locked either with `gw lock` or with `git worktree lock`
+            let gw_locked = name != "root" && is_locked(&ctx.repo_root, &name);
+            items.push(serde_json::json!({
+                "name": name,
+                "branch": wt.branch.as_deref().map(short_branch),
+                "head": wt.head,
+                "path": path,
+                "is_current": is_current,
+                "locked": gw_locked || wt.locked,
+                "lock_reason": if gw_locked { lock_reason(&ctx.repo_root, &name) } else { None },
+                "broken": is_broken,
+                "owner": worktree_owner(ctx, &name),
+                "created_by": worktree_creator(ctx, &name),
+            }));
+            continue;
+        }
+        if is_broken {
+            broken.push(wt.locked);
+        }
         let mark = if is_current { "*" } else { " " };
-        println!("{}  {:<8} {:<10} {}", mark, name, branch, path);
+        let path = display_path(ctx, &wt.path);
+        let mut line = format!("{}  {:<8} {:<10} ", mark, name, branch);
+        if show_owner {
+            let owner = worktree_owner(ctx, &name).unwrap_or("-");
+            line.push_str(&format!("{:<10} ", owner));
+        }
+        if show_creator {
+            let creator = worktree_creator(ctx, &name).unwrap_or("-");
+            line.push_str(&format!("{:<10} ", creator));
+        }
+        line.push_str(&path);
+        if is_broken {
+            line.push_str("  BROKEN");
+        }
+        lines.push((name, line));
+    }
+    if args.porcelain {
+        return Ok(());
+    }
+    if ctx.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&items).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    let mut header = "CUR NAME     BRANCH     ".to_string();
+    if show_owner {
+        header.push_str("OWNER      ");
+    }
+    if show_creator {
+        header.push_str("CREATED BY ");
+    }
+    header.push_str("PATH");
+    println!("{}", header);
+    match args.group_by.as_deref() {
+        Some(group_by) => {
+            for (group, members) in group_lines(ctx, group_by, &lines) {
+                println!();
+                println!("[{}]", group);
+                for line in members {
+                    println!("{}", line);
+                }
+            }
+        }
+        None => {
+            for (_, line) in &lines {
+                println!("{}", line);
+            }
+        }
+    }
+    broken_hint(broken.len(), broken.iter().any(|locked| *locked));
+    let unmanaged = scan::unmanaged_worktrees(ctx)?.len();
+    if unmanaged > 0 {
+        eprintln!(
+            "hint: {} worktree(s) created outside gw; run `gw scan` to register them by name",
+            unmanaged
+        );
     }
     Ok(())
 }
 
-pub fn status(ctx: &Context, args: StatusArgs) -> Result<()> {
-    let worktrees = ctx.git.worktrees().map_err(git_error)?;
-    let root = ctx
-        .repo_root
-        .canonicalize()
-        .unwrap_or_else(|_| ctx.repo_root.clone());
-    if ctx.json {
-        let mut items = Vec::new();
-        for wt in worktrees {
-            let name = worktree_display_name(ctx, &wt.path, &root);
-            let dirty = dirty_files(&ctx.git, &wt.path).map_err(git_error)?;
-            let (commit_time, commit_subject) =
-                last_commit_info(&ctx.git, &wt.path).unwrap_or((0, "".to_string()));
-            let recent = recent_uncommitted(&ctx.git, &wt.path, args.recent);
-            let last_change_time = recent.first().map(|(_, _, ts)| *ts).unwrap_or(0);
-            let recent_items: Vec<_> = recent
-                .iter()
-                .map(|(file, status, ts)| {
-                    serde_json::json!({
-                        "file": file,
-                        "status": status.to_string(),
-                        "time": pretty_time(*ts),
-                    })
-                })
-                .collect();
-            items.push(serde_json::json!({
-                "name": name,
-                "branch": wt.branch.as_ref().map(|b| short_branch(b)),
-                "changes": if args.changes_detail {
-                    format!("{} ({}/{}/{})", dirty.total, dirty.staged, dirty.unstaged, dirty.untracked)
-                } else {
-                    dirty.total.to_string()
-                },
-                "last_commit_time": pretty_time(commit_time),
-                "last_commit_subject": commit_subject,
-                "last_change_time": pretty_time(last_change_time),
-                "recent_files": recent_items,
-            }));
+/// Explain BROKEN rows: the directory is missing or git marked it prunable.
+pub(crate) fn broken_hint(count: usize, any_locked: bool) {
+    if count == 0 {
+        return;
+    }
+    eprintln!(
+        "hint: {} broken worktree(s); run `git worktree repair` if the directory was moved, or `git worktree prune` to forget it",
+        count
+    );
+    if any_locked {
+        eprintln!(
+            "hint: locked worktrees are never pruned; `git worktree unlock <path>` them first"
+        );
+    }
+}
+
+/// A worktree path for human-readable output, per `[ui] path_style`.
+pub(crate) fn display_path(ctx: &Context, path: &Path) -> String {
+    match ctx.config.path_style().as_str() {
+        "relative" => {
+            if crate::paths::same_path(path, &ctx.repo_root) {
+                return ".".to_string();
+            }
+            crate::paths::relative_to(path, &ctx.repo_root)
+                .unwrap_or_else(|| path.to_string_lossy().to_string())
         }
-        println!("{}", serde_json::to_string_pretty(&items).unwrap_or("[]".to_string()));
-        return Ok(());
+        "home" => match crate::config::home_dir() {
+            Some(home) => crate::paths::home_abbreviated(path, &home),
+            None => path.to_string_lossy().to_string(),
+        },
+        _ => path.to_string_lossy().to_string(),
     }
+}
 
-    let mut rows = Vec::new();
-    let mut recent_map = Vec::new();
-    for wt in worktrees {
-        let name = worktree_display_name(ctx, &wt.path, &root);
-        let dirty = dirty_files(&ctx.git, &wt.path).map_err(git_error)?;
-        let (commit_time, commit_subject) =
-            last_commit_info(&ctx.git, &wt.path).unwrap_or((0, "".to_string()));
-        let commit_display = if commit_time == 0 {
-            String::new()
-        } else {
-            let subject = truncate_text(&commit_subject, 20);
-            format!("{} ({})", subject, pretty_time(commit_time))
-        };
-        let recent = recent_uncommitted(&ctx.git, &wt.path, args.recent);
-        let last_change_time = recent.first().map(|(_, _, ts)| *ts).unwrap_or(0);
-        let last_change_display = if last_change_time == 0 {
-            "-".to_string()
+/// Print one `--porcelain` record: tab-separated fields, with tabs and newlines
+/// inside a field replaced by spaces so every record stays on one line.
+pub(crate) fn porcelain_line(fields: &[&str]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|f| f.replace(['\t', '\n', '\r'], " "))
+        .collect();
+    println!("{}", fields.join("\t"));
+}
+
+/// Bucket list lines by owner or tag, sorted by group name with the catch-all
+/// group last. A worktree with several tags appears under each of them.
+fn group_lines<'a>(
+    ctx: &Context,
+    group_by: &str,
+    lines: &'a [(String, String)],
+) -> Vec<(String, Vec<&'a str>)> {
+    let fallback = if group_by == "owner" {
+        "(unassigned)"
+    } else {
+        "(untagged)"
+    };
+    let mut groups: std::collections::BTreeMap<String, Vec<&str>> = Default::default();
+    let mut rest = Vec::new();
+    for (name, line) in lines {
+        let keys: Vec<String> = if group_by == "owner" {
+            worktree_owner(ctx, name)
+                .map(|o| o.to_string())
+                .into_iter()
+                .collect()
         } else {
-            pretty_time(last_change_time)
+            ctx.meta
+                .get(name)
+                .map(|m| m.tags.clone())
+                .unwrap_or_default()
         };
-        rows.push(vec![
-            name,
-            wt.branch
-                .as_ref()
-                .map(|b| short_branch(b))
-                .unwrap_or_default(),
-            format_changes(&dirty, args.changes_detail),
-            last_change_display,
-            commit_display,
-            String::new(),
-        ]);
-        recent_map.push(recent);
-    }
-
-    let headers = vec![
-        "NAME".to_string(),
-        "BRANCH".to_string(),
-        if args.changes_detail {
-            "CHANGES (ST/UN/??)".to_string()
-        } else {
-            "CHANGES".to_string()
-        },
-        "LAST CHANGE".to_string(),
-        "LAST COMMIT".to_string(),
-        "RECENT FILES".to_string(),
-    ];
-    print_table_box(headers, rows, recent_map);
-    Ok(())
+        if keys.is_empty() {
+            rest.push(line.as_str());
+        }
+        for key in keys {
+            groups.entry(key).or_default().push(line.as_str());
+        }
+    }
+    let mut out: Vec<(String, Vec<&str>)> = groups.into_iter().collect();
+    if !rest.is_empty() {
+        out.push((fallback.to_string(), rest));
+    }
+    out
 }
 
 pub fn apply(ctx: &Context, args: ApplyArgs) -> Result<()> {
-    let worktree = find_worktree(ctx, &args.name)?
-        .ok_or_else(|| GwError::new(1, "worktree not found"))?;
-    let source_branch = worktree
-        .branch
-        .clone()
-        .unwrap_or_else(|| args.name.clone());
+    let worktree = require_worktree(ctx, &args.name)?;
+    if args.cleanup && is_locked(&ctx.repo_root, &args.name) {
+        return Err(GwError::new(
+            ErrorKind::Locked,
+            format!(
+                "{}; apply without --cleanup or `gw unlock` it first",
+                locked_message(&ctx.repo_root, &args.name)
+            ),
+        ));
+    }
+    if is_detached(ctx, &args.name) {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' is a detached worktree; nothing to apply", args.name),
+        ));
+    }
+    let source_branch = worktree.branch.clone().unwrap_or_else(|| args.name.clone());
     let source_branch = source_branch.trim_start_matches("refs/heads/").to_string();
 
     let target = if let Some(target) = args.target {
@@ -452,30 +983,54 @@ pub fn apply(ctx: &Context, args: ApplyArgs) -> Result<()> {
     } else {
         ctx.git.current_branch(&ctx.repo_root).map_err(git_error)?
     };
+    check_protected(ctx, &target, args.allow_protected)?;
 
+    operation::ensure_idle(&ctx.git, &args.name, &worktree.path, "apply")?;
+    operation::ensure_idle(&ctx.git, "root", &ctx.repo_root, "apply into")?;
     let dirty = dirty_files(&ctx.git, &ctx.repo_root).map_err(git_error)?;
     if dirty.total > 0 {
-        return Err(GwError::new(1, "target worktree is dirty"));
+        return Err(GwError::new(ErrorKind::Dirty, "target worktree is dirty"));
     }
 
     ctx.git
         .run_in(&ctx.repo_root, &["checkout", &target])
         .map_err(git_error)?;
 
-    let mode = merge_mode(args.merge, args.squash, args.rebase);
+    let mode = merge_mode(
+        args.merge,
+        args.squash,
+        args.rebase,
+        ctx.config.apply_mode(),
+    );
     let result = match mode.as_str() {
         "squash" => ctx
             .git
             .run_in(&ctx.repo_root, &["merge", "--squash", &source_branch]),
         "rebase" => ctx.git.run_in(&ctx.repo_root, &["rebase", &source_branch]),
-        _ => ctx
-            .git
-            .run_in(&ctx.repo_root, &["merge", "--no-ff", &source_branch]),
+        _ => {
+            let message = with_trailers(
+                args.message
+                    .clone()
+                    .unwrap_or_else(|| format!("Merge branch '{}'", source_branch)),
+                &apply_trailers(ctx, &args.name)?,
+            );
+            ctx.git.run_in(
+                &ctx.repo_root,
+                &["merge", "--no-ff", "-m", &message, &source_branch],
+            )
+        }
     };
 
     if let Err(err) = result {
-        return Err(GwError::new(4, format!("apply failed: {}", err)));
+        return Err(GwError::new(
+            ErrorKind::Conflict,
+            format!("apply failed: {}", err),
+        ));
+    }
+    if mode == "squash" {
+        commit_squash(ctx, &args.name, &source_branch, args.message.as_deref())?;
     }
+    hooks::run_hook(ctx, "post_apply", &args.name, &worktree)?;
 
     if args.cleanup {
         del(
@@ -484,6 +1039,8 @@ pub fn apply(ctx: &Context, args: ApplyArgs) -> Result<()> {
                 name: args.name,
                 force: true,
                 delete_branch: true,
+                // The commits were just applied to the target branch
+                force_unpushed: true,
             },
         )?;
     }
@@ -491,21 +1048,199 @@ pub fn apply(ctx: &Context, args: ApplyArgs) -> Result<()> {
     Ok(())
 }
 
+/// Commit a staged `merge --squash` of `source_branch`. Without `message`, an
+/// interactive session gets the editor pre-filled with the branch's commit
+/// subjects; otherwise that summary is committed as is.
+fn commit_squash(
+    ctx: &Context,
+    name: &str,
+    source_branch: &str,
+    message: Option<&str>,
+) -> Result<()> {
+    let trailers = apply_trailers(ctx, name)?;
+    let edit = message.is_none() && std::io::stdin().is_terminal();
+    let message = match message {
+        Some(message) => with_trailers(message.to_string(), &trailers),
+        None => {
+            let range = format!("HEAD..{}", source_branch);
+            let subjects = ctx
+                .git
+                .run_in(&ctx.repo_root, &["log", "--reverse", "--format=%s", &range])
+                .map_err(git_error)?;
+            with_trailers(squash_summary(source_branch, &subjects), &trailers)
+        }
+    };
+
+    let msg_file = patch::scratch_path(ctx, "squash-msg")?;
+    fs::write(&msg_file, &message).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let msg_arg = msg_file.to_string_lossy().to_string();
+    let committed = if edit {
+        ctx.git
+            .run_passthrough(&ctx.repo_root, &["commit", "--edit", "-F", &msg_arg])
+    } else {
+        ctx.git
+            .run_in(&ctx.repo_root, &["commit", "-F", &msg_arg])
+            .map(|_| true)
+    };
+    let _ = fs::remove_file(&msg_file);
+    match committed {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(git_error(
+            "squash commit aborted; the changes are left staged",
+        )),
+        Err(e) => Err(git_error(format!("squash commit failed: {}", e))),
+    }
+}
+
+/// Default squash message: the lone commit's subject, or a summary line
+/// followed by every subject on the branch, oldest first.
+fn squash_summary(source_branch: &str, subjects: &str) -> String {
+    let subjects: Vec<&str> = subjects.lines().filter(|l| !l.trim().is_empty()).collect();
+    match subjects.as_slice() {
+        [only] => only.to_string(),
+        _ => {
+            let mut message = format!("Squash branch '{}'\n", source_branch);
+            if !subjects.is_empty() {
+                message.push('\n');
+            }
+            for subject in subjects {
+                message.push_str(&format!("* {}\n", subject));
+            }
+            message
+        }
+    }
+}
+
+/// Trailer key naming the worktree an applied change came from.
+pub(crate) const WORKTREE_TRAILER: &str = "Gw-Worktree";
+/// Trailer key naming that worktree's base branch.
+pub(crate) const BASE_TRAILER: &str = "Gw-Base";
+
+/// `Gw-*` trailer lines for a commit applying worktree `name`, or none when
+/// `[apply] trailers = false`.
+fn apply_trailers(ctx: &Context, name: &str) -> Result<Vec<String>> {
+    if !ctx.config.apply_trailers() {
+        return Ok(Vec::new());
+    }
+    let base = worktree_base(ctx, name)?;
+    Ok(vec![
+        format!("{}: {}", WORKTREE_TRAILER, name),
+        format!("{}: {}", BASE_TRAILER, short_branch(&base)),
+    ])
+}
+
+/// Append `trailers` to `message` as a trailing paragraph.
+fn with_trailers(message: String, trailers: &[String]) -> String {
+    if trailers.is_empty() {
+        return message;
+    }
+    format!("{}\n\n{}", message.trim_end(), trailers.join("\n"))
+}
+
+/// Refuse to apply into an `[apply] protected` branch unless allowed on the
+/// command line or confirmed at an interactive prompt.
+pub(crate) fn check_protected(ctx: &Context, target: &str, allow: bool) -> Result<()> {
+    if allow || !ctx.config.apply_protected(target) {
+        return Ok(());
+    }
+    let prompt = format!(
+        "'{}' is a protected branch; apply directly into it?",
+        target
+    );
+    if can_confirm(ctx) {
+        return if confirm(ctx, &prompt) {
+            Ok(())
+        } else {
+            Err(cancelled())
+        };
+    }
+    Err(GwError::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "'{}' is protected by [apply] protected; open a pull request instead, or pass --allow-protected",
+            target
+        ),
+    ))
+}
+
+/// Ask before a destructive action, showing `summary`. `--yes` answers for
+/// the user; without a terminal (or with `--no-input`) nobody can be asked,
+/// so the answer is no.
+pub(crate) fn confirm(ctx: &Context, summary: &str) -> bool {
+    if ctx.yes {
+        return true;
+    }
+    if !ctx.interactive {
+        return false;
+    }
+    eprint!("{} [y/N] ", summary);
+    let _ = std::io::stderr().flush();
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Whether a safety check may be settled by a prompt instead of a flag: on an
+/// interactive terminal, or with `--yes`. Otherwise it stays a refusal.
+pub(crate) fn can_confirm(ctx: &Context) -> bool {
+    ctx.yes || ctx.interactive
+}
+
+/// Confirm the destructive `action` described by `summary`, refusing when
+/// there is no terminal to ask on and `--yes` was not given.
+pub(crate) fn require_confirmation(ctx: &Context, action: &str, summary: &str) -> Result<()> {
+    if !can_confirm(ctx) {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} needs confirmation; pass --yes when running without a terminal",
+                action
+            ),
+        ));
+    }
+    if confirm(ctx, summary) {
+        Ok(())
+    } else {
+        Err(cancelled())
+    }
+}
+
+/// The user answered no at a confirmation prompt.
+pub(crate) fn cancelled() -> GwError {
+    GwError::new(ErrorKind::General, "cancelled")
+}
+
 pub fn sync(ctx: &Context, args: SyncArgs) -> Result<()> {
-    ctx.git
-        .run(&["fetch", "origin", "--prune"])
-        .map_err(git_error)?;
+    if args.fetch || ctx.config.fetch_before_resolve() {
+        refresh_origin(ctx)?;
+    } else {
+        ctx.git
+            .run(&["fetch", "origin", "--prune"])
+            .map_err(git_error)?;
+    }
 
-    let worktrees = ctx.git.worktrees().map_err(git_error)?;
+    let worktrees = ctx.worktrees()?;
     let target_names: Vec<String> = if args.all {
         worktrees
             .iter()
             .filter_map(|wt| worktree_name_with_config(ctx, &wt.path))
+            .filter(|name| !is_detached(ctx, name))
             .collect()
     } else if let Some(name) = args.name.clone() {
+        if is_detached(ctx, &name) {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                format!("'{}' is a detached worktree; nothing to sync", name),
+            ));
+        }
         vec![name]
     } else {
-        return Err(GwError::new(1, "sync requires <name> or --all"));
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            "sync requires <name> or --all",
+        ));
     };
 
     let base = if let Some(base) = args.base {
@@ -516,76 +1251,472 @@ pub fn sync(ctx: &Context, args: SyncArgs) -> Result<()> {
             .map_err(git_error)?
     };
 
-    let mode = if args.merge { "merge" } else { "rebase" };
+    let mode = merge_mode(args.merge, false, args.rebase, ctx.config.sync_mode());
 
+    // Check every target first so --all never stops halfway through
+    let mut targets = Vec::new();
     for name in target_names {
-        let wt = find_worktree(ctx, &name)?
-            .ok_or_else(|| GwError::new(1, "worktree not found"))?;
-        let result = match mode {
-            "merge" => ctx
-                .git
-                .run_in(&wt.path, &["merge", &base]),
-            _ => ctx
-                .git
-                .run_in(&wt.path, &["rebase", &base]),
+        if args.all && ctx.config.sync_skip_locked() && is_locked(&ctx.repo_root, &name) {
+            if !ctx.quiet {
+                eprintln!("sync: skipping {}", locked_label(&ctx.repo_root, &name));
+            }
+            continue;
+        }
+        let wt = require_worktree(ctx, &name)?;
+        operation::ensure_idle(&ctx.git, &name, &wt.path, "sync")?;
+        // git rebase ignores submodules, so a moved pointer would be carried
+        // over silently and later committed by accident
+        let submodules = changed_submodules(&ctx.git, &wt.path).map_err(git_error)?;
+        if !submodules.is_empty() {
+            if args.all {
+                eprintln!(
+                    "sync: skipping '{}' (uncommitted submodule changes: {})",
+                    name,
+                    submodules.join(", ")
+                );
+                continue;
+            }
+            return Err(GwError::new(
+                ErrorKind::Dirty,
+                format!(
+                    "'{}' has uncommitted submodule changes ({}); commit or reset them before syncing",
+                    name,
+                    submodules.join(", ")
+                ),
+            ));
+        }
+        targets.push(wt);
+    }
+
+    // With --all, a worktree based on another worktree's branch syncs onto that
+    // branch, after the parent itself has been synced
+    let nodes: Vec<(String, Option<String>)> = targets
+        .iter()
+        .map(|wt| {
+            let branch = wt.branch.as_deref().map(short_branch).unwrap_or_default();
+            let name = worktree_name_with_config(ctx, &wt.path).unwrap_or_default();
+            let parent = if args.all {
+                ctx.meta.get(&name).and_then(|m| m.base.clone())
+            } else {
+                None
+            };
+            (branch, parent)
+        })
+        .collect();
+
+    for (index, parent) in stack_order(&nodes) {
+        let wt = &targets[index];
+        let onto = match parent {
+            Some(parent) => nodes[parent].0.clone(),
+            None => base.clone(),
+        };
+        if ctx.verbose {
+            eprintln!("sync: {} onto {}", wt.path.display(), onto);
+        }
+        let result = match mode.as_str() {
+            "merge" => ctx.git.run_in(&wt.path, &["merge", &onto]),
+            _ => ctx.git.run_in(&wt.path, &["rebase", &onto]),
         };
         if let Err(err) = result {
-            return Err(GwError::new(4, format!("sync failed: {}", err)));
+            return Err(GwError::new(
+                ErrorKind::Conflict,
+                format!("sync failed: {}", err),
+            ));
         }
+        // Check out the submodule commits the synced branch now records
+        if wt.path.join(".gitmodules").exists() {
+            ctx.git
+                .run_in(&wt.path, &["submodule", "update", "--recursive"])
+                .map_err(git_error)?;
+        }
+        let name = worktree_name_with_config(ctx, &wt.path).unwrap_or_default();
+        hooks::run_hook(ctx, "post_sync", &name, wt)?;
     }
 
     Ok(())
 }
 
+/// Order `(branch, base)` nodes parents-first. Returns each node's index with the
+/// index of its parent (the node whose branch is its base), if any. Cycles are
+/// broken by treating the remaining nodes as roots.
+fn stack_order(nodes: &[(String, Option<String>)]) -> Vec<(usize, Option<usize>)> {
+    let parent_of: Vec<Option<usize>> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (_, base))| {
+            let base = base.as_deref()?;
+            (0..nodes.len()).find(|&j| j != i && !nodes[j].0.is_empty() && nodes[j].0 == base)
+        })
+        .collect();
+    let mut done = vec![false; nodes.len()];
+    let mut order = Vec::new();
+    while order.len() < nodes.len() {
+        let before = order.len();
+        for i in 0..nodes.len() {
+            if !done[i] && parent_of[i].map(|p| done[p]).unwrap_or(true) {
+                done[i] = true;
+                order.push((i, parent_of[i]));
+            }
+        }
+        if order.len() == before {
+            let i = (0..nodes.len()).find(|i| !done[*i]).unwrap_or(0);
+            done[i] = true;
+            order.push((i, None));
+        }
+    }
+    order
+}
+
 pub fn verify(ctx: &Context, args: VerifyArgs) -> Result<()> {
-    let wt = find_worktree(ctx, &args.name)?
-        .ok_or_else(|| GwError::new(1, "worktree not found"))?;
-
-    let run_dir = resolve_worktree_dir(
-        ctx,
-        &wt.path,
-        &args.name,
-        args.root,
-        args.subdir.as_deref(),
-    );
+    let wt = if args.name == "root" {
+        root_worktree(ctx)?
+    } else {
+        require_worktree(ctx, &args.name)?
+    };
 
-    let mut commands = Vec::new();
-    // Check both worktree root and resolved subdir for project files
-    if wt.path.join("Cargo.toml").exists() || run_dir.join("Cargo.toml").exists() {
-        commands.push(ctx.config.verify_rust());
-    }
-    if wt.path.join("package.json").exists() || run_dir.join("package.json").exists() {
-        commands.push(ctx.config.verify_node());
+    let run_dir =
+        resolve_worktree_dir(ctx, &wt.path, &args.name, args.root, args.subdir.as_deref());
+
+    let envs = env::worktree_env(ctx, &args.name, &wt);
+    let mut jobs: Vec<(String, PathBuf)> = Vec::new();
+    let mut fix_jobs: Vec<(String, PathBuf)> = Vec::new();
+    if args.changed_only {
+        // Run each ecosystem's command only in the subprojects that changed
+        let files = changed::changed_files(ctx, &args.name, &wt.path)?;
+        let dirs = changed::project_dirs(&files, |dir| {
+            !verify_commands(ctx, &[&wt.path.join(dir)]).is_empty()
+        });
+        for dir in dirs {
+            let dir = wt.path.join(dir);
+            for cmd in verify_commands(ctx, &[&dir]) {
+                jobs.push((cmd, dir.clone()));
+            }
+            for cmd in fix_commands(ctx, &[&dir]) {
+                fix_jobs.push((cmd, dir.clone()));
+            }
+        }
+    } else {
+        // Check both worktree root and resolved subdir for project files
+        for cmd in verify_commands(ctx, &[&wt.path, &run_dir]) {
+            jobs.push((cmd, run_dir.clone()));
+        }
+        for cmd in fix_commands(ctx, &[&wt.path, &run_dir]) {
+            fix_jobs.push((cmd, run_dir.clone()));
+        }
     }
-    if wt.path.join("pyproject.toml").exists()
-        || wt.path.join("requirements.txt").exists()
-        || run_dir.join("pyproject.toml").exists()
-        || run_dir.join("requirements.txt").exists()
-    {
-        commands.push(ctx.config.verify_python());
+    if !args.fix {
+        fix_jobs.clear();
     }
 
-    if commands.is_empty() {
+    if jobs.is_empty() {
         if !ctx.quiet {
-            println!("verify: no commands to run");
+            if args.changed_only {
+                println!("verify: no changed projects");
+            } else {
+                println!("verify: no commands to run");
+            }
         }
         return Ok(());
     }
 
-    for cmd in commands {
-        let status = run_shell(&cmd, &run_dir).map_err(|e| GwError::new(3, e))?;
+    let shell = ctx.config.exec_shell();
+    for cmd in ctx.config.verify_pre() {
+        let ok = run_shell(&shell, &cmd, &wt.path, &envs)
+            .map_err(|e| GwError::new(ErrorKind::Verify, e))?;
+        if !ok {
+            return Err(GwError::new(
+                ErrorKind::Verify,
+                format!("verify pre hook failed: {}", cmd),
+            ));
+        }
+    }
+
+    let mut failed = None;
+    if !fix_jobs.is_empty() {
+        // Only a clean worktree can be committed without sweeping in other work
+        let was_clean = dirty_files(&ctx.git, &wt.path)
+            .map(|d| d.total == 0)
+            .unwrap_or(false);
+        for (cmd, dir) in &fix_jobs {
+            if !ctx.quiet {
+                println!("verify: fix: {}", cmd);
+            }
+            let ok = run_shell(&shell, cmd, dir, &envs)
+                .map_err(|e| GwError::new(ErrorKind::Verify, e))?;
+            if !ok {
+                failed = Some(format!("fix {}", cmd));
+                break;
+            }
+        }
+        if failed.is_none() && (args.commit || ctx.config.verify_fix_commit()) {
+            commit_fixes(ctx, &wt.path, was_clean)?;
+        }
+    }
+    for (cmd, dir) in jobs {
+        if failed.is_some() {
+            break;
+        }
+        if args.changed_only && !ctx.quiet {
+            let rel = crate::paths::relative_to(&dir, &wt.path).unwrap_or_default();
+            println!(
+                "verify: {}: {}",
+                if rel.is_empty() { "." } else { rel.as_str() },
+                cmd
+            );
+        }
+        let status =
+            run_shell(&shell, &cmd, &dir, &envs).map_err(|e| GwError::new(ErrorKind::Verify, e))?;
         if !status {
-            return Err(GwError::new(3, format!("verify failed: {}", cmd)));
+            failed = Some(cmd);
+            break;
         }
     }
 
+    // Post hooks always run (e.g. teardown, coverage); their failure only
+    // fails verify if the pipeline itself passed
+    for cmd in ctx.config.verify_post() {
+        let ok = run_shell(&shell, &cmd, &wt.path, &envs)
+            .map_err(|e| GwError::new(ErrorKind::Verify, e))?;
+        if !ok && failed.is_none() {
+            failed = Some(format!("post hook {}", cmd));
+        }
+    }
+
+    if args.name != "root" {
+        let mut meta = ctx.meta.clone();
+        meta.record_verify(&args.name, failed.is_none());
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+
+    if let Some(cmd) = failed {
+        return Err(GwError::new(
+            ErrorKind::Verify,
+            format!("verify failed: {}", cmd),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Verify commands for the project manifests found in any of `dirs`.
+fn verify_commands(ctx: &Context, dirs: &[&Path]) -> Vec<String> {
+    manifest_commands(
+        dirs,
+        [
+            Some(ctx.config.verify_rust()),
+            Some(ctx.config.verify_node()),
+            Some(ctx.config.verify_python()),
+        ],
+    )
+}
+
+/// Configured `verify --fix` commands for the project manifests found in any of `dirs`.
+fn fix_commands(ctx: &Context, dirs: &[&Path]) -> Vec<String> {
+    manifest_commands(
+        dirs,
+        [
+            ctx.config.verify_fix_rust(),
+            ctx.config.verify_fix_node(),
+            ctx.config.verify_fix_python(),
+        ],
+    )
+}
+
+/// Pick the rust, node and python entries of `commands` whose manifests exist in `dirs`.
+fn manifest_commands(dirs: &[&Path], commands: [Option<String>; 3]) -> Vec<String> {
+    let has = |files: &[&str]| {
+        dirs.iter()
+            .any(|d| files.iter().any(|f| d.join(f).exists()))
+    };
+    let [rust, node, python] = commands;
+    let mut found = Vec::new();
+    if has(&["Cargo.toml"]) {
+        found.extend(rust);
+    }
+    if has(&["package.json"]) {
+        found.extend(node);
+    }
+    if has(&["pyproject.toml", "requirements.txt"]) {
+        found.extend(python);
+    }
+    found
+}
+
+const FIX_COMMIT_MESSAGE: &str = "gw verify --fix: apply formatters and autofixes";
+
+/// Commit everything the fixers changed in `wt_path` with a standard message.
+fn commit_fixes(ctx: &Context, wt_path: &Path, was_clean: bool) -> Result<()> {
+    let dirty = dirty_files(&ctx.git, wt_path).map_err(git_error)?;
+    if dirty.total == 0 {
+        return Ok(());
+    }
+    if !was_clean {
+        eprintln!("verify: worktree had uncommitted changes; leaving fixes uncommitted");
+        return Ok(());
+    }
+    ctx.git
+        .run_in(wt_path, &["add", "-A"])
+        .and_then(|_| {
+            ctx.git
+                .run_in(wt_path, &["commit", "-m", FIX_COMMIT_MESSAGE])
+        })
+        .map_err(git_error)?;
+    if !ctx.quiet {
+        println!("verify: committed fixes ({} files)", dirty.total);
+    }
     Ok(())
 }
 
 pub fn note(ctx: &Context, args: NoteArgs) -> Result<()> {
     let mut meta = ctx.meta.clone();
     meta.add_note(&args.name, args.text);
-    meta.save().map_err(|e| GwError::new(1, e.to_string()))?;
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    Ok(())
+}
+
+pub fn meta_cmd(ctx: &Context, args: MetaArgs) -> Result<()> {
+    match args.action {
+        MetaAction::Set { name, key, value } => {
+            require_worktree(ctx, &name)?;
+            if key.is_empty()
+                || !key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            {
+                return Err(GwError::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "invalid meta key '{}': use letters, digits, '_', '-' or '.'",
+                        key
+                    ),
+                ));
+            }
+            let mut meta = ctx.meta.clone();
+            meta.set_field(&name, &key, Some(value));
+            meta.save()
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        }
+        MetaAction::Get { name, key } => {
+            let fields = ctx
+                .meta
+                .get(&name)
+                .map(|m| m.fields.clone())
+                .unwrap_or_default();
+            match key {
+                Some(key) => {
+                    let value = fields.get(&key).ok_or_else(|| {
+                        GwError::new(
+                            ErrorKind::NotFound,
+                            format!("no meta '{}' for '{}'", key, name),
+                        )
+                    })?;
+                    println!("{}", value);
+                }
+                None if ctx.json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&fields).unwrap_or("{}".to_string())
+                ),
+                None => {
+                    for (key, value) in &fields {
+                        println!("{}={}", key, value);
+                    }
+                }
+            }
+        }
+        MetaAction::Unset { name, key } => {
+            let mut meta = ctx.meta.clone();
+            meta.set_field(&name, &key, None);
+            meta.save()
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        }
+        MetaAction::History { name, limit } => {
+            let changes = ctx
+                .meta
+                .history(name.as_deref(), limit)
+                .map_err(|e| GwError::new(ErrorKind::InvalidInput, e.to_string()))?;
+            if ctx.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&changes).unwrap_or("[]".to_string())
+                );
+                return Ok(());
+            }
+            for change in &changes {
+                let what = if change.meta.is_some() {
+                    "updated"
+                } else {
+                    "removed"
+                };
+                println!("{}  {}  {}", change.at, change.name, what);
+            }
+        }
+        MetaAction::Push { remote } => {
+            share::push(ctx, &remote.unwrap_or_else(|| ctx.config.meta_remote()))?
+        }
+        MetaAction::Pull { remote } => {
+            share::pull(ctx, &remote.unwrap_or_else(|| ctx.config.meta_remote()))?
+        }
+        MetaAction::Migrate { to } => meta_migrate(ctx, &to)?,
+    }
+    Ok(())
+}
+
+/// Copy every worktree's metadata from the other backend into `to`, replacing
+/// what `to` held. The source is left in place as a backup.
+fn meta_migrate(ctx: &Context, to: &str) -> Result<()> {
+    let from = if to == "sqlite" { "json" } else { "sqlite" };
+    let open = |kind: &str| {
+        crate::meta::open_backend(&ctx.repo_root, kind)
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+    };
+    let (source, target) = (open(from)?, open(to)?);
+    let data = source
+        .load()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let existing = target
+        .load()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    target
+        .save(&existing, &data)
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    if !ctx.quiet {
+        println!(
+            "migrated {} worktree(s) from {} to {}",
+            data.worktrees.len(),
+            from,
+            to
+        );
+        if ctx.config.meta_backend() != to {
+            println!(
+                "set `[meta] backend = \"{}\"` in .gw/config.toml to use it",
+                to
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn assign(ctx: &Context, args: AssignArgs) -> Result<()> {
+    let mut meta = ctx.meta.clone();
+    if args.unset {
+        meta.set_owner(&args.name, None);
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        if !ctx.quiet {
+            println!("unassigned '{}'", args.name);
+        }
+    } else if let Some(owner) = args.owner {
+        meta.set_owner(&args.name, Some(owner.clone()));
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        if !ctx.quiet {
+            println!("assigned '{}' to {}", args.name, owner);
+        }
+    } else {
+        println!("{}", worktree_owner(ctx, &args.name).unwrap_or("(none)"));
+    }
     Ok(())
 }
 
@@ -593,14 +1724,43 @@ pub fn subdir(ctx: &Context, args: SubdirArgs) -> Result<()> {
     let mut meta = ctx.meta.clone();
     if args.unset {
         meta.set_subdir(&args.name, None);
-        meta.save().map_err(|e| GwError::new(1, e.to_string()))?;
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
         if !ctx.quiet {
             println!("unset subdir for '{}'", args.name);
         }
+    } else if args.auto {
+        let wt = require_worktree(ctx, &args.name)?;
+        match changed::auto_subdir(ctx, &args.name, &wt.path) {
+            Some(path) => {
+                meta.set_subdir(&args.name, Some(path.clone()));
+                meta.save()
+                    .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+                if !ctx.quiet {
+                    println!(
+                        "set subdir for '{}': {} (inferred from changes)",
+                        args.name, path
+                    );
+                }
+            }
+            None => {
+                if !ctx.quiet {
+                    println!(
+                        "no common subdir: changes of '{}' span the worktree root",
+                        args.name
+                    );
+                }
+            }
+        }
     } else if let Some(path) = args.path {
-        let path = path.trim_start_matches('/').to_string();
+        let path = ctx
+            .config
+            .expand_subdir(&path)
+            .trim_start_matches('/')
+            .to_string();
         meta.set_subdir(&args.name, Some(path.clone()));
-        meta.save().map_err(|e| GwError::new(1, e.to_string()))?;
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
         if !ctx.quiet {
             println!("set subdir for '{}': {}", args.name, path);
         }
@@ -609,8 +1769,16 @@ pub fn subdir(ctx: &Context, args: SubdirArgs) -> Result<()> {
         let wt_meta = meta.get(&args.name);
         let meta_subdir = wt_meta.and_then(|m| m.subdir.as_deref());
         let config_subdir = ctx.config.default_subdir();
+        let auto = if meta_subdir.is_none() && ctx.config.auto_subdir() {
+            find_worktree(ctx, &args.name)?
+                .and_then(|wt| changed::auto_subdir(ctx, &args.name, &wt.path))
+        } else {
+            None
+        };
         if let Some(s) = meta_subdir {
             println!("{} (from: meta.json)", s);
+        } else if let Some(ref s) = auto {
+            println!("{} (from: auto_subdir)", s);
         } else if let Some(ref s) = config_subdir {
             println!("{} (from: config default)", s);
         } else {
@@ -626,7 +1794,7 @@ pub fn config(ctx: &Context, args: ConfigArgs) -> Result<()> {
     if args.edit {
         let config_path = ctx.repo_root.join(".gw").join("config.toml");
         fs::create_dir_all(config_path.parent().unwrap())
-            .map_err(|e| GwError::new(1, e.to_string()))?;
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
         if !config_path.exists() {
             let default_content = format!(
                 "[defaults]\nworktrees_dir = \"{}\"\nbranch_prefix = \"{}\"\n# subdir = \"services/app\"\n\n[gc]\nstale_days = {}\n",
@@ -634,29 +1802,30 @@ pub fn config(ctx: &Context, args: ConfigArgs) -> Result<()> {
                 ctx.config.branch_prefix(),
                 ctx.config.gc_stale_days(),
             );
-            fs::write(&config_path, default_content).map_err(|e| GwError::new(1, e.to_string()))?;
+            fs::write(&config_path, default_content)
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
         }
         let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
         let status = Command::new(&editor)
             .arg(&config_path)
             .status()
-            .map_err(|e| GwError::new(1, format!("failed to open editor '{}': {}", editor, e)))?;
+            .map_err(|e| {
+                GwError::new(
+                    ErrorKind::General,
+                    format!("failed to open editor '{}': {}", editor, e),
+                )
+            })?;
         if !status.success() {
-            return Err(GwError::new(1, "editor exited with error"));
+            return Err(GwError::new(ErrorKind::General, "editor exited with error"));
         }
         return Ok(());
     }
 
     // [defaults]
     println!("[defaults]");
-    println!(
-        "worktrees_dir = {}",
-        ctx.config.worktrees_dir()
-    );
-    println!(
-        "branch_prefix = {}",
-        ctx.config.branch_prefix()
-    );
+    println!("worktrees_dir = {}", ctx.config.worktrees_dir());
+    println!("tmp_dir = {}", ctx.config.tmp_dir().display());
+    println!("branch_prefix = {}", ctx.config.branch_prefix());
     if let Some(ref base) = ctx.config.default_base() {
         println!("base = {}", base);
     }
@@ -680,16 +1849,18 @@ pub fn config(ctx: &Context, args: ConfigArgs) -> Result<()> {
                 println!("[worktree subdirs]");
                 has_subdirs = true;
             }
-            println!(
-                "{} = {}",
-                name,
-                wt_meta.subdir.as_deref().unwrap_or("")
-            );
+            println!("{} = {}", name, wt_meta.subdir.as_deref().unwrap_or(""));
         }
     }
 
     // Validation
-    let warnings = Config::validate(&ctx.repo_root);
+    let mut warnings = Config::validate(&ctx.repo_root);
+    for path in exclude::unexcluded(ctx) {
+        warnings.push(format!(
+            "{} is not ignored and clutters git status (the next gw add excludes it)",
+            path
+        ));
+    }
     if !warnings.is_empty() {
         println!();
         println!("warnings:");
@@ -704,14 +1875,52 @@ pub fn config(ctx: &Context, args: ConfigArgs) -> Result<()> {
 pub fn info(ctx: &Context, args: InfoArgs) -> Result<()> {
     let meta = ctx.meta.clone();
     if let Some(wt) = meta.get(&args.name) {
-        if ctx.json {
+        if args.porcelain {
+            let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+            porcelain_line(&["name", &args.name]);
+            porcelain_line(&["created_at", &opt(&wt.created_at)]);
+            porcelain_line(&["created_by", &opt(&wt.created_by)]);
+            porcelain_line(&["last_activity_at", &opt(&wt.last_activity_at)]);
+            porcelain_line(&["branch", &opt(&wt.branch)]);
+            porcelain_line(&["base", &opt(&wt.base)]);
+            porcelain_line(&["owner", &opt(&wt.owner)]);
+            porcelain_line(&["subdir", &opt(&wt.subdir)]);
+            porcelain_line(&["detached", if wt.detached { "1" } else { "0" }]);
+            porcelain_line(&["ephemeral", if wt.ephemeral { "1" } else { "0" }]);
+            porcelain_line(&["expires_at", &opt(&wt.expires_at)]);
+            porcelain_line(&["issue_url", &opt(&wt.issue_url)]);
+            for tag in &wt.tags {
+                porcelain_line(&["tag", tag]);
+            }
+            for note in &wt.notes {
+                porcelain_line(&["note", note]);
+            }
+            for (key, value) in &wt.fields {
+                porcelain_line(&[&format!("meta.{}", key), value]);
+            }
+        } else if ctx.json {
             let out = serde_json::to_string_pretty(&wt).unwrap_or_else(|_| "{}".to_string());
             println!("{}", out);
         } else {
             println!("name: {}", args.name);
             println!("created_at: {}", wt.created_at.clone().unwrap_or_default());
             println!("created_by: {}", wt.created_by.clone().unwrap_or_default());
-            println!("last_activity_at: {}", wt.last_activity_at.clone().unwrap_or_default());
+            println!(
+                "last_activity_at: {}",
+                wt.last_activity_at.clone().unwrap_or_default()
+            );
+            if let Some(ref owner) = wt.owner {
+                println!("owner: {}", owner);
+            }
+            if wt.ephemeral {
+                println!(
+                    "ephemeral: expires {}",
+                    wt.expires_at.as_deref().unwrap_or("when merged")
+                );
+            }
+            if let Some(ref url) = wt.issue_url {
+                println!("issue: {}", url);
+            }
             if let Some(ref subdir) = wt.subdir {
                 println!("subdir: {} (from: meta.json)", subdir);
             } else if let Some(ref default) = ctx.config.default_subdir() {
@@ -726,54 +1935,177 @@ pub fn info(ctx: &Context, args: InfoArgs) -> Result<()> {
             if !wt.tags.is_empty() {
                 println!("tags: {}", wt.tags.join(", "));
             }
+            if !wt.fields.is_empty() {
+                println!("meta:");
+                for (key, value) in &wt.fields {
+                    println!("  {}: {}", key, value);
+                }
+            }
         }
         Ok(())
     } else {
-        Err(GwError::new(1, "no meta for worktree"))
+        Err(GwError::new(ErrorKind::NotFound, "no meta for worktree"))
     }
 }
 
 pub fn lock(ctx: &Context, args: LockArgs) -> Result<()> {
-    let lock_path = lock_path(&ctx.repo_root, &args.name);
-    fs::create_dir_all(lock_path.parent().unwrap()).map_err(|e| GwError::new(1, e.to_string()))?;
-    fs::write(&lock_path, b"").map_err(|e| GwError::new(1, e.to_string()))?;
+    let Some(name) = args.name else {
+        return list_locks(ctx);
+    };
+    let lock_path = lock_path(&ctx.repo_root, &name);
+    fs::create_dir_all(lock_path.parent().unwrap())
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let reason = args.reason.unwrap_or_default();
+    fs::write(&lock_path, reason.trim())
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    Ok(())
+}
+
+/// `gw lock --list`: the running gw operation, then the locked worktrees.
+fn list_locks(ctx: &Context) -> Result<()> {
+    let operation = crate::oplock::current(&ctx.repo_root);
+    let mut locked: Vec<String> = fs::read_dir(ctx.repo_root.join(".gw").join("locks"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let file = e.file_name().to_string_lossy().to_string();
+                    file.strip_suffix(".lock").map(str::to_string)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    locked.sort();
+
+    if ctx.json {
+        let worktrees: Vec<_> = locked
+            .iter()
+            .map(|name| {
+                serde_json::json!({
+                    "name": name,
+                    "reason": lock_reason(&ctx.repo_root, name),
+                })
+            })
+            .collect();
+        let value = serde_json::json!({
+            "operation": operation,
+            "worktrees": worktrees,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        );
+        return Ok(());
+    }
+    if let Some(ref holder) = operation {
+        println!("operation: {}", holder.describe());
+    }
+    for name in &locked {
+        match lock_reason(&ctx.repo_root, name) {
+            Some(reason) => println!("{}: locked ({})", name, reason),
+            None => println!("{}: locked", name),
+        }
+    }
+    if operation.is_none() && locked.is_empty() && !ctx.quiet {
+        eprintln!("no locks");
+    }
     Ok(())
 }
 
 pub fn unlock(ctx: &Context, args: UnlockArgs) -> Result<()> {
     let lock_path = lock_path(&ctx.repo_root, &args.name);
     if lock_path.exists() {
-        fs::remove_file(&lock_path).map_err(|e| GwError::new(1, e.to_string()))?;
+        fs::remove_file(&lock_path).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
     }
     Ok(())
 }
 
 pub fn gc(ctx: &Context, args: GcArgs) -> Result<()> {
-    let worktrees = ctx.git.worktrees().map_err(git_error)?;
+    let worktrees = ctx.worktrees()?;
     let meta = ctx.meta.clone();
     let mut candidates = Vec::new();
+    // Clean worktrees that are not candidates, with last activity, for the size rule
+    let mut spare = Vec::new();
 
+    let mut targets = Vec::new();
+    let mut others = 0;
     for wt in worktrees {
         let name = match worktree_name_with_config(ctx, &wt.path) {
             Some(n) => n,
             None => continue,
         };
-        if is_locked(&ctx.repo_root, &name) {
+        if is_locked(&ctx.repo_root, &name) || wt.is_broken() {
+            continue;
+        }
+        if !owner_matches(ctx, &name, &args.filter) {
+            continue;
+        }
+        // Never propose another user's worktree unless asked to
+        let explicit = args.all_users || args.filter.owner.is_some() || args.filter.user.is_some();
+        if !explicit && is_others(ctx, &name) {
+            others += 1;
             continue;
         }
-        let dirty = dirty_files(&ctx.git, &wt.path).map_err(git_error)?;
-        let last_commit_time = last_commit_unix(&ctx.git, &wt.path).unwrap_or(0);
+        targets.push((name, wt));
+    }
+    if others > 0 && !ctx.quiet {
+        eprintln!(
+            "gc: skipped {} worktree(s) of other users (--all-users to include them)",
+            others
+        );
+    }
+
+    // The per-worktree queries are independent; run them side by side
+    let merged = merged_branches(&ctx.git, &ctx.repo_root);
+    let branches = ctx.git.branch_infos();
+    let evaluated = parallel_map(&targets, |(_, wt)| {
+        let dirty = dirty_files(&ctx.git, &wt.path)?;
+        let last_commit_time = match wt
+            .branch
+            .as_deref()
+            .and_then(|b| branches.get(&short_branch(b)))
+        {
+            Some(info) => info.commit_time,
+            None => last_commit_unix(&ctx.git, &wt.path).unwrap_or(0),
+        };
+        Ok::<_, String>((dirty, last_commit_time))
+    });
+
+    let now = Utc::now().timestamp();
+    let stale_days = ctx.config.gc_stale_days();
+    for ((name, wt), result) in targets.into_iter().zip(evaluated) {
+        let (dirty, last_commit_time) = result.map_err(git_error)?;
         let last_activity = meta
             .get(&name)
             .and_then(|m| m.last_activity_at.clone())
             .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
             .map(|dt| dt.timestamp())
             .unwrap_or(last_commit_time);
-        let now = Utc::now().timestamp();
-        let stale_days = ctx.config.gc_stale_days();
         let stale = now - last_activity >= stale_days * 24 * 60 * 60;
-        if stale || (dirty.total == 0 && branch_merged(&ctx.git, &wt, &ctx.repo_root)) {
-            candidates.push((name, wt.path));
+        let is_merged = wt
+            .branch
+            .as_deref()
+            .is_some_and(|b| merged.contains(&short_branch(b)));
+        if stale || (dirty.total == 0 && is_merged) {
+            candidates.push((name, wt.path, wt.branch, None));
+        } else if dirty.total == 0 {
+            spare.push((last_activity, name, wt.path, wt.branch));
+        }
+    }
+
+    // Over the disk budget: also propose the least recently active clean worktrees
+    if let Some(budget) = ctx.config.gc_max_total_size() {
+        let mut total = disk::dir_size(&ctx.repo_root.join(ctx.config.worktrees_dir()));
+        total = total.saturating_sub(candidates.iter().map(|c| disk::dir_size(&c.1)).sum());
+        spare.sort_by_key(|(activity, ..)| *activity);
+        for (_, name, path, branch) in spare {
+            if total <= budget {
+                break;
+            }
+            let size = disk::dir_size(&path);
+            total = total.saturating_sub(size);
+            let reason = format!("over max_total_size, frees {}", disk::format_size(size));
+            candidates.push((name, path, branch, Some(reason)));
         }
     }
 
@@ -784,76 +2116,352 @@ pub fn gc(ctx: &Context, args: GcArgs) -> Result<()> {
         return Ok(());
     }
 
-    for (name, path) in candidates {
+    if args.prune {
+        let names: Vec<&str> = candidates.iter().map(|c| c.0.as_str()).collect();
+        let summary = format!(
+            "prune {} worktree(s), discarding any uncommitted changes{}: {}?",
+            names.len(),
+            if args.delete_branches {
+                " and deleting their branches"
+            } else {
+                ""
+            },
+            names.join(", ")
+        );
+        require_confirmation(ctx, "gc --prune", &summary)?;
+    }
+
+    let mut meta = meta;
+    let mut failed = Vec::new();
+    for (name, path, branch, reason) in candidates {
         if args.prune {
-            let _ = ctx.git.run(&["worktree", "remove", "--force", path.to_string_lossy().as_ref()]);
+            let removed = ctx.git.run(&[
+                "worktree",
+                "remove",
+                "--force",
+                path.to_string_lossy().as_ref(),
+            ]);
+            // The branch stays while its worktree does
+            if let Err(e) = removed {
+                eprintln!("gc: failed to prune {}: {}", name, e);
+                failed.push(name);
+                continue;
+            }
+            meta.remove(&name);
             println!("pruned: {}", name);
+            if args.delete_branches {
+                if let Some(branch) = branch {
+                    let branch = short_branch(&branch);
+                    archive::archive_branch(ctx, &name, &branch)?;
+                    if let Err(e) = ctx.git.run(&["branch", "-D", &branch]) {
+                        eprintln!("gc: failed to delete branch {}: {}", branch, e);
+                        failed.push(name);
+                    }
+                }
+            }
+        } else if let Some(reason) = reason {
+            println!("candidate: {} ({})", name, reason);
         } else {
             println!("candidate: {}", name);
         }
     }
+    if args.prune {
+        meta.save()
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    if !failed.is_empty() {
+        return Err(GwError::new(
+            ErrorKind::Git,
+            format!("gc: could not prune {}", failed.join(", ")),
+        ));
+    }
 
     Ok(())
 }
 
 pub fn cd(ctx: &Context, args: CdArgs) -> Result<()> {
-    let mut target = resolve_subdir(
-        &ctx.repo_root,
-        args.root,
-        args.subdir.as_deref(),
-        None,
-        ctx.config.default_subdir().as_deref(),
-    );
-    if let Some(ref name) = args.name {
-        if name != "root" {
-            let wt = find_worktree(ctx, name)?
-                .ok_or_else(|| GwError::new(1, "worktree not found"))?;
-            target = resolve_worktree_dir(
-                ctx,
-                &wt.path,
-                name,
-                args.root,
-                args.subdir.as_deref(),
-            );
+    let name = match args.name.as_deref() {
+        Some(n) => Some(recent::resolve_ref(ctx, n)?),
+        // Bare `gw cd` at a terminal picks interactively; scripts still get the repo root
+        None if !args.root && args.subdir.is_none() && switch::can_pick() => {
+            match switch::pick_worktree(ctx)? {
+                Some(name) => Some(name),
+                None => return Ok(()),
+            }
+        }
+        None => None,
+    };
+    let target = resolve_cd_target(ctx, name.as_deref(), args.root, args.subdir.as_deref())?;
+    // Switching into a worktree counts as activity for `gw recent`
+    if let Some(name) = name.as_deref().filter(|n| *n != "root") {
+        let mut meta = ctx.meta.clone();
+        meta.set_last_activity(name);
+        let _ = meta.save();
+    }
+    if args.shell || args.print_env {
+        let format = match detect_shell() {
+            Some(clap_complete::Shell::PowerShell) => "powershell",
+            Some(clap_complete::Shell::Fish) => "fish",
+            _ => "sh",
+        };
+        println!(
+            "{}",
+            crate::paths::shell_cd_command(&target, format == "powershell")
+        );
+        if args.print_env {
+            let (name, wt) = match name.as_deref().filter(|n| *n != "root") {
+                Some(name) => (name.to_string(), require_worktree(ctx, name)?),
+                None => ("root".to_string(), root_worktree(ctx)?),
+            };
+            for (key, value) in env::worktree_env(ctx, &name, &wt) {
+                println!("{}", env::export_line(&key, &value, format));
+            }
         }
-    }
-    if args.shell {
-        println!("cd \"{}\"", target.display());
     } else {
         println!("{}", target.display());
     }
     Ok(())
 }
 
+/// Print the main checkout's root (or the shared git dir), the same from
+/// every worktree.
+pub fn root(ctx: &Context, args: RootArgs) -> Result<()> {
+    let git_dir = ctx.git.common_dir(&ctx.repo_root).map_err(git_error)?;
+    if ctx.json {
+        println!(
+            "{}",
+            serde_json::json!({ "root": ctx.repo_root, "git_dir": git_dir })
+        );
+    } else if args.git_dir {
+        println!("{}", git_dir.display());
+    } else {
+        println!("{}", ctx.repo_root.display());
+    }
+    Ok(())
+}
+
+/// Mark a worktree (the one containing the current directory by default) as
+/// active. Called in the background by the shell-init cd hook, so outside a
+/// gw worktree it quietly does nothing.
+pub fn touch(ctx: &Context, args: TouchArgs) -> Result<()> {
+    let name = match args.name {
+        Some(name) => {
+            let name = recent::resolve_ref(ctx, &name)?;
+            require_worktree(ctx, &name)?;
+            name
+        }
+        None => {
+            let Some(name) = ctx
+                .git
+                .current_toplevel()
+                .ok()
+                .and_then(|path| worktree_name_with_config(ctx, &path))
+            else {
+                return Ok(());
+            };
+            name
+        }
+    };
+    let mut meta = ctx.meta.clone();
+    meta.set_last_activity(&name);
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    if !ctx.quiet {
+        println!("touched: {}", name);
+    }
+    Ok(())
+}
+
+/// Directory `gw cd` would switch to: a worktree (or the repo root) plus its subdir.
+pub(crate) fn resolve_cd_target(
+    ctx: &Context,
+    name: Option<&str>,
+    cli_root: bool,
+    cli_subdir: Option<&str>,
+) -> Result<PathBuf> {
+    let cli_subdir = cli_subdir.map(|s| ctx.config.expand_subdir(s));
+    let cli_subdir = cli_subdir.as_deref();
+    let name = name.map(|n| recent::resolve_ref(ctx, n)).transpose()?;
+    match name.as_deref() {
+        Some(name) if name != "root" => {
+            let wt = require_worktree(ctx, name)?;
+            Ok(resolve_worktree_dir(
+                ctx, &wt.path, name, cli_root, cli_subdir,
+            ))
+        }
+        _ => Ok(resolve_subdir(
+            &ctx.repo_root,
+            cli_root,
+            cli_subdir,
+            None,
+            ctx.config.default_subdir().as_deref(),
+        )),
+    }
+}
+
 pub fn complete_(ctx: &Context, args: crate::cli::CompleteTypeArgs) -> Result<()> {
     match args.comp_type.as_str() {
         "worktrees" => {
             println!("root");
-            let worktrees = ctx.git.worktrees().map_err(git_error)?;
+            let worktrees = ctx.worktrees()?;
             for wt in worktrees {
                 if let Some(name) = worktree_name_with_config(ctx, &wt.path) {
                     println!("{}", name);
                 }
             }
         }
+        "branches" => {
+            let out = ctx
+                .git
+                .run(&[
+                    "for-each-ref",
+                    "--format=%(refname:short)",
+                    "refs/heads",
+                    "refs/remotes",
+                ])
+                .map_err(git_error)?;
+            for branch in out.lines().filter(|b| !b.ends_with("/HEAD")) {
+                println!("{}", branch);
+            }
+        }
+        "tags" => {
+            let mut tags: Vec<&String> = ctx.meta.all().values().flat_map(|m| &m.tags).collect();
+            tags.sort();
+            tags.dedup();
+            for tag in tags {
+                println!("{}", tag);
+            }
+        }
+        "subdirs" => {
+            for name in ctx.config.subdirs.keys() {
+                println!("{}", name);
+            }
+            // Anything that is not a worktree (a new name for `add`, a flag) means the current one
+            let root = match args.worktree.as_deref() {
+                Some(name) if !name.is_empty() => {
+                    find_worktree(ctx, name).ok().flatten().map(|wt| wt.path)
+                }
+                _ => None,
+            };
+            let root = match root {
+                Some(root) => root,
+                None => ctx.git.current_toplevel().map_err(git_error)?,
+            };
+            for dir in subdir_candidates(&root, &args.prefix) {
+                println!("{}", dir);
+            }
+        }
+        "config-keys" => {
+            for key in crate::config::KNOWN_KEYS {
+                println!("{}", key);
+            }
+        }
         _ => {}
     }
     Ok(())
 }
 
+/// Directories one level below the directory part of `prefix`, as `/`-terminated
+/// paths relative to `root`. Hidden directories only show up once `.` is typed.
+fn subdir_candidates(root: &Path, prefix: &str) -> Vec<String> {
+    let (parent, leaf) = match prefix.rfind('/') {
+        Some(i) => (&prefix[..=i], &prefix[i + 1..]),
+        None => ("", prefix),
+    };
+    let entries = match fs::read_dir(root.join(parent)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut dirs: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+        .filter(|name| name != ".git" && (!name.starts_with('.') || leaf.starts_with('.')))
+        .filter(|name| name.starts_with(leaf))
+        .map(|name| format!("{}{}/", parent, name))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
 pub fn completion(args: crate::cli::CompletionArgs) -> Result<()> {
     use clap::CommandFactory;
+    let shell = match args.shell {
+        Some(shell) => shell,
+        None => detect_shell()
+            .ok_or_else(|| GwError::new(ErrorKind::InvalidInput, "could not detect shell"))?,
+    };
     let mut cmd = crate::cli::Cli::command();
-    clap_complete::generate(args.shell, &mut cmd, "gw", &mut std::io::stdout());
+    if !args.install {
+        clap_complete::generate(shell, &mut cmd, "gw", &mut std::io::stdout());
+        return Ok(());
+    }
+
+    let path = completion_install_path(shell).ok_or_else(|| {
+        GwError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "no completion directory for {} (redirect `gw completion {}` into your profile instead)",
+                shell, shell
+            ),
+        )
+    })?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, "gw", &mut script);
+    fs::write(&path, script).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    eprintln!("installed {} completions: {}", shell, path.display());
+    if let (clap_complete::Shell::Zsh, Some(dir)) = (shell, path.parent()) {
+        eprintln!(
+            "add it to fpath before compinit: fpath=({} $fpath)",
+            dir.display()
+        );
+    }
     Ok(())
 }
 
+/// Where each shell picks up completion scripts for the current user.
+fn completion_install_path(shell: clap_complete::Shell) -> Option<PathBuf> {
+    use clap_complete::Shell;
+    let xdg = |var: &str, fallback: &str| {
+        std::env::var(var)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| expand_home(fallback))
+    };
+    match shell {
+        Shell::Bash => Some(
+            xdg("XDG_DATA_HOME", "~/.local/share")
+                .join("bash-completion")
+                .join("completions")
+                .join("gw"),
+        ),
+        Shell::Zsh => {
+            let zdotdir = std::env::var("ZDOTDIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| expand_home("~/"));
+            Some(zdotdir.join(".zfunc").join("_gw"))
+        }
+        Shell::Fish => Some(
+            xdg("XDG_CONFIG_HOME", "~/.config")
+                .join("fish")
+                .join("completions")
+                .join("gw.fish"),
+        ),
+        _ => None,
+    }
+}
+
 pub fn shell_init(args: crate::cli::ShellInitArgs) -> Result<()> {
     use clap_complete::Shell;
     let shell = if let Some(shell) = args.shell {
         shell
     } else {
-        detect_shell().ok_or_else(|| GwError::new(1, "could not detect shell"))?
+        detect_shell()
+            .ok_or_else(|| GwError::new(ErrorKind::InvalidInput, "could not detect shell"))?
     };
 
     let auto = args.shell.is_none() && !args.install && !args.apply;
@@ -900,20 +2508,79 @@ pub fn shell_init(args: crate::cli::ShellInitArgs) -> Result<()> {
     Ok(())
 }
 
-#[derive(Debug)]
-#[derive(Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct DirtyInfo {
     total: usize,
     staged: usize,
     unstaged: usize,
     untracked: usize,
+    /// Submodules with new commits or uncommitted work of their own; these are
+    /// also counted in the totals above
+    #[serde(default)]
+    submodules: usize,
 }
 
 pub(crate) fn dirty_files(
     git: &crate::git::Git,
     path: &Path,
 ) -> std::result::Result<DirtyInfo, String> {
-    let out = git.run_in(path, &["status", "--porcelain"])?;
+    let out = git.status_porcelain(path)?;
+    let mut dirty = parse_dirty(&out);
+    dirty.submodules = changed_submodules(git, path)?.len();
+    Ok(dirty)
+}
+
+/// Paths of submodules whose checked-out commit differs from the recorded
+/// pointer or whose own worktree is dirty. Only asks git when the worktree
+/// has a `.gitmodules`.
+pub(crate) fn changed_submodules(
+    git: &crate::git::Git,
+    path: &Path,
+) -> std::result::Result<Vec<String>, String> {
+    if !path.join(".gitmodules").exists() {
+        return Ok(Vec::new());
+    }
+    let out = git.run_in(
+        path,
+        &["status", "--porcelain=v2", "--ignore-submodules=none"],
+    )?;
+    Ok(parse_submodule_changes(&out))
+}
+
+/// Ordinary porcelain v2 entries (`1 XY sub mH mI mW hH hI path`) whose
+/// submodule field is `S` followed by at least one change flag.
+fn parse_submodule_changes(out: &str) -> Vec<String> {
+    out.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(9, ' ').collect();
+            match fields.as_slice() {
+                ["1", _, sub, .., path] if sub.starts_with('S') && *sub != "S..." => {
+                    Some(path.to_string())
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// A changed file: path, status letter, and modification time.
+pub(crate) type RecentFile = (String, char, i64);
+
+/// Dirty counts plus the `max` most recently modified changed files, from a
+/// single `git status`. At most `max_scan` files are looked at.
+pub(crate) fn worktree_changes(
+    git: &crate::git::Git,
+    path: &Path,
+    max: usize,
+    max_scan: usize,
+) -> std::result::Result<(DirtyInfo, Vec<RecentFile>), String> {
+    let out = git.status_porcelain(path)?;
+    let mut dirty = parse_dirty(&out);
+    dirty.submodules = changed_submodules(git, path)?.len();
+    Ok((dirty, recent_from_porcelain(path, &out, max, max_scan)))
+}
+
+fn parse_dirty(out: &str) -> DirtyInfo {
     let mut staged = 0;
     let mut unstaged = 0;
     let mut untracked = 0;
@@ -921,7 +2588,7 @@ pub(crate) fn dirty_files(
         if line.starts_with("??") {
             untracked += 1;
         } else {
-            if line.chars().nth(0).unwrap_or(' ') != ' ' {
+            if line.chars().next().unwrap_or(' ') != ' ' {
                 staged += 1;
             }
             if line.chars().nth(1).unwrap_or(' ') != ' ' {
@@ -929,46 +2596,58 @@ pub(crate) fn dirty_files(
             }
         }
     }
-    Ok(DirtyInfo {
+    DirtyInfo {
         total: staged + unstaged + untracked,
         staged,
         unstaged,
         untracked,
-    })
+        submodules: 0,
+    }
 }
 
-fn last_commit_info(git: &crate::git::Git, path: &Path) -> Option<(i64, String)> {
-    let out = git.run_in(path, &["log", "-1", "--format=%ct|%s"]).ok()?;
-    let mut parts = out.trim().splitn(2, '|');
-    let ts = parts.next().unwrap_or("").trim();
-    let subject = parts.next().unwrap_or("").trim();
-    let ts_num: i64 = ts.parse().unwrap_or(0);
-    Some((ts_num, subject.to_string()))
+fn stash_count(counts: &std::collections::HashMap<String, usize>, wt: &Worktree) -> usize {
+    wt.branch
+        .as_ref()
+        .and_then(|b| counts.get(&short_branch(b)))
+        .copied()
+        .unwrap_or(0)
 }
 
 fn last_commit_unix(git: &crate::git::Git, path: &Path) -> Option<i64> {
-    let out = git.run_in(path, &["log", "-1", "--format=%ct"]).ok()?;
-    out.trim().parse().ok()
+    git.last_commit(path).map(|(time, _)| time)
 }
 
 pub(crate) fn worktree_name_with_config(ctx: &Context, path: &Path) -> Option<String> {
     let worktrees_dir = ctx.repo_root.join(ctx.config.worktrees_dir());
-    let worktrees_dir = worktrees_dir.canonicalize().unwrap_or(worktrees_dir);
-    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-    let rel = path.strip_prefix(&worktrees_dir).ok()?;
-    Some(rel.to_string_lossy().to_string())
+    crate::paths::relative_to(path, &worktrees_dir)
+        .or_else(|| crate::paths::relative_to(path, &tmp_worktrees_dir(ctx)))
+        .or_else(|| {
+            ctx.meta.all().iter().find_map(|(name, meta)| {
+                let adopted = meta.path.as_deref()?;
+                crate::paths::same_path(Path::new(adopted), path).then(|| name.clone())
+            })
+        })
+}
+
+/// Where `gw add --tmp` puts this repository's worktrees: `<tmp_dir>/<repo>`.
+pub(crate) fn tmp_worktrees_dir(ctx: &Context) -> PathBuf {
+    let repo = ctx
+        .repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    ctx.config.tmp_dir().join(repo)
 }
 
 fn worktree_display_name(ctx: &Context, path: &Path, root: &Path) -> String {
-    if let Ok(canon) = path.canonicalize() {
-        if canon == root {
-            return "root".to_string();
-        }
+    if crate::paths::same_path(path, root) {
+        return "root".to_string();
     }
     worktree_name_with_config(ctx, path).unwrap_or_else(|| path.to_string_lossy().to_string())
 }
 
-fn merge_mode(merge: bool, squash: bool, rebase: bool) -> String {
+/// Pick the strategy from CLI flags, falling back to the configured `default`.
+fn merge_mode(merge: bool, squash: bool, rebase: bool, default: String) -> String {
     if squash {
         return "squash".to_string();
     }
@@ -978,37 +2657,174 @@ fn merge_mode(merge: bool, squash: bool, rebase: bool) -> String {
     if merge {
         return "merge".to_string();
     }
-    "merge".to_string()
+    default
 }
 
-pub(crate) fn run_shell(cmd: &str, dir: &Path) -> std::result::Result<bool, String> {
-    let status = if cfg!(windows) {
-        Command::new("cmd")
-            .arg("/C")
-            .arg(cmd)
-            .current_dir(dir)
-            .status()
-    } else {
-        Command::new("sh")
-            .arg("-c")
-            .arg(cmd)
-            .current_dir(dir)
-            .status()
-    }
-    .map_err(|e| format!("command failed: {}", e))?;
+/// Run `cmd` in `dir` through `shell` (program plus leading args, see `[exec] shell`)
+/// with `envs` added to the environment.
+pub(crate) fn run_shell(
+    shell: &[String],
+    cmd: &str,
+    dir: &Path,
+    envs: &[(String, String)],
+) -> std::result::Result<bool, String> {
+    let (program, shell_args) = shell
+        .split_first()
+        .ok_or_else(|| "empty [exec] shell".to_string())?;
+    let status = Command::new(program)
+        .args(shell_args)
+        .arg(cmd)
+        .current_dir(dir)
+        .envs(envs.iter().map(|(k, v)| (k, v)))
+        .status()
+        .map_err(|e| format!("command failed: {}: {}", program, e))?;
     Ok(status.success())
 }
 
-fn is_locked(repo_root: &Path, name: &str) -> bool {
+/// Like `run_shell`, but hand the terminal over to `cmd` for interactive use.
+///
+/// On unix gw replaces itself with the shell, so the command owns the TTY and
+/// its signals and gw exits with its status. Elsewhere gw waits and exits with
+/// the command's code. Only returns if the command could not be started.
+pub(crate) fn exec_shell(
+    shell: &[String],
+    cmd: &str,
+    dir: &Path,
+    envs: &[(String, String)],
+) -> String {
+    let Some((program, shell_args)) = shell.split_first() else {
+        return "empty [exec] shell".to_string();
+    };
+    let mut command = Command::new(program);
+    command
+        .args(shell_args)
+        .arg(cmd)
+        .current_dir(dir)
+        .envs(envs.iter().map(|(k, v)| (k, v)));
+    #[cfg(unix)]
+    let err = std::os::unix::process::CommandExt::exec(&mut command);
+    #[cfg(not(unix))]
+    let err = match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => e,
+    };
+    format!("command failed: {}: {}", program, err)
+}
+
+pub(crate) fn is_locked(repo_root: &Path, name: &str) -> bool {
     lock_path(repo_root, name).exists()
 }
 
+/// Reason recorded with `gw lock --reason`; `None` when there is none.
+pub(crate) fn lock_reason(repo_root: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(lock_path(repo_root, name))
+        .ok()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+}
+
+/// "'name' is locked", with the lock reason when one was given.
+pub(crate) fn locked_message(repo_root: &Path, name: &str) -> String {
+    match lock_reason(repo_root, name) {
+        Some(reason) => format!("'{}' is locked: {}", name, reason),
+        None => format!("'{}' is locked", name),
+    }
+}
+
+/// "locked 'name'", with the lock reason in parentheses, for skip notices.
+pub(crate) fn locked_label(repo_root: &Path, name: &str) -> String {
+    match lock_reason(repo_root, name) {
+        Some(reason) => format!("locked '{}' ({})", name, reason),
+        None => format!("locked '{}'", name),
+    }
+}
+
 fn lock_path(repo_root: &Path, name: &str) -> PathBuf {
-    repo_root.join(".gw").join("locks").join(format!("{}.lock", name))
+    repo_root
+        .join(".gw")
+        .join("locks")
+        .join(format!("{}.lock", name))
+}
+
+fn worktree_owner<'a>(ctx: &'a Context, name: &str) -> Option<&'a str> {
+    ctx.meta.get(name).and_then(|m| m.owner.as_deref())
+}
+
+fn has_owners(ctx: &Context) -> bool {
+    ctx.meta.all().values().any(|m| m.owner.is_some())
+}
+
+/// User part of the `created_by` recorded at add time (`user@host` -> `user`).
+fn worktree_creator<'a>(ctx: &'a Context, name: &str) -> Option<&'a str> {
+    ctx.meta
+        .get(name)
+        .and_then(|m| m.created_by.as_deref())
+        .and_then(|c| c.split('@').next())
+}
+
+/// Whether someone other than the current user created a worktree, so the
+/// creator is worth a column in list/status.
+fn has_other_creators(ctx: &Context) -> bool {
+    let user = crate::meta::current_user();
+    ctx.meta
+        .all()
+        .values()
+        .filter_map(|m| m.created_by.as_deref())
+        .any(|c| !user_matches(c, &user))
+}
+
+/// Owners and creators may be recorded as "user" or "user@host".
+fn user_matches(recorded: &str, user: &str) -> bool {
+    recorded == user || recorded.split('@').next() == Some(user)
+}
+
+/// Whether a worktree belongs to another user: assigned to someone else, or
+/// unassigned and created by someone else.
+pub(crate) fn is_others(ctx: &Context, name: &str) -> bool {
+    let user = crate::meta::current_user();
+    let meta = ctx.meta.get(name);
+    match meta.and_then(|m| m.owner.as_deref().or(m.created_by.as_deref())) {
+        Some(who) => !user_matches(who, &user),
+        None => false,
+    }
+}
+
+/// Whether a worktree passes the `--mine` / `--owner` / `--user` filters.
+pub(crate) fn owner_matches(ctx: &Context, name: &str, filter: &OwnerFilterArgs) -> bool {
+    if let Some(ref want) = filter.owner {
+        if worktree_owner(ctx, name) != Some(want.as_str()) {
+            return false;
+        }
+    }
+    if let Some(ref want) = filter.user {
+        let created_by = ctx.meta.get(name).and_then(|m| m.created_by.as_deref());
+        if !created_by.is_some_and(|c| user_matches(c, want)) {
+            return false;
+        }
+    }
+    if filter.mine {
+        let meta = ctx.meta.get(name);
+        // An assignment takes precedence over who created the worktree
+        let who = meta.and_then(|m| m.owner.as_deref().or(m.created_by.as_deref()));
+        if !who.is_some_and(|w| user_matches(w, &crate::meta::current_user())) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Base branch recorded at add time, falling back to the configured/detected base.
+pub(crate) fn worktree_base(ctx: &Context, name: &str) -> Result<String> {
+    if let Some(base) = ctx.meta.get(name).and_then(|m| m.base.clone()) {
+        return Ok(base);
+    }
+    ctx.git
+        .resolve_base(&ctx.repo_root, ctx.config.default_base())
+        .map_err(git_error)
 }
 
 pub(crate) fn find_worktree(ctx: &Context, name: &str) -> Result<Option<Worktree>> {
-    let worktrees = ctx.git.worktrees().map_err(git_error)?;
+    let worktrees = ctx.worktrees()?;
     for wt in worktrees {
         if let Some(wt_name) = worktree_name_with_config(ctx, &wt.path) {
             if wt_name == name {
@@ -1019,6 +2835,60 @@ pub(crate) fn find_worktree(ctx: &Context, name: &str) -> Result<Option<Worktree
     Ok(None)
 }
 
+/// The main worktree, checked out at the repo root.
+pub(crate) fn root_worktree(ctx: &Context) -> Result<Worktree> {
+    let repo_root = crate::paths::canonical(&ctx.repo_root);
+    ctx.worktrees()?
+        .into_iter()
+        .find(|wt| crate::paths::same_path(&wt.path, &repo_root))
+        .ok_or_else(|| GwError::new(ErrorKind::NotFound, "repo root is not a worktree"))
+}
+
+/// Like `find_worktree`, but a missing worktree is an error suggesting close names.
+pub(crate) fn require_worktree(ctx: &Context, name: &str) -> Result<Worktree> {
+    if let Some(wt) = find_worktree(ctx, name)? {
+        return Ok(wt);
+    }
+    let worktrees = ctx.worktrees()?;
+    let names: Vec<String> = worktrees
+        .iter()
+        .filter_map(|wt| worktree_name_with_config(ctx, &wt.path))
+        .collect();
+    let suggestions = suggest_names(name, &names);
+    let message = match suggestions.len() {
+        0 => format!("worktree not found: {}", name),
+        1 => format!(
+            "worktree not found: {} (did you mean '{}'?)",
+            name, suggestions[0]
+        ),
+        _ => format!(
+            "worktree not found: {} (did you mean one of: {}?)",
+            name,
+            suggestions.join(", ")
+        ),
+    };
+    Err(GwError::new(ErrorKind::NotFound, message))
+}
+
+/// Close matches for a mistyped worktree name: prefix matches first, then by edit distance.
+fn suggest_names(input: &str, candidates: &[String]) -> Vec<String> {
+    let lower = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &String)> = candidates
+        .iter()
+        .filter_map(|c| {
+            let c_lower = c.to_lowercase();
+            if c_lower.starts_with(&lower) {
+                return Some((0, c));
+            }
+            let d = strsim::levenshtein(&lower, &c_lower);
+            (d <= max_distance).then_some((d, c))
+        })
+        .collect();
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    scored.into_iter().take(3).map(|(_, c)| c.clone()).collect()
+}
+
 fn resolve_subdir(
     wt_path: &Path,
     cli_root: bool,
@@ -1029,15 +2899,17 @@ fn resolve_subdir(
     if cli_root {
         return wt_path.to_path_buf();
     }
-    let subdir = cli_subdir
-        .or(meta_subdir)
-        .or(config_subdir);
+    let subdir = cli_subdir.or(meta_subdir).or(config_subdir);
     match subdir {
         Some(s) if !s.is_empty() => {
             let s = s.trim_start_matches('/');
             let target = wt_path.join(s);
             if !target.exists() {
-                eprintln!("warning: subdir '{}' does not exist in {}", s, wt_path.display());
+                eprintln!(
+                    "warning: subdir '{}' does not exist in {}",
+                    s,
+                    wt_path.display()
+                );
             }
             target
         }
@@ -1052,7 +2924,13 @@ pub(crate) fn resolve_worktree_dir(
     cli_root: bool,
     cli_subdir: Option<&str>,
 ) -> PathBuf {
-    let meta_subdir = ctx.meta.get(wt_name).and_then(|m| m.subdir.clone());
+    let cli_subdir = cli_subdir.map(|s| ctx.config.expand_subdir(s));
+    let cli_subdir = cli_subdir.as_deref();
+    let mut meta_subdir = ctx.meta.get(wt_name).and_then(|m| m.subdir.clone());
+    // An inferred subdir ranks below one recorded for the worktree, above the repo default
+    if meta_subdir.is_none() && cli_subdir.is_none() && !cli_root && ctx.config.auto_subdir() {
+        meta_subdir = changed::auto_subdir(ctx, wt_name, wt_path);
+    }
     let config_subdir = ctx.config.default_subdir();
     resolve_subdir(
         wt_path,
@@ -1063,34 +2941,99 @@ pub(crate) fn resolve_worktree_dir(
     )
 }
 
-fn branch_merged(git: &crate::git::Git, wt: &Worktree, repo_root: &Path) -> bool {
-    let base = git
-        .resolve_base(repo_root, None)
-        .unwrap_or_else(|_| "".to_string());
-    let branch = wt
-        .branch
-        .clone()
-        .unwrap_or_else(|| "".to_string())
-        .trim_start_matches("refs/heads/")
-        .to_string();
-    if base.is_empty() || branch.is_empty() {
-        return false;
-    }
-    git.run(&["branch", "--merged", &base])
-        .map(|out| out.lines().any(|line| line.trim() == branch))
-        .unwrap_or(false)
+/// Commits reachable from the worktree's HEAD but from no remote-tracking ref, as
+/// "<sha> <subject>" lines. Empty when the repository has no remotes.
+pub(crate) fn unpushed_commits(git: &crate::git::Git, path: &Path) -> Vec<String> {
+    let has_remote = git
+        .run(&["remote"])
+        .map(|out| !out.trim().is_empty())
+        .unwrap_or(false);
+    if !has_remote {
+        return Vec::new();
+    }
+    git.run_in(
+        path,
+        &["log", "--format=%h %s", "HEAD", "--not", "--remotes"],
+    )
+    .map(|out| out.lines().map(|l| l.to_string()).collect())
+    .unwrap_or_default()
 }
 
-fn recent_uncommitted(
-    git: &crate::git::Git,
-    path: &Path,
-    max: usize,
-) -> Vec<(String, char, i64)> {
-    let out = match git.run_in(path, &["status", "--porcelain"]) {
-        Ok(out) => out,
-        Err(_) => return Vec::new(),
+/// Whether the worktree has no branch (`gw add --detach`, or detached by `add --move`).
+pub(crate) fn is_detached(ctx: &Context, name: &str) -> bool {
+    ctx.meta.get(name).map(|m| m.detached).unwrap_or(false)
+}
+
+pub(crate) fn branch_merged(git: &crate::git::Git, wt: &Worktree, repo_root: &Path) -> bool {
+    wt.branch
+        .as_deref()
+        .is_some_and(|b| merged_branches(git, repo_root).contains(&short_branch(b)))
+}
+
+/// Local branches merged into the detected base, from a single `git branch --merged`.
+pub(crate) fn merged_branches(git: &crate::git::Git, repo_root: &Path) -> HashSet<String> {
+    let base = match git.resolve_base(repo_root, None) {
+        Ok(base) if !base.is_empty() => base,
+        _ => return HashSet::new(),
     };
-    let mut results = Vec::new();
+    git.run(&["branch", "--merged", &base])
+        // Branches checked out elsewhere are prefixed with '+', the current one with '*'
+        .map(|out| {
+            out.lines()
+                .map(|line| line.trim_start_matches(['*', '+', ' ']).to_string())
+                .filter(|b| !b.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Map `f` over `items` on a small thread pool, keeping the input order.
+/// Meant for per-worktree git calls, which mostly wait on child processes.
+pub(crate) fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is mapped"))
+        .collect()
+}
+
+fn recent_uncommitted(ctx: &Context, path: &Path, max: usize) -> Vec<(String, char, i64)> {
+    match ctx.git.status_porcelain(path) {
+        Ok(out) => recent_from_porcelain(path, &out, max, ctx.config.recent_files_max_scan()),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// The `max` newest of the changed files in porcelain `out`. Untracked
+/// directories (usually a dependency or build dir missing from .gitignore) are
+/// skipped, and only the first `max_scan` files are stat'ed, tracked changes
+/// before untracked ones, so a stray `node_modules/` full of files stays cheap.
+fn recent_from_porcelain(path: &Path, out: &str, max: usize, max_scan: usize) -> Vec<RecentFile> {
+    let mut tracked = Vec::new();
+    let mut untracked = Vec::new();
     for line in out.lines() {
         let line = line.trim_end();
         if line.len() < 3 {
@@ -1101,17 +3044,26 @@ fn recent_uncommitted(
         if file.is_empty() {
             continue;
         }
-        let status_char = if status == "??" {
-            '?'
-        } else if status.chars().next().unwrap_or(' ') != ' ' {
+        if status == "??" {
+            if !file.ends_with('/') {
+                untracked.push((file, '?'));
+            }
+            continue;
+        }
+        let status_char = if status.chars().next().unwrap_or(' ') != ' ' {
             status.chars().next().unwrap_or('?')
         } else {
             status.chars().nth(1).unwrap_or('?')
         };
-        let time = file_mtime(path, file);
-        results.push((file.to_string(), status_char, time));
-    }
-    results.sort_by(|a, b| b.2.cmp(&a.2));
+        tracked.push((file, status_char));
+    }
+    let mut results: Vec<RecentFile> = tracked
+        .into_iter()
+        .chain(untracked)
+        .take(max_scan)
+        .map(|(file, status)| (file.to_string(), status, file_mtime(path, file)))
+        .collect();
+    results.sort_by_key(|r| std::cmp::Reverse(r.2));
     results.truncate(max);
     results
 }
@@ -1133,10 +3085,22 @@ fn file_mtime(root: &Path, rel: &str) -> i64 {
     since.as_secs() as i64
 }
 
+/// `[ui] time_format`, set once at startup since pretty_time has no Context.
+static TIME_FORMAT: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+pub fn set_time_format(format: String) {
+    let _ = TIME_FORMAT.set(format);
+}
+
 fn pretty_time(ts: i64) -> String {
     if ts <= 0 {
         return "-".to_string();
     }
+    match TIME_FORMAT.get().map(|f| f.as_str()) {
+        Some("absolute") => return format_timestamp(ts, "%Y-%m-%d %H:%M"),
+        Some("iso") => return format_timestamp(ts, "%Y-%m-%dT%H:%M:%S%:z"),
+        _ => {}
+    }
     let now = Utc::now().timestamp();
     let diff = now.saturating_sub(ts);
     if diff < 60 {
@@ -1161,16 +3125,27 @@ fn short_branch(branch: &str) -> String {
 }
 
 fn format_changes(dirty: &DirtyInfo, detail: bool) -> String {
+    let submodules = match dirty.submodules {
+        0 => String::new(),
+        n => format!(" +{} sub", n),
+    };
     if detail {
         format!(
-            "{} ({}/{}/{})",
-            dirty.total, dirty.staged, dirty.unstaged, dirty.untracked
+            "{} ({}/{}/{}){}",
+            dirty.total, dirty.staged, dirty.unstaged, dirty.untracked, submodules
         )
     } else {
-        dirty.total.to_string()
+        format!("{}{}", dirty.total, submodules)
     }
 }
 
+/// A unix timestamp in local time.
+fn format_timestamp(ts: i64, format: &str) -> String {
+    DateTime::<Utc>::from_timestamp(ts, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
 fn truncate_text(input: &str, max_width: usize) -> String {
     let mut width = 0usize;
     let mut out = String::new();
@@ -1230,7 +3205,7 @@ fn print_table_box(
     println!("{}", border);
     println!("{}", table_row(&headers, &widths));
     println!("{}", border);
-    for (row, files) in rows.into_iter().zip(recent.into_iter()) {
+    for (row, files) in rows.into_iter().zip(recent) {
         let base = row;
         let files = if files.is_empty() {
             vec![("-".to_string(), '-', 0)]
@@ -1240,15 +3215,13 @@ fn print_table_box(
         for (idx, (file, status, ts)) in files.into_iter().enumerate() {
             let mut current = base.clone();
             if idx == 0 {
-                current[headers.len() - 1] =
-                    format!("{} {} ({})", status, file, pretty_time(ts));
+                current[headers.len() - 1] = format!("{} {} ({})", status, file, pretty_time(ts));
                 println!("{}", table_row(&current, &widths));
             } else {
                 for cell in current.iter_mut().take(headers.len() - 1) {
                     cell.clear();
                 }
-                current[headers.len() - 1] =
-                    format!("{} {} ({})", status, file, pretty_time(ts));
+                current[headers.len() - 1] = format!("{} {} ({})", status, file, pretty_time(ts));
                 println!("{}", table_row(&current, &widths));
             }
         }
@@ -1256,6 +3229,23 @@ fn print_table_box(
     }
 }
 
+fn print_table(headers: &[String], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (idx, cell) in row.iter().enumerate() {
+            widths[idx] = widths[idx].max(cell.len());
+        }
+    }
+    let border = table_border(&widths);
+    println!("{}", border);
+    println!("{}", table_row(headers, &widths));
+    println!("{}", border);
+    for row in rows {
+        println!("{}", table_row(row, &widths));
+    }
+    println!("{}", border);
+}
+
 fn pad_right(value: &str, width: usize) -> String {
     if value.len() >= width {
         return value.to_string();
@@ -1291,22 +3281,61 @@ fn table_row(cells: &[String], widths: &[usize]) -> String {
 fn bash_init() -> String {
     [
         "gw() {",
-        "  if [ \"$1\" = \"cd\" ]; then",
+        "  if [ \"$1\" = \"cd\" ] || [ \"$1\" = \"switch\" ]; then",
+        "    local sub=\"$1\"",
         "    shift",
         "    local target",
-        "    target=\"$(command gw cd \"$@\")\" && cd \"$target\"",
+        "    case \" $* \" in",
+        "      *\" --shell \"*|*\" --print-env \"*)",
+        "        target=\"$(command gw \"$sub\" \"$@\")\" && eval \"$target\"",
+        "        return",
+        "        ;;",
+        "    esac",
+        "    target=\"$(command gw \"$sub\" \"$@\")\" && [ -n \"$target\" ] && cd \"$target\"",
         "  else",
         "    command gw \"$@\"",
         "  fi",
         "}",
         "",
+        "# On every directory change: point GW_ROOT at the main checkout and record",
+        "# activity when the shell enters a worktree, however it got there",
+        "_gw_track() {",
+        "  [ \"$PWD\" = \"${_gw_last_pwd-}\" ] && return",
+        "  _gw_last_pwd=\"$PWD\"",
+        "  if GW_ROOT=\"$(command gw root 2>/dev/null)\"; then export GW_ROOT; else unset GW_ROOT; fi",
+        "  (command gw -q touch >/dev/null 2>&1 &)",
+        "}",
+        "if [ -n \"${ZSH_VERSION-}\" ]; then",
+        "  autoload -Uz add-zsh-hook && add-zsh-hook chpwd _gw_track",
+        "  _gw_track",
+        "else",
+        "  case \";${PROMPT_COMMAND-};\" in",
+        "    *\";_gw_track;\"*) ;;",
+        "    *) PROMPT_COMMAND=\"_gw_track${PROMPT_COMMAND:+;$PROMPT_COMMAND}\" ;;",
+        "  esac",
+        "fi",
+        "",
         "_gw_completions() {",
         "  local cur=\"${COMP_WORDS[COMP_CWORD]}\"",
+        "  local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"",
         "  if [ \"$COMP_CWORD\" -eq 1 ]; then",
-        "    COMPREPLY=($(compgen -W 'add new del rm list ls status st apply merge sync sy verify note info show lock lk unlock ul gc cd exec subdir config completion shell-init' -- \"$cur\"))",
+        "    COMPREPLY=($(compgen -W 'add new del rm rename mv list ls status st apply merge sync sy verify note info show lock lk unlock ul gc cd exec watch git subdir config stats summary log scan copy cp transfer stash assign daemon serve code env recent touch root browse switch completion shell-init' -- \"$cur\"))",
         "  else",
-        "    local words",
-        "    words=\"$(command gw _complete worktrees 2>/dev/null)\"",
+        "    local kind=worktrees",
+        "    case \"$prev\" in",
+        "      -b|--base|-B|--branch|-t|--target) kind=branches ;;",
+        "      --tag) kind=tags ;;",
+        "      --subdir) kind=subdirs ;;",
+        "    esac",
+        "    if [ \"${COMP_WORDS[1]}\" = \"subdir\" ] && [ \"$COMP_CWORD\" -eq 3 ]; then",
+        "      kind=subdirs",
+        "    fi",
+        "    local words extra=()",
+        "    if [ \"$kind\" = subdirs ]; then",
+        "      extra=(--worktree \"${COMP_WORDS[2]}\" --prefix \"$cur\")",
+        "      compopt -o nospace 2>/dev/null",
+        "    fi",
+        "    words=\"$(command gw _complete $kind \"${extra[@]}\" 2>/dev/null)\"",
         "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))",
         "  fi",
         "}",
@@ -1319,40 +3348,105 @@ fn bash_init() -> String {
 fn fish_init() -> String {
     [
         "function gw",
-        "  if test (count $argv) -ge 1; and test $argv[1] = \"cd\"",
+        "  if test (count $argv) -ge 1; and contains -- $argv[1] cd switch",
+        "    set -l sub $argv[1]",
         "    set -e argv[1]",
-        "    set -l target (command gw cd $argv)",
+        "    if contains -- --shell $argv; or contains -- --print-env $argv",
+        "      set -l script (command gw $sub $argv)",
+        "      and printf '%s\\n' $script | source",
+        "      return",
+        "    end",
+        "    set -l target (command gw $sub $argv)",
+        "    and test -n \"$target\"",
         "    and cd $target",
         "  else",
         "    command gw $argv",
         "  end",
         "end",
         "",
+        "function __gw_track --on-variable PWD",
+        "  if set -l root (command gw root 2>/dev/null)",
+        "    set -gx GW_ROOT $root",
+        "  else",
+        "    set -e GW_ROOT",
+        "  end",
+        "  command gw -q touch >/dev/null 2>&1 &",
+        "  disown 2>/dev/null",
+        "end",
+        "__gw_track",
+        "",
         "function __gw_worktrees",
         "  command gw _complete worktrees 2>/dev/null",
         "end",
         "",
+        "function __gw_branches",
+        "  command gw _complete branches 2>/dev/null",
+        "end",
+        "",
+        "function __gw_tags",
+        "  command gw _complete tags 2>/dev/null",
+        "end",
+        "",
+        "function __gw_subdirs",
+        "  set -l tokens (commandline -opc)",
+        "  command gw _complete subdirs --worktree \"$tokens[3]\" --prefix (commandline -ct) 2>/dev/null",
+        "end",
+        "",
         "complete -c gw -f",
         "complete -c gw -n __fish_use_subcommand -a 'add new a' -d 'Add worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'del rm d' -d 'Delete worktree'",
+        "complete -c gw -n __fish_use_subcommand -a 'rename mv' -d 'Rename worktree and branch'",
         "complete -c gw -n __fish_use_subcommand -a 'list ls' -d 'List worktrees'",
         "complete -c gw -n __fish_use_subcommand -a 'status st' -d 'Show status'",
         "complete -c gw -n __fish_use_subcommand -a 'apply merge ap' -d 'Apply worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'sync sy' -d 'Sync worktree'",
+        "complete -c gw -n __fish_use_subcommand -a 'train' -d 'Land worktrees in order'",
+        "complete -c gw -n __fish_use_subcommand -a 'conflicts' -d 'Conflict matrix'",
         "complete -c gw -n __fish_use_subcommand -a 'verify v' -d 'Verify worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'note n' -d 'Add note'",
+        "complete -c gw -n __fish_use_subcommand -a 'meta' -d 'Custom worktree fields'",
         "complete -c gw -n __fish_use_subcommand -a 'info show i' -d 'Show info'",
         "complete -c gw -n __fish_use_subcommand -a 'lock lk' -d 'Lock worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'unlock ul' -d 'Unlock worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'gc g' -d 'Garbage collect'",
         "complete -c gw -n __fish_use_subcommand -a 'cd c' -d 'Change directory'",
         "complete -c gw -n __fish_use_subcommand -a 'exec x' -d 'Execute command'",
+        "complete -c gw -n __fish_use_subcommand -a 'watch' -d 'Rerun command on changes'",
+        "complete -c gw -n __fish_use_subcommand -a 'git' -d 'Run git in a worktree'",
         "complete -c gw -n __fish_use_subcommand -a 'subdir' -d 'Manage subdir'",
+        "complete -c gw -n __fish_use_subcommand -a 'stats' -d 'Show worktree stats'",
+        "complete -c gw -n __fish_use_subcommand -a 'summary' -d 'Markdown summary of a worktree'",
+        "complete -c gw -n __fish_use_subcommand -a 'log' -d 'Show worktree commits'",
+        "complete -c gw -n __fish_use_subcommand -a 'copy cp' -d 'Copy worktree'",
+        "complete -c gw -n __fish_use_subcommand -a 'transfer' -d 'Move changes between worktrees'",
+        "complete -c gw -n __fish_use_subcommand -a 'stash' -d 'Manage worktree stashes'",
+        "complete -c gw -n __fish_use_subcommand -a 'backup' -d 'Back up .gw state'",
+        "complete -c gw -n __fish_use_subcommand -a 'restore' -d 'Restore .gw state'",
+        "complete -c gw -n __fish_use_subcommand -a 'assign' -d 'Assign worktree owner'",
+        "complete -c gw -n __fish_use_subcommand -a 'daemon' -d 'Refresh status cache'",
+        "complete -c gw -n __fish_use_subcommand -a 'serve' -d 'JSON-RPC server'",
+        "complete -c gw -n __fish_use_subcommand -a 'code' -d 'Open VS Code workspace'",
+        "complete -c gw -n __fish_use_subcommand -a 'env' -d 'Print worktree environment'",
+        "complete -c gw -n __fish_use_subcommand -a 'recent' -d 'Recently active worktrees'",
+        "complete -c gw -n __fish_use_subcommand -a 'touch' -d 'Mark worktree as active'",
+        "complete -c gw -n __fish_use_subcommand -a 'root' -d 'Print main checkout root'",
+        "complete -c gw -n __fish_use_subcommand -a 'browse' -d 'Open linked issue'",
+        "complete -c gw -n __fish_use_subcommand -a 'review' -d 'Diff worktree against base'",
+        "complete -c gw -n __fish_use_subcommand -a 'search' -d 'Search names, branches, notes, tags'",
+        "complete -c gw -n __fish_use_subcommand -a 'scan' -d 'Register worktrees made with plain git'",
+        "complete -c gw -n __fish_use_subcommand -a 'switch' -d 'Interactive worktree switcher'",
         "complete -c gw -n __fish_use_subcommand -a 'config' -d 'Show config'",
         "complete -c gw -n __fish_use_subcommand -a 'completion' -d 'Generate completions'",
         "complete -c gw -n __fish_use_subcommand -a 'shell-init' -d 'Shell integration'",
-        "complete -c gw -n '__fish_seen_subcommand_from cd c del rm d apply merge ap sync sy verify v note n info show i lock lk unlock ul subdir exec x' -a '(__gw_worktrees)'",
+        "complete -c gw -n '__fish_seen_subcommand_from cd c del rm d rename mv apply merge ap sync sy train verify v note n info show i lock lk unlock ul subdir exec x watch git log review summary env touch browse' -a '(__gw_worktrees)'",
         "complete -c gw -n '__fish_seen_subcommand_from exec x' -s w -l worktree -a '(__gw_worktrees)'",
+        "complete -c gw -n '__fish_seen_subcommand_from add new a' -s b -l base -a '(__gw_branches)'",
+        "complete -c gw -n '__fish_seen_subcommand_from add new a copy cp' -s B -l branch -a '(__gw_branches)'",
+        "complete -c gw -n '__fish_seen_subcommand_from add new a' -l tag -a '(__gw_tags)'",
+        "complete -c gw -n '__fish_seen_subcommand_from sync sy' -l base -a '(__gw_branches)'",
+        "complete -c gw -n '__fish_seen_subcommand_from apply merge ap train conflicts' -s t -l target -a '(__gw_branches)'",
+        "complete -c gw -n '__fish_seen_subcommand_from add new a cd c exec x watch git verify v code' -l subdir -a '(__gw_subdirs)'",
+        "complete -c gw -n '__fish_seen_subcommand_from subdir; and test (count (commandline -opc)) -eq 3' -a '(__gw_subdirs)'",
         "",
     ]
     .join("\n")
@@ -1362,24 +3456,50 @@ fn powershell_init() -> String {
     [
         "function gw {",
         "  param([Parameter(ValueFromRemainingArguments=$true)] $Args)",
-        "  if ($Args.Count -gt 0 -and $Args[0] -eq 'cd') {",
-        "    $target = & gw.exe cd $Args[1]",
-        "    if ($LASTEXITCODE -eq 0) { Set-Location $target }",
+        "  if ($Args.Count -gt 0 -and ($Args[0] -eq 'cd' -or $Args[0] -eq 'switch')) {",
+        "    if ($Args -contains '--shell' -or $Args -contains '--print-env') {",
+        "      $script = & gw.exe @Args",
+        "      if ($LASTEXITCODE -eq 0 -and $script) { Invoke-Expression ($script -join [Environment]::NewLine) }",
+        "      return",
+        "    }",
+        "    $target = & gw.exe @Args",
+        "    if ($LASTEXITCODE -eq 0 -and $target) { Set-Location -LiteralPath $target }",
         "  } else {",
         "    & gw.exe @Args",
         "  }",
         "}",
         "",
+        "if (-not $global:__gwPrompt) {",
+        "  $global:__gwPrompt = $function:prompt",
+        "  function global:prompt {",
+        "    if ($PWD.Path -ne $global:__gwLastPwd) {",
+        "      $global:__gwLastPwd = $PWD.Path",
+        "      $root = & gw.exe root 2>$null",
+        "      if ($LASTEXITCODE -eq 0 -and $root) { $env:GW_ROOT = $root } else { Remove-Item Env:GW_ROOT -ErrorAction SilentlyContinue }",
+        "      Start-Process -FilePath gw.exe -ArgumentList '-q','touch' -WindowStyle Hidden -ErrorAction SilentlyContinue",
+        "    }",
+        "    & $global:__gwPrompt",
+        "  }",
+        "}",
+        "",
         "Register-ArgumentCompleter -CommandName gw -ScriptBlock {",
         "  param($wordToComplete, $commandAst, $cursorPosition)",
-        "  $subcmds = @('add','new','del','rm','list','ls','status','st','apply','merge','sync','sy','verify','note','info','show','lock','lk','unlock','ul','gc','cd','exec','subdir','config','completion','shell-init')",
+        "  $subcmds = @('add','new','del','rm','rename','mv','list','ls','status','st','apply','merge','sync','sy','train','conflicts','verify','note','meta','info','show','lock','lk','unlock','ul','gc','cd','exec','watch','git','subdir','stats','summary','log','review','search','scan','copy','cp','transfer','stash','backup','restore','assign','daemon','serve','code','env','recent','touch','root','browse','switch','config','completion','shell-init')",
         "  $tokens = $commandAst.ToString() -split '\\s+'",
         "  if ($tokens.Count -le 2) {",
         "    $subcmds | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {",
         "      [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)",
         "    }",
         "  } else {",
-        "    $names = & gw.exe _complete worktrees 2>$null",
+        "    $prev = if ($wordToComplete) { $tokens[-2] } else { $tokens[-1] }",
+        "    $kind = switch -Regex ($prev) {",
+        "      '^(-b|--base|-B|--branch|-t|--target)$' { 'branches' }",
+        "      '^--tag$' { 'tags' }",
+        "      '^--subdir$' { 'subdirs' }",
+        "      default { 'worktrees' }",
+        "    }",
+        "    $extra = if ($kind -eq 'subdirs') { @('--worktree', $tokens[2], '--prefix', $wordToComplete) } else { @() }",
+        "    $names = & gw.exe _complete $kind @extra 2>$null",
         "    $names | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {",
         "      [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)",
         "    }",
@@ -1416,7 +3536,10 @@ fn install_shell_init(shell: clap_complete::Shell) -> Result<()> {
         clap_complete::Shell::Zsh => install_append("~/.zshrc", "eval \"$(gw shell-init zsh)\""),
         clap_complete::Shell::Fish => install_fish(),
         clap_complete::Shell::PowerShell => install_powershell(),
-        _ => Err(GwError::new(1, "unsupported shell for install")),
+        _ => Err(GwError::new(
+            ErrorKind::InvalidInput,
+            "unsupported shell for install",
+        )),
     }
 }
 
@@ -1432,14 +3555,14 @@ fn install_append(path: &str, line: &str) -> Result<()> {
     }
     new_content.push_str(line);
     new_content.push('\n');
-    std::fs::write(&path, new_content).map_err(|e| GwError::new(1, e.to_string()))
+    std::fs::write(&path, new_content).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
 }
 
 fn install_fish() -> Result<()> {
     let path = expand_home("~/.config/fish/conf.d/gw.fish");
     std::fs::create_dir_all(path.parent().unwrap())
-        .map_err(|e| GwError::new(1, e.to_string()))?;
-    std::fs::write(&path, fish_init()).map_err(|e| GwError::new(1, e.to_string()))
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    std::fs::write(&path, fish_init()).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
 }
 
 fn install_powershell() -> Result<()> {
@@ -1452,10 +3575,14 @@ fn install_powershell() -> Result<()> {
             .join("PowerShell")
             .join("Microsoft.PowerShell_profile.ps1")
     } else {
-        return Err(GwError::new(1, "unable to locate PowerShell profile"));
+        return Err(GwError::new(
+            ErrorKind::General,
+            "unable to locate PowerShell profile",
+        ));
     };
     if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| GwError::new(1, e.to_string()))?;
+        std::fs::create_dir_all(parent)
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
     }
     let line = "gw shell-init powershell | Invoke-Expression";
     let content = std::fs::read_to_string(&path).unwrap_or_default();
@@ -1468,7 +3595,7 @@ fn install_powershell() -> Result<()> {
     }
     new_content.push_str(line);
     new_content.push('\n');
-    std::fs::write(&path, new_content).map_err(|e| GwError::new(1, e.to_string()))
+    std::fs::write(&path, new_content).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
 }
 
 fn expand_home(path: &str) -> std::path::PathBuf {
@@ -1487,17 +3614,127 @@ fn expand_home(path: &str) -> std::path::PathBuf {
 mod tests {
     use super::*;
 
+    #[test]
+    fn subdir_candidates_lists_matching_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        for d in ["services/app", "services/api", "scripts", ".github", ".git"] {
+            fs::create_dir_all(dir.path().join(d)).unwrap();
+        }
+        fs::write(dir.path().join("services/README.md"), "").unwrap();
+        assert_eq!(
+            subdir_candidates(dir.path(), ""),
+            vec!["scripts/", "services/"]
+        );
+        assert_eq!(subdir_candidates(dir.path(), "se"), vec!["services/"]);
+        assert_eq!(
+            subdir_candidates(dir.path(), "services/ap"),
+            vec!["services/api/", "services/app/"]
+        );
+        assert_eq!(subdir_candidates(dir.path(), "."), vec![".github/"]);
+        assert!(subdir_candidates(dir.path(), "missing/").is_empty());
+    }
+
+    #[test]
+    fn parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+        assert_eq!(
+            parallel_map(&items, |n| n * 2),
+            items.iter().map(|n| n * 2).collect::<Vec<_>>()
+        );
+        assert!(parallel_map(&[] as &[u64], |n| *n).is_empty());
+    }
+
+    #[test]
+    fn with_trailers_appends_paragraph() {
+        let trailers = vec!["Gw-Worktree: pay".to_string(), "Gw-Base: main".to_string()];
+        assert_eq!(
+            with_trailers("Merge branch 'wt/pay'\n".to_string(), &trailers),
+            "Merge branch 'wt/pay'\n\nGw-Worktree: pay\nGw-Base: main"
+        );
+        assert_eq!(with_trailers("subject".to_string(), &[]), "subject");
+    }
+
+    #[test]
+    fn squash_summary_lists_subjects() {
+        assert_eq!(squash_summary("wt/pay", "Add pay\n"), "Add pay");
+        assert_eq!(
+            squash_summary("wt/pay", "Add pay\nFix pay\n"),
+            "Squash branch 'wt/pay'\n\n* Add pay\n* Fix pay\n"
+        );
+    }
+
+    #[test]
+    fn parse_submodule_changes_keeps_changed_submodules_only() {
+        let out = "\
+1 .M SC.. 160000 160000 160000 aaa bbb libs/core
+1 .M S.M. 160000 160000 160000 aaa aaa vendor/ui kit
+1 .M N... 100644 100644 100644 ccc ccc src/main.rs
+1 A. S... 000000 160000 160000 000 ddd libs/new
+? notes.txt
+";
+        assert_eq!(
+            parse_submodule_changes(out),
+            vec!["libs/core".to_string(), "vendor/ui kit".to_string()]
+        );
+    }
+
+    #[test]
+    fn recent_from_porcelain_caps_scan_and_skips_untracked_dirs() {
+        let out = "?? node_modules/\n?? notes.txt\n M src/a.rs\nA  src/b.rs\n";
+        let root = Path::new("/nonexistent");
+        let files: Vec<String> = recent_from_porcelain(root, out, 10, 10)
+            .into_iter()
+            .map(|(file, _, _)| file)
+            .collect();
+        assert_eq!(files, vec!["src/a.rs", "src/b.rs", "notes.txt"]);
+        let capped = recent_from_porcelain(root, out, 10, 2);
+        assert_eq!(capped.len(), 2);
+        assert!(capped.iter().all(|(_, status, _)| *status != '?'));
+    }
+
+    #[test]
+    fn stack_order_puts_parents_first() {
+        let nodes = vec![
+            ("wt/child".to_string(), Some("wt/parent".to_string())),
+            ("wt/other".to_string(), Some("main".to_string())),
+            ("wt/parent".to_string(), Some("main".to_string())),
+            ("wt/grandchild".to_string(), Some("wt/child".to_string())),
+        ];
+        assert_eq!(
+            stack_order(&nodes),
+            vec![(1, None), (2, None), (0, Some(2)), (3, Some(0))]
+        );
+
+        let cycle = vec![
+            ("a".to_string(), Some("b".to_string())),
+            ("b".to_string(), Some("a".to_string())),
+        ];
+        assert_eq!(stack_order(&cycle), vec![(0, None), (1, Some(0))]);
+    }
+
     #[test]
     fn resolve_subdir_cli_root_ignores_all() {
         let dir = PathBuf::from("/tmp/wt");
-        let result = resolve_subdir(&dir, true, Some("services/app"), Some("meta/path"), Some("cfg/path"));
+        let result = resolve_subdir(
+            &dir,
+            true,
+            Some("services/app"),
+            Some("meta/path"),
+            Some("cfg/path"),
+        );
         assert_eq!(result, PathBuf::from("/tmp/wt"));
     }
 
     #[test]
     fn resolve_subdir_cli_subdir_wins() {
         let dir = PathBuf::from("/tmp/wt");
-        let result = resolve_subdir(&dir, false, Some("cli/path"), Some("meta/path"), Some("cfg/path"));
+        let result = resolve_subdir(
+            &dir,
+            false,
+            Some("cli/path"),
+            Some("meta/path"),
+            Some("cfg/path"),
+        );
         assert_eq!(result, PathBuf::from("/tmp/wt/cli/path"));
     }
 
@@ -1536,6 +3773,18 @@ mod tests {
         assert_eq!(result, PathBuf::from("/tmp/wt"));
     }
 
+    #[test]
+    fn suggest_names_typo_and_prefix() {
+        let names = vec![
+            "payment".to_string(),
+            "payment-v2".to_string(),
+            "search".to_string(),
+        ];
+        assert_eq!(suggest_names("paymnet", &names), vec!["payment"]);
+        assert_eq!(suggest_names("pay", &names), vec!["payment", "payment-v2"]);
+        assert!(suggest_names("zzz", &names).is_empty());
+    }
+
     #[test]
     fn meta_json_backward_compat_no_subdir() {
         let json = r#"{"created_at":"2024-01-01","created_by":"user@host","notes":[],"tags":[]}"#;
@@ -1549,4 +3798,30 @@ mod tests {
         let meta: crate::meta::WorktreeMeta = serde_json::from_str(json).unwrap();
         assert_eq!(meta.subdir.unwrap(), "services/app");
     }
+
+    #[test]
+    fn user_matches_bare_and_host_qualified() {
+        assert!(user_matches("alice", "alice"));
+        assert!(user_matches("alice@build-01", "alice"));
+        assert!(!user_matches("alicia@build-01", "alice"));
+        assert!(!user_matches("bob", "alice"));
+    }
+
+    #[test]
+    fn copy_patterns_stay_inside_the_repo() {
+        assert!(is_inside_pattern(".env*"));
+        assert!(is_inside_pattern("config/local/*.toml"));
+        assert!(!is_inside_pattern("../secrets/.env"));
+        assert!(!is_inside_pattern("/etc/passwd"));
+    }
+
+    #[test]
+    fn resolve_lenient_keeps_missing_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("a").join("b");
+        assert_eq!(
+            resolve_lenient(&missing),
+            crate::paths::canonical(dir.path()).join("a").join("b")
+        );
+    }
 }
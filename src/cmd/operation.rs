@@ -0,0 +1,141 @@
+use crate::git::Git;
+use crate::{ErrorKind, GwError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A merge, rebase, cherry-pick, revert or am left in progress in a worktree.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Operation {
+    pub kind: &'static str,
+    /// (current, total) step of a rebase or am
+    pub step: Option<(usize, usize)>,
+}
+
+impl Operation {
+    pub fn describe(&self, conflicts: bool) -> String {
+        let mut out = self.kind.to_string();
+        if let Some((current, total)) = self.step {
+            out.push_str(&format!(" {}/{}", current, total));
+        }
+        if conflicts {
+            out.push_str(", conflicts");
+        }
+        out
+    }
+}
+
+/// Inspect a worktree's gitdir for the state files git leaves behind.
+pub(crate) fn operation_in(git_dir: &Path) -> Option<Operation> {
+    let rebase_merge = git_dir.join("rebase-merge");
+    if rebase_merge.is_dir() {
+        return Some(Operation {
+            kind: "rebase",
+            step: read_step(&rebase_merge, "msgnum", "end"),
+        });
+    }
+    let rebase_apply = git_dir.join("rebase-apply");
+    if rebase_apply.is_dir() {
+        let kind = if rebase_apply.join("applying").exists() {
+            "am"
+        } else {
+            "rebase"
+        };
+        return Some(Operation {
+            kind,
+            step: read_step(&rebase_apply, "next", "last"),
+        });
+    }
+    let heads = [
+        ("MERGE_HEAD", "merge"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+    ];
+    heads
+        .iter()
+        .find(|(file, _)| git_dir.join(file).exists())
+        .map(|(_, kind)| Operation { kind, step: None })
+}
+
+fn read_step(dir: &Path, current: &str, total: &str) -> Option<(usize, usize)> {
+    let read = |file: &str| -> Option<usize> {
+        fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()
+    };
+    Some((read(current)?, read(total)?))
+}
+
+fn git_dir(git: &Git, path: &Path) -> Option<PathBuf> {
+    // Read `.git` directly when we can; status calls this for every worktree
+    let dot_git = path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let linked = fs::read_to_string(&dot_git)
+        .ok()
+        .and_then(|s| s.strip_prefix("gitdir:").map(|d| PathBuf::from(d.trim())));
+    if let Some(dir) = linked {
+        return Some(if dir.is_absolute() {
+            dir
+        } else {
+            path.join(dir)
+        });
+    }
+    let out = git
+        .run_in(path, &["rev-parse", "--absolute-git-dir"])
+        .ok()?;
+    Some(crate::paths::from_git(&out))
+}
+
+/// Status text such as "rebase 3/7, conflicts" for a worktree, if an operation is in progress.
+pub(crate) fn pending_operation(git: &Git, path: &Path) -> Option<String> {
+    let op = operation_in(&git_dir(git, path)?)?;
+    let conflicts = git
+        .run_in(path, &["diff", "--name-only", "--diff-filter=U"])
+        .map(|out| !out.trim().is_empty())
+        .unwrap_or(false);
+    Some(op.describe(conflicts))
+}
+
+/// Refuse to start `verb` in a worktree that is mid-merge/rebase/cherry-pick.
+pub(crate) fn ensure_idle(git: &Git, name: &str, path: &Path, verb: &str) -> Result<()> {
+    match git_dir(git, path).and_then(|dir| operation_in(&dir)) {
+        Some(op) => Err(GwError::new(
+            ErrorKind::Conflict,
+            format!(
+                "cannot {} '{}': {} in progress; finish with `git {} --continue` or `git {} --abort`",
+                verb,
+                name,
+                op.describe(false),
+                op.kind,
+                op.kind
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(operation_in(dir.path()), None);
+
+        fs::write(dir.path().join("MERGE_HEAD"), "abc\n").unwrap();
+        assert_eq!(
+            operation_in(dir.path()).unwrap().describe(true),
+            "merge, conflicts"
+        );
+        fs::remove_file(dir.path().join("MERGE_HEAD")).unwrap();
+
+        let rebase = dir.path().join("rebase-merge");
+        fs::create_dir(&rebase).unwrap();
+        fs::write(rebase.join("msgnum"), "3\n").unwrap();
+        fs::write(rebase.join("end"), "7\n").unwrap();
+        assert_eq!(
+            operation_in(dir.path()).unwrap().describe(false),
+            "rebase 3/7"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::cli::GitArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::process::Command;
+
+/// Run `git <args>` inside a worktree (its subdir, unless `--root`) with the
+/// worktree environment, passing git's exit code through.
+pub fn git_cmd(ctx: &Context, args: GitArgs) -> Result<()> {
+    let name = super::recent::resolve_ref(ctx, &args.name)?;
+    let dir = super::resolve_cd_target(ctx, Some(&name), args.root, args.subdir.as_deref())?;
+    let wt = if name == "root" {
+        super::root_worktree(ctx)?
+    } else {
+        super::require_worktree(ctx, &name)?
+    };
+    let envs = super::env::worktree_env(ctx, &name, &wt);
+    if ctx.verbose {
+        eprintln!("git: {} in {}", args.args.join(" "), dir.display());
+    }
+    let status = Command::new("git")
+        .args(&args.args)
+        .current_dir(&dir)
+        .envs(envs)
+        .status()
+        .map_err(|e| GwError::new(ErrorKind::General, format!("failed to run git: {}", e)))?;
+    if !status.success() {
+        return Err(GwError::new(
+            ErrorKind::Custom(status.code().unwrap_or(1)),
+            "",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Scratch file under `.gw/tmp`, unique to this process.
+pub(crate) fn scratch_path(ctx: &Context, label: &str) -> Result<PathBuf> {
+    let dir = ctx.repo_root.join(".gw").join("tmp");
+    fs::create_dir_all(&dir).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    Ok(dir.join(format!("{}-{}", label, std::process::id())))
+}
+
+/// Write the worktree's uncommitted changes (staged and unstaged, plus untracked
+/// files when requested) to `out` as a binary-safe patch against HEAD.
+/// Returns false when there is nothing to transfer.
+pub(crate) fn write_uncommitted_patch(
+    ctx: &Context,
+    wt_path: &Path,
+    include_untracked: bool,
+    out: &Path,
+) -> Result<bool> {
+    let output = format!("--output={}", out.to_string_lossy());
+    if include_untracked {
+        // Stage everything into a throwaway index so the source index is untouched
+        let index = ctx
+            .git
+            .run_in(wt_path, &["rev-parse", "--git-path", "index"])
+            .map_err(git_error)?;
+        let index = wt_path.join(index.trim());
+        let tmp_index = scratch_path(ctx, "index")?;
+        if index.exists() {
+            fs::copy(&index, &tmp_index)
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        }
+        let tmp_index_str = tmp_index.to_string_lossy().to_string();
+        let env = [("GIT_INDEX_FILE", tmp_index_str.as_str())];
+        let result = ctx
+            .git
+            .run_in_env(wt_path, &["add", "-A"], &env)
+            .and_then(|_| {
+                ctx.git.run_in_env(
+                    wt_path,
+                    &["diff", "--cached", "--binary", "HEAD", &output],
+                    &env,
+                )
+            });
+        let _ = fs::remove_file(&tmp_index);
+        result.map_err(git_error)?;
+    } else {
+        ctx.git
+            .run_in(wt_path, &["diff", "--binary", "HEAD", &output])
+            .map_err(git_error)?;
+    }
+    let len = fs::metadata(out).map(|m| m.len()).unwrap_or(0);
+    Ok(len > 0)
+}
+
+/// Apply a patch in `wt_path`, refusing up front (without touching files) if it conflicts.
+pub(crate) fn apply_patch(ctx: &Context, wt_path: &Path, patch: &Path) -> Result<()> {
+    let patch_str = patch.to_string_lossy().to_string();
+    if let Err(err) = ctx.git.run_in(wt_path, &["apply", "--check", &patch_str]) {
+        return Err(GwError::new(
+            ErrorKind::Conflict,
+            format!("changes do not apply cleanly: {}", err),
+        ));
+    }
+    ctx.git
+        .run_in(wt_path, &["apply", &patch_str])
+        .map_err(git_error)?;
+    Ok(())
+}
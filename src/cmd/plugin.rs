@@ -0,0 +1,124 @@
+use crate::{Context, ErrorKind, GwError, Result};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run `gw-<name>` from PATH for an unknown subcommand, like git/cargo plugins.
+/// The plugin receives GW_REPO_ROOT, GW_CONFIG_PATH, GW_BIN, and a JSON
+/// description of the repository in GW_CONTEXT.
+pub fn plugin(ctx: &Context, argv: Vec<String>) -> Result<()> {
+    let (name, rest) = argv
+        .split_first()
+        .ok_or_else(|| GwError::new(ErrorKind::InvalidInput, "missing command"))?;
+    let program = match find_plugin(name) {
+        Some(p) => p,
+        None => return Err(unknown_command(name)),
+    };
+
+    let context = plugin_context(ctx)?;
+    let config_path = ctx.repo_root.join(".gw").join("config.toml");
+    let gw_bin = env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "gw".to_string());
+
+    let status = Command::new(&program)
+        .args(rest)
+        .env("GW_REPO_ROOT", &ctx.repo_root)
+        .env("GW_CONFIG_PATH", &config_path)
+        .env("GW_BIN", gw_bin)
+        .env("GW_CONTEXT", context)
+        .status()
+        .map_err(|e| {
+            GwError::new(
+                ErrorKind::General,
+                format!("failed to run {}: {}", program.display(), e),
+            )
+        })?;
+    if !status.success() {
+        return Err(GwError::new(
+            ErrorKind::Custom(status.code().unwrap_or(1)),
+            "",
+        ));
+    }
+    Ok(())
+}
+
+fn plugin_context(ctx: &Context) -> Result<String> {
+    let worktrees = ctx.worktrees()?;
+    let items: Vec<_> = worktrees
+        .iter()
+        .filter_map(|wt| {
+            let name = super::worktree_name_with_config(ctx, &wt.path)?;
+            Some(serde_json::json!({
+                "name": name,
+                "path": wt.path,
+                "branch": wt.branch.as_ref().map(|b| super::short_branch(b)),
+                "meta": ctx.meta.get(&name),
+            }))
+        })
+        .collect();
+    let value = serde_json::json!({
+        "repo_root": ctx.repo_root,
+        "worktrees_dir": ctx.repo_root.join(ctx.config.worktrees_dir()),
+        "current_worktree": ctx
+            .git
+            .current_toplevel()
+            .ok()
+            .and_then(|p| super::worktree_name_with_config(ctx, &p)),
+        "worktrees": items,
+    });
+    Ok(value.to_string())
+}
+
+fn find_plugin(name: &str) -> Option<PathBuf> {
+    let file = format!("gw-{}", name);
+    let path_var = env::var_os("PATH")?;
+    for dir in env::split_paths(&path_var) {
+        for candidate in plugin_candidates(&dir, &file) {
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+fn plugin_candidates(dir: &Path, file: &str) -> Vec<PathBuf> {
+    if cfg!(windows) {
+        ["exe", "cmd", "bat"]
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", file, ext)))
+            .collect()
+    } else {
+        vec![dir.join(file)]
+    }
+}
+
+fn unknown_command(name: &str) -> GwError {
+    use clap::CommandFactory;
+    let cmd = crate::cli::Cli::command();
+    let known: Vec<String> = cmd
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .flat_map(|c| {
+            std::iter::once(c.get_name().to_string())
+                .chain(c.get_visible_aliases().map(|a| a.to_string()))
+        })
+        .collect();
+    let suggestion = known
+        .iter()
+        .map(|k| (k, strsim::levenshtein(name, k)))
+        .filter(|(_, d)| *d <= 2)
+        .min_by_key(|(_, d)| *d)
+        .map(|(k, _)| k.clone());
+    match suggestion {
+        Some(s) => GwError::new(
+            ErrorKind::NotFound,
+            format!("unknown command '{}' (did you mean '{}'?)", name, s),
+        ),
+        None => GwError::new(
+            ErrorKind::NotFound,
+            format!("unknown command '{}' (no gw-{} found on PATH)", name, name),
+        ),
+    }
+}
@@ -0,0 +1,128 @@
+use super::{last_commit_unix, pretty_time, recent_uncommitted, worktree_name_with_config};
+use crate::cli::RecentArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use chrono::DateTime;
+use std::path::PathBuf;
+
+/// Worktrees ordered by last activity, newest first: the later of the last
+/// `gw cd` into it, its last commit, and its newest uncommitted change.
+pub(crate) fn recent_worktrees(ctx: &Context) -> Result<Vec<(String, PathBuf, i64)>> {
+    let mut items = Vec::new();
+    for wt in ctx.worktrees()? {
+        if wt.is_broken() {
+            continue;
+        }
+        let name = match worktree_name_with_config(ctx, &wt.path) {
+            Some(n) => n,
+            None => continue,
+        };
+        let recorded = ctx
+            .meta
+            .get(&name)
+            .and_then(|m| m.last_activity_at.as_deref())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0);
+        let commit = last_commit_unix(&ctx.git, &wt.path).unwrap_or(0);
+        let changed = recent_uncommitted(ctx, &wt.path, 1)
+            .first()
+            .map(|(_, _, ts)| *ts)
+            .unwrap_or(0);
+        items.push((name, wt.path, recorded.max(commit).max(changed)));
+    }
+    items.sort_by_key(|(_, _, ts)| std::cmp::Reverse(*ts));
+    Ok(items)
+}
+
+/// `@N` refers to the N-th entry of `gw recent` (1-based).
+pub(crate) fn parse_recent_index(name: &str) -> Option<usize> {
+    name.strip_prefix('@')?.parse().ok().filter(|n| *n > 0)
+}
+
+/// Resolve `@N` to a worktree name; anything else (including a worktree
+/// literally named `@N`) is returned unchanged.
+pub(crate) fn resolve_ref(ctx: &Context, name: &str) -> Result<String> {
+    match parse_recent_index(name) {
+        Some(index) if super::find_worktree(ctx, name)?.is_none() => nth_recent(ctx, index),
+        _ => Ok(name.to_string()),
+    }
+}
+
+fn nth_recent(ctx: &Context, index: usize) -> Result<String> {
+    let items = recent_worktrees(ctx)?;
+    items
+        .into_iter()
+        .nth(index - 1)
+        .map(|(name, _, _)| name)
+        .ok_or_else(|| {
+            GwError::new(
+                ErrorKind::NotFound,
+                format!("no worktree at @{} (see `gw recent`)", index),
+            )
+        })
+}
+
+pub fn recent(ctx: &Context, args: RecentArgs) -> Result<()> {
+    let items: Vec<_> = recent_worktrees(ctx)?
+        .into_iter()
+        .take(args.count)
+        .collect();
+    let current = ctx.git.current_toplevel().ok();
+
+    if ctx.json {
+        let out: Vec<_> = items
+            .iter()
+            .enumerate()
+            .map(|(i, (name, path, ts))| {
+                serde_json::json!({
+                    "index": i + 1,
+                    "name": name,
+                    "path": path.to_string_lossy(),
+                    "last_activity": ts,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        if !ctx.quiet {
+            println!("no worktrees");
+        }
+        return Ok(());
+    }
+    for (i, (name, path, ts)) in items.iter().enumerate() {
+        let is_current = current
+            .as_ref()
+            .map(|c| crate::paths::same_path(path, c))
+            .unwrap_or(false);
+        let mark = if is_current { "*" } else { " " };
+        println!(
+            "{} @{:<3} {:<16} {:<10} {}",
+            mark,
+            i + 1,
+            name,
+            pretty_time(*ts),
+            super::display_path(ctx, path)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_index_syntax() {
+        assert_eq!(parse_recent_index("@1"), Some(1));
+        assert_eq!(parse_recent_index("@12"), Some(12));
+        assert_eq!(parse_recent_index("@0"), None);
+        assert_eq!(parse_recent_index("@x"), None);
+        assert_eq!(parse_recent_index("foo"), None);
+    }
+}
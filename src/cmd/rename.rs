@@ -0,0 +1,170 @@
+use crate::cli::RenameArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+
+/// Rename a worktree in place: move its directory, rename the branch gw
+/// created for it, repair git's admin files and carry its metadata (notes,
+/// tags, subdir) and `.gw/worktrees/<name>.env` over to the new name.
+pub fn rename(ctx: &Context, args: RenameArgs) -> Result<()> {
+    let old = super::recent::resolve_ref(ctx, &args.name)?;
+    let new = args.new_name;
+    crate::names::validate_name(&new)?;
+    if super::is_locked(&ctx.repo_root, &old) {
+        return Err(GwError::new(
+            ErrorKind::Locked,
+            super::locked_message(&ctx.repo_root, &old),
+        ));
+    }
+    let wt = super::require_worktree(ctx, &old)?;
+    if wt.locked {
+        return Err(GwError::new(
+            ErrorKind::Locked,
+            format!(
+                "worktree is locked by git: {} (`git worktree unlock {}` first)",
+                old,
+                wt.path.display()
+            ),
+        ));
+    }
+    if super::find_worktree(ctx, &new)?.is_some() || ctx.meta.get(&new).is_some() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!("worktree already exists: {}", new),
+        ));
+    }
+    let new_path = wt
+        .path
+        .parent()
+        .map(|parent| parent.join(&new))
+        .ok_or_else(|| GwError::new(ErrorKind::General, "worktree has no parent directory"))?;
+    if new_path.exists() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!("path already exists: {}", new_path.display()),
+        ));
+    }
+
+    let branch = wt.branch.as_deref().map(super::short_branch);
+    let new_branch = if args.keep_branch {
+        None
+    } else {
+        branch.as_deref().and_then(|current| {
+            renamed_branch(
+                current,
+                args.branch,
+                &ctx.config.branch_prefix(),
+                &old,
+                &new,
+            )
+        })
+    };
+    if let Some(ref new_branch) = new_branch {
+        if ctx.git.branch_exists(new_branch) {
+            return Err(GwError::new(
+                ErrorKind::AlreadyExists,
+                format!("branch already exists: {}", new_branch),
+            ));
+        }
+    }
+
+    if let (Some(current), Some(new_branch)) = (&branch, &new_branch) {
+        ctx.git
+            .run(&["branch", "-m", current, new_branch])
+            .map_err(git_error)?;
+    }
+    let undo_branch = || {
+        if let (Some(current), Some(new_branch)) = (&branch, &new_branch) {
+            let _ = ctx.git.run(&["branch", "-m", new_branch, current]);
+        }
+    };
+    if let Err(e) = fs::rename(&wt.path, &new_path) {
+        undo_branch();
+        return Err(GwError::new(
+            ErrorKind::General,
+            format!("failed to move {}: {}", wt.path.display(), e),
+        ));
+    }
+    let new_path_str = new_path.to_string_lossy().to_string();
+    if let Err(e) = ctx.git.run(&["worktree", "repair", &new_path_str]) {
+        // Put the directory back where git's admin files still point
+        if fs::rename(&new_path, &wt.path).is_ok() {
+            undo_branch();
+        }
+        return Err(git_error(e));
+    }
+
+    let env_dir = ctx.repo_root.join(".gw").join("worktrees");
+    let old_env = env_dir.join(format!("{}.env", old));
+    if old_env.exists() {
+        if let Err(e) = fs::rename(&old_env, env_dir.join(format!("{}.env", new))) {
+            eprintln!("warning: failed to move {}: {}", old_env.display(), e);
+        }
+    }
+
+    let mut meta = ctx.meta.clone();
+    let mut entry = meta.get(&old).cloned().unwrap_or_default();
+    meta.remove(&old);
+    if entry.path.is_some() {
+        entry.path = Some(new_path_str);
+    }
+    if let Some(ref new_branch) = new_branch {
+        entry.branch = Some(new_branch.clone());
+    }
+    *meta.ensure(&new) = entry;
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+
+    if !ctx.quiet {
+        match new_branch {
+            Some(new_branch) => println!("renamed: {} -> {} (branch: {})", old, new, new_branch),
+            None => println!("renamed: {} -> {}", old, new),
+        }
+    }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    if cwd.starts_with(&wt.path) {
+        eprintln!(
+            "hint: your shell is still in the old path; run `gw cd {}`",
+            new
+        );
+    }
+    Ok(())
+}
+
+/// The branch to rename `current` to, if any: `explicit` when given,
+/// otherwise only a branch gw named after the worktree follows the rename.
+fn renamed_branch(
+    current: &str,
+    explicit: Option<String>,
+    prefix: &str,
+    old: &str,
+    new: &str,
+) -> Option<String> {
+    match explicit {
+        Some(explicit) => Some(explicit).filter(|b| b != current),
+        None if current == format!("{}{}", prefix, old) => Some(format!("{}{}", prefix, new)),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_gw_named_branches_follow_the_rename() {
+        assert_eq!(
+            renamed_branch("wt/bar", None, "wt/", "bar", "baz").as_deref(),
+            Some("wt/baz")
+        );
+        assert_eq!(renamed_branch("feature/x", None, "wt/", "bar", "baz"), None);
+        assert_eq!(
+            renamed_branch("feature/x", Some("feature/y".into()), "wt/", "bar", "baz").as_deref(),
+            Some("feature/y")
+        );
+        assert_eq!(
+            renamed_branch("wt/bar", Some("wt/bar".into()), "wt/", "bar", "baz"),
+            None
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use crate::cli::ReviewArgs;
+use crate::git::git_error;
+use crate::{Context, Result};
+
+/// Show what a worktree's branch changes relative to its base, through git's
+/// pager (or `[ui] pager`): the full diff, `--stat`, or `--files`.
+pub fn review(ctx: &Context, args: ReviewArgs) -> Result<()> {
+    let wt = super::require_worktree(ctx, &args.name)?;
+    let base = super::worktree_base(ctx, &args.name)?;
+    let merge_base = ctx
+        .git
+        .run_in(&wt.path, &["merge-base", "HEAD", &base])
+        .map_err(git_error)?;
+    let merge_base = merge_base.trim().to_string();
+
+    let pager = ctx.config.pager().map(|p| format!("core.pager={}", p));
+    let mut cmd_args: Vec<&str> = Vec::new();
+    if let Some(pager) = pager.as_deref() {
+        cmd_args.extend(["-c", pager]);
+    }
+    cmd_args.push("diff");
+    if args.stat {
+        cmd_args.push("--stat");
+    }
+    if args.files {
+        cmd_args.push("--name-status");
+    }
+    // Without a second revision git diffs the merge base against the working tree
+    cmd_args.push(&merge_base);
+    if !args.uncommitted {
+        cmd_args.push("HEAD");
+    }
+
+    let ok = ctx
+        .git
+        .run_passthrough(&wt.path, &cmd_args)
+        .map_err(git_error)?;
+    if !ok {
+        return Err(git_error(format!("git diff against {} failed", base)));
+    }
+    Ok(())
+}
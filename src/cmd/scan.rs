@@ -0,0 +1,126 @@
+use crate::cli::ScanArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Register worktrees created with plain `git worktree add` outside the
+/// worktrees dir, so gw commands can address them by name.
+pub fn scan(ctx: &Context, args: ScanArgs) -> Result<()> {
+    let unmanaged = unmanaged_worktrees(ctx)?;
+    if unmanaged.is_empty() {
+        if !ctx.quiet {
+            println!("no unregistered worktrees");
+        }
+        return Ok(());
+    }
+
+    let mut taken: HashSet<String> = ctx.meta.all().keys().cloned().collect();
+    for wt in ctx.worktrees()? {
+        if let Some(name) = super::worktree_name_with_config(ctx, &wt.path) {
+            taken.insert(name);
+        }
+    }
+    let mut meta = ctx.meta.clone();
+    for wt in &unmanaged {
+        let dir = wt
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Some(name) = import_name(&dir, &taken) else {
+            eprintln!(
+                "skipped: {} (cannot derive a worktree name)",
+                wt.path.display()
+            );
+            continue;
+        };
+        taken.insert(name.clone());
+        let branch = wt.branch.as_ref().map(|b| super::short_branch(b));
+        if !ctx.quiet {
+            println!(
+                "{}: {} -> {}{}",
+                if args.dry_run {
+                    "would register"
+                } else {
+                    "registered"
+                },
+                super::display_path(ctx, &wt.path),
+                name,
+                branch
+                    .as_deref()
+                    .map(|b| format!(" (branch: {})", b))
+                    .unwrap_or_default()
+            );
+        }
+        let entry = meta.ensure(&name);
+        entry.path = Some(wt.path.to_string_lossy().to_string());
+        if entry.created_at.is_none() {
+            entry.created_at = created_at(&wt.path);
+        }
+        match branch {
+            Some(branch) => entry.branch = Some(branch),
+            None => entry.detached = true,
+        }
+        meta.set_created(&name);
+    }
+    if args.dry_run {
+        return Ok(());
+    }
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+}
+
+/// Non-root worktrees gw has no name for.
+pub(crate) fn unmanaged_worktrees(ctx: &Context) -> Result<Vec<crate::git::Worktree>> {
+    Ok(ctx
+        .worktrees()?
+        .into_iter()
+        .filter(|wt| !wt.is_broken() && !crate::paths::same_path(&wt.path, &ctx.repo_root))
+        .filter(|wt| super::worktree_name_with_config(ctx, &wt.path).is_none())
+        .collect())
+}
+
+/// Worktree name for an adopted directory: the directory name itself when it
+/// is a valid name, its slug otherwise, with `-2`, `-3`... on collisions.
+fn import_name(dir: &str, taken: &HashSet<String>) -> Option<String> {
+    let name = if crate::names::validate_name(dir).is_ok() {
+        dir.to_string()
+    } else {
+        crate::names::slugify(dir)
+    };
+    if name.is_empty() || crate::names::validate_name(&name).is_err() {
+        return None;
+    }
+    if !taken.contains(&name) {
+        return Some(name);
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !taken.contains(candidate))
+}
+
+/// Best guess at when a worktree was made: its directory's creation time, or
+/// its modification time where the filesystem does not record one.
+fn created_at(path: &Path) -> Option<String> {
+    let meta = std::fs::metadata(path).ok()?;
+    let time = meta.created().or_else(|_| meta.modified()).ok()?;
+    Some(DateTime::<Utc>::from(time).to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_name_sanitizes_and_dedupes() {
+        let taken: HashSet<String> = ["api".to_string(), "api-2".to_string()].into();
+        assert_eq!(import_name("web", &taken).as_deref(), Some("web"));
+        assert_eq!(import_name("api", &taken).as_deref(), Some("api-3"));
+        assert_eq!(
+            import_name("My Feature", &taken).as_deref(),
+            Some("my-feature")
+        );
+        assert_eq!(import_name("..", &taken), None);
+    }
+}
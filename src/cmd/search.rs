@@ -0,0 +1,88 @@
+use crate::cli::SearchArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use regex::RegexBuilder;
+
+/// Find worktrees whose name, branch, notes, tags or meta fields match the query
+/// (case-insensitive substring, or a regex with `--regex`).
+pub fn search(ctx: &Context, args: SearchArgs) -> Result<()> {
+    let matcher: Box<dyn Fn(&str) -> bool> = if args.regex {
+        let re = RegexBuilder::new(&args.query)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| GwError::new(ErrorKind::InvalidInput, format!("invalid regex: {}", e)))?;
+        Box::new(move |text| re.is_match(text))
+    } else {
+        let needle = args.query.to_lowercase();
+        Box::new(move |text| text.to_lowercase().contains(&needle))
+    };
+
+    let mut results = Vec::new();
+    for wt in ctx.worktrees()? {
+        let Some(name) = super::worktree_name_with_config(ctx, &wt.path) else {
+            continue;
+        };
+        if !super::owner_matches(ctx, &name, &args.filter) {
+            continue;
+        }
+        let branch = wt.branch.as_deref().map(super::short_branch);
+        let meta = ctx.meta.get(&name);
+        let mut fields: Vec<(&str, String)> = vec![("name", name.clone())];
+        fields.extend(branch.clone().map(|b| ("branch", b)));
+        if let Some(meta) = meta {
+            fields.extend(meta.notes.iter().map(|n| ("note", n.clone())));
+            fields.extend(meta.tags.iter().map(|t| ("tag", t.clone())));
+            fields.extend(
+                meta.fields
+                    .iter()
+                    .map(|(k, v)| ("meta", format!("{}={}", k, v))),
+            );
+        }
+        let matches: Vec<(&str, String)> = fields
+            .into_iter()
+            .filter(|(_, value)| matcher(value))
+            .collect();
+        if !matches.is_empty() {
+            results.push((name, branch, wt.path, matches));
+        }
+    }
+
+    if ctx.json {
+        let out: Vec<_> = results
+            .iter()
+            .map(|(name, branch, path, matches)| {
+                let matches: Vec<_> = matches
+                    .iter()
+                    .map(|(field, value)| serde_json::json!({ "field": field, "value": value }))
+                    .collect();
+                serde_json::json!({
+                    "name": name,
+                    "branch": branch,
+                    "path": path.to_string_lossy(),
+                    "matches": matches,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        if !ctx.quiet {
+            println!("search: no matches for '{}'", args.query);
+        }
+        return Ok(());
+    }
+    for (name, branch, _, matches) in &results {
+        match branch {
+            Some(branch) => println!("{} ({})", name, branch),
+            None => println!("{}", name),
+        }
+        for (field, value) in matches {
+            println!("  {}: {}", field, value);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use crate::cli::{AddArgs, DelArgs, ServeArgs};
+use crate::meta::MetaStore;
+use crate::oplock::OpLock;
+use crate::{Context, ErrorKind, GwError, Result};
+use serde_json::{json, Value};
+
+/// Serve newline-delimited JSON-RPC 2.0 on a unix socket for editor integrations.
+///
+/// Methods: `list`, `status`, `add {name, base?, branch?, subdir?}`,
+/// `del {name, force?, delete_branch?}`, `resolve {name?, subdir?, root?}`.
+#[cfg(unix)]
+pub fn serve(ctx: &Context, args: ServeArgs) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    let socket = std::path::PathBuf::from(&args.socket);
+    clear_stale_socket(&socket)?;
+    let listener = UnixListener::bind(&socket).map_err(|e| {
+        GwError::new(
+            ErrorKind::General,
+            format!("failed to bind {}: {}", socket.display(), e),
+        )
+    })?;
+    if !ctx.quiet {
+        eprintln!("gw serve: listening on {}", socket.display());
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(err) => {
+                eprintln!("gw serve: accept failed: {}", err);
+                continue;
+            }
+        };
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let mut writer = match stream.try_clone() {
+                Ok(w) => w,
+                Err(_) => return,
+            };
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(_) => break,
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let response = handle_line(&ctx, &line);
+                if writeln!(writer, "{}", response).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(_ctx: &Context, _args: ServeArgs) -> Result<()> {
+    Err(GwError::new(
+        ErrorKind::General,
+        "gw serve requires unix domain sockets",
+    ))
+}
+
+/// A leftover socket from a crashed run blocks bind, so remove it, but only
+/// when it is a socket nobody is listening on; any other file is left alone.
+#[cfg(unix)]
+fn clear_stale_socket(socket: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    let Ok(meta) = std::fs::symlink_metadata(socket) else {
+        return Ok(());
+    };
+    if !meta.file_type().is_socket() {
+        return Err(GwError::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists and is not a socket", socket.display()),
+        ));
+    }
+    match UnixStream::connect(socket) {
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {}
+        _ => {
+            return Err(GwError::new(
+                ErrorKind::AlreadyExists,
+                format!("{} is in use by a running server", socket.display()),
+            ))
+        }
+    }
+    std::fs::remove_file(socket).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+}
+
+fn handle_line(ctx: &Context, line: &str) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return rpc_error(Value::Null, -32700, &format!("parse error: {}", e)),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = match request.get("method").and_then(|m| m.as_str()) {
+        Some(m) => m,
+        None => return rpc_error(id, -32600, "missing method"),
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    match dispatch(ctx, method, &params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => rpc_error(id, err.code(), &err.message),
+    }
+}
+
+fn rpc_error(id: Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn dispatch(ctx: &Context, method: &str, params: &Value) -> Result<Value> {
+    // Other gw processes may have changed the meta store or the repository since startup
+    let mut ctx = ctx.clone();
+    ctx.meta = MetaStore::new(&ctx.repo_root, &ctx.config.meta_backend())
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    ctx.git.clear_cache();
+    ctx.quiet = true;
+    // Requests come over the socket; nobody is at stdin to answer prompts
+    ctx.interactive = false;
+    let ctx = &ctx;
+
+    match method {
+        "list" => {
+            let worktrees = ctx.worktrees()?;
+            let items: Vec<Value> = worktrees
+                .iter()
+                .filter_map(|wt| {
+                    let name = super::worktree_name_with_config(ctx, &wt.path)?;
+                    Some(json!({
+                        "name": name,
+                        "path": wt.path,
+                        "branch": wt.branch.as_ref().map(|b| super::short_branch(b)),
+                    }))
+                })
+                .collect();
+            Ok(Value::Array(items))
+        }
+        "status" => {
+            let entries = super::status::collect_status(ctx, ctx.config.recent_files())?;
+            serde_json::to_value(entries)
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+        }
+        "add" => {
+            let name = str_param(params, "name")?
+                .ok_or_else(|| GwError::new(ErrorKind::Custom(-32602), "missing param: name"))?;
+            let _lock = OpLock::acquire(&ctx.repo_root, "add", ctx.config.lock_timeout())?;
+            super::add(
+                ctx,
+                AddArgs {
+                    name: Some(name.clone()),
+                    base: str_param(params, "base")?,
+                    branch: str_param(params, "branch")?,
+                    subdir: str_param(params, "subdir")?,
+                    ..Default::default()
+                },
+            )?;
+            let path = super::resolve_cd_target(ctx, Some(&name), true, None)?;
+            Ok(json!({ "name": name, "path": path }))
+        }
+        "del" => {
+            let name = str_param(params, "name")?
+                .ok_or_else(|| GwError::new(ErrorKind::Custom(-32602), "missing param: name"))?;
+            let _lock = OpLock::acquire(&ctx.repo_root, "del", ctx.config.lock_timeout())?;
+            super::del(
+                ctx,
+                DelArgs {
+                    name: name.clone(),
+                    force: bool_param(params, "force"),
+                    delete_branch: bool_param(params, "delete_branch"),
+                    force_unpushed: bool_param(params, "force_unpushed"),
+                },
+            )?;
+            Ok(json!({ "name": name }))
+        }
+        "resolve" => {
+            let name = str_param(params, "name")?;
+            let subdir = str_param(params, "subdir")?;
+            let path = super::resolve_cd_target(
+                ctx,
+                name.as_deref(),
+                bool_param(params, "root"),
+                subdir.as_deref(),
+            )?;
+            Ok(json!({ "path": path }))
+        }
+        _ => Err(GwError::new(
+            ErrorKind::Custom(-32601),
+            format!("unknown method: {}", method),
+        )),
+    }
+}
+
+fn str_param(params: &Value, key: &str) -> Result<Option<String>> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(GwError::new(
+            ErrorKind::Custom(-32602),
+            format!("param '{}' must be a string", key),
+        )),
+    }
+}
+
+fn bool_param(params: &Value, key: &str) -> bool {
+    params.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
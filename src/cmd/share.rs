@@ -0,0 +1,285 @@
+use crate::git::git_error;
+use crate::meta::WorktreeMeta;
+use crate::{Context, ErrorKind, GwError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Ref holding the team's shared annotations, one commit per push.
+const SHARED_REF: &str = "refs/gw/meta";
+const SHARED_FILE: &str = "meta.json";
+
+/// Contents of `meta.json` in `refs/gw/meta`. Worktree names are local to each
+/// machine, so annotations are keyed by branch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SharedMeta {
+    #[serde(default)]
+    branches: BTreeMap<String, SharedEntry>,
+}
+
+/// The part of `WorktreeMeta` that is meaningful to teammates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SharedEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subdir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<String, String>,
+}
+
+impl SharedEntry {
+    fn from_meta(meta: &WorktreeMeta) -> Self {
+        Self {
+            created_by: meta.created_by.clone(),
+            notes: meta.notes.clone(),
+            tags: meta.tags.clone(),
+            subdir: meta.subdir.clone(),
+            owner: meta.owner.clone(),
+            fields: meta.fields.clone(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+            && self.tags.is_empty()
+            && self.subdir.is_none()
+            && self.owner.is_none()
+            && self.fields.is_empty()
+    }
+
+    /// Fold `other` in: notes and tags are unioned, and values already set
+    /// here win over `other`'s.
+    fn merge(&mut self, other: &SharedEntry) {
+        for note in &other.notes {
+            if !self.notes.contains(note) {
+                self.notes.push(note.clone());
+            }
+        }
+        for tag in &other.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        if self.created_by.is_none() {
+            self.created_by = other.created_by.clone();
+        }
+        if self.subdir.is_none() {
+            self.subdir = other.subdir.clone();
+        }
+        if self.owner.is_none() {
+            self.owner = other.owner.clone();
+        }
+        for (key, value) in &other.fields {
+            self.fields
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+
+    fn apply_to(&self, meta: &mut WorktreeMeta) {
+        meta.created_by = self.created_by.clone();
+        meta.notes = self.notes.clone();
+        meta.tags = self.tags.clone();
+        meta.subdir = self.subdir.clone();
+        meta.owner = self.owner.clone();
+        meta.fields = self.fields.clone();
+    }
+}
+
+/// Merge local annotations into the remote's `refs/gw/meta` and push it.
+pub(crate) fn push(ctx: &Context, remote: &str) -> Result<()> {
+    let remote_tip = fetch(ctx, remote)?;
+    let before = read(ctx, remote_tip.as_deref())?;
+    let mut shared = before.clone();
+    for (name, branch) in local_branches(ctx)? {
+        let Some(meta) = ctx.meta.get(&name) else {
+            continue;
+        };
+        let mut entry = SharedEntry::from_meta(meta);
+        if let Some(theirs) = shared.branches.get(&branch) {
+            entry.merge(theirs);
+        }
+        if !entry.is_empty() {
+            shared.branches.insert(branch, entry);
+        }
+    }
+    if shared == before {
+        if !ctx.quiet {
+            println!("meta push: {} is up to date", remote);
+        }
+        return Ok(());
+    }
+    let commit = write_commit(ctx, &shared, remote_tip.as_deref())?;
+    let mut update = vec!["update-ref", SHARED_REF, commit.as_str()];
+    if let Some(ref tip) = remote_tip {
+        update.push(tip);
+    }
+    ctx.git.run(&update).map_err(git_error)?;
+    let refspec = format!("{}:{}", SHARED_REF, SHARED_REF);
+    ctx.git.run(&["push", remote, &refspec]).map_err(|e| {
+        GwError::new(
+            ErrorKind::Conflict,
+            format!(
+                "pushing {} to {} failed (a teammate may have pushed first; run gw meta push again): {}",
+                SHARED_REF, remote, e
+            ),
+        )
+    })?;
+    if !ctx.quiet {
+        println!(
+            "meta push: {} branch(es) shared on {}",
+            shared.branches.len(),
+            remote
+        );
+    }
+    Ok(())
+}
+
+/// Fetch `refs/gw/meta` and merge teammates' annotations into the local
+/// metadata of worktrees on the same branches.
+pub(crate) fn pull(ctx: &Context, remote: &str) -> Result<()> {
+    let Some(tip) = fetch(ctx, remote)? else {
+        if !ctx.quiet {
+            println!("meta pull: {} has no shared metadata yet", remote);
+        }
+        return Ok(());
+    };
+    let shared = read(ctx, Some(&tip))?;
+    let mut meta = ctx.meta.clone();
+    let mut updated = 0;
+    for (name, branch) in local_branches(ctx)? {
+        let Some(theirs) = shared.branches.get(&branch) else {
+            continue;
+        };
+        let local = meta.ensure(&name);
+        let mut entry = SharedEntry::from_meta(local);
+        entry.merge(theirs);
+        if entry != SharedEntry::from_meta(local) {
+            entry.apply_to(local);
+            updated += 1;
+        }
+    }
+    meta.save()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    if !ctx.quiet {
+        println!("meta pull: updated {} worktree(s) from {}", updated, remote);
+    }
+    Ok(())
+}
+
+/// (worktree name, branch) for every gw-managed worktree on a branch.
+fn local_branches(ctx: &Context) -> Result<Vec<(String, String)>> {
+    Ok(ctx
+        .worktrees()?
+        .into_iter()
+        .filter_map(|wt| {
+            let name = super::worktree_name_with_config(ctx, &wt.path)?;
+            let branch = super::short_branch(wt.branch.as_deref()?);
+            Some((name, branch))
+        })
+        .collect())
+}
+
+/// Fetch the remote's `refs/gw/meta` over the local one; `None` if it has none.
+fn fetch(ctx: &Context, remote: &str) -> Result<Option<String>> {
+    let listed = ctx
+        .git
+        .run(&["ls-remote", remote, SHARED_REF])
+        .map_err(git_error)?;
+    if listed.trim().is_empty() {
+        return Ok(None);
+    }
+    let refspec = format!("+{}:{}", SHARED_REF, SHARED_REF);
+    ctx.git
+        .run(&["fetch", "--quiet", remote, &refspec])
+        .map_err(git_error)?;
+    let tip = ctx
+        .git
+        .run(&["rev-parse", "--verify", SHARED_REF])
+        .map_err(git_error)?;
+    Ok(Some(tip.trim().to_string()))
+}
+
+fn read(ctx: &Context, commit: Option<&str>) -> Result<SharedMeta> {
+    let Some(commit) = commit else {
+        return Ok(SharedMeta::default());
+    };
+    let raw = ctx
+        .git
+        .run(&["cat-file", "blob", &format!("{}:{}", commit, SHARED_FILE)])
+        .map_err(git_error)?;
+    serde_json::from_str(&raw).map_err(|e| {
+        GwError::new(
+            ErrorKind::General,
+            format!("{} has an unreadable {}: {}", SHARED_REF, SHARED_FILE, e),
+        )
+    })
+}
+
+/// Commit `shared` as the only file of a tree, on top of `parent`.
+fn write_commit(ctx: &Context, shared: &SharedMeta, parent: Option<&str>) -> Result<String> {
+    let data = serde_json::to_string_pretty(shared)
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let file = super::patch::scratch_path(ctx, "shared-meta.json")?;
+    let index = super::patch::scratch_path(ctx, "shared-meta.index")?;
+    fs::write(&file, data).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let index_str = index.to_string_lossy().to_string();
+    let env = [("GIT_INDEX_FILE", index_str.as_str())];
+    let result = (|| {
+        let blob = ctx
+            .git
+            .run(&["hash-object", "-w", &file.to_string_lossy()])?;
+        let cacheinfo = format!("100644,{},{}", blob.trim(), SHARED_FILE);
+        ctx.git.run_in_env(
+            &ctx.repo_root,
+            &["update-index", "--add", "--cacheinfo", &cacheinfo],
+            &env,
+        )?;
+        let tree = ctx.git.run_in_env(&ctx.repo_root, &["write-tree"], &env)?;
+        let mut args = vec!["commit-tree", tree.trim(), "-m", "gw meta push"];
+        if let Some(parent) = parent {
+            args.extend(["-p", parent]);
+        }
+        ctx.git.run(&args)
+    })();
+    let _ = fs::remove_file(&file);
+    let _ = fs::remove_file(&index);
+    Ok(result.map_err(git_error)?.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_unions_lists_and_keeps_own_values() {
+        let mut mine = SharedEntry {
+            notes: vec!["a".into()],
+            owner: Some("me".into()),
+            ..Default::default()
+        };
+        let mut fields = BTreeMap::new();
+        fields.insert("ticket".to_string(), "PAY-1".to_string());
+        let theirs = SharedEntry {
+            notes: vec!["a".into(), "b".into()],
+            tags: vec!["wip".into()],
+            owner: Some("them".into()),
+            subdir: Some("svc".into()),
+            fields,
+            ..Default::default()
+        };
+        mine.merge(&theirs);
+        assert_eq!(mine.notes, vec!["a", "b"]);
+        assert_eq!(mine.tags, vec!["wip"]);
+        assert_eq!(mine.owner.as_deref(), Some("me"));
+        assert_eq!(mine.subdir.as_deref(), Some("svc"));
+        assert_eq!(mine.fields["ticket"], "PAY-1");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::cli::{StashAction, StashArgs};
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::collections::HashMap;
+
+/// A `git stash list` entry. Stashes are shared by all worktrees, so entries are
+/// attributed to a worktree through the branch recorded in the stash message.
+pub(crate) struct StashEntry {
+    pub reference: String,
+    pub branch: String,
+    pub subject: String,
+}
+
+pub fn stash(ctx: &Context, args: StashArgs) -> Result<()> {
+    match args.action {
+        StashAction::Push {
+            name,
+            message,
+            include_untracked,
+        } => {
+            let wt = super::require_worktree(ctx, &name)?;
+            let mut cmd_args = vec!["stash", "push"];
+            if include_untracked {
+                cmd_args.push("--include-untracked");
+            }
+            if let Some(ref message) = message {
+                cmd_args.push("-m");
+                cmd_args.push(message);
+            }
+            let out = ctx.git.run_in(&wt.path, &cmd_args).map_err(git_error)?;
+            if !ctx.quiet {
+                print!("{}", out);
+            }
+        }
+        StashAction::Pop { name, index } => {
+            let wt = super::require_worktree(ctx, &name)?;
+            let branch = worktree_branch(&wt);
+            let entries: Vec<StashEntry> = stash_entries(&ctx.git)
+                .into_iter()
+                .filter(|e| e.branch == branch)
+                .collect();
+            let entry = entries.get(index).ok_or_else(|| {
+                GwError::new(
+                    ErrorKind::NotFound,
+                    format!("no stash #{} for '{}'", index, name),
+                )
+            })?;
+            let out = ctx
+                .git
+                .run_in(&wt.path, &["stash", "pop", &entry.reference])
+                .map_err(|e| {
+                    GwError::new(ErrorKind::Conflict, format!("stash pop failed: {}", e))
+                })?;
+            if !ctx.quiet {
+                print!("{}", out);
+            }
+        }
+        StashAction::List { name } => {
+            let entries = stash_entries(&ctx.git);
+            let branch = match name {
+                Some(ref name) => {
+                    let wt = super::require_worktree(ctx, name)?;
+                    Some(worktree_branch(&wt))
+                }
+                None => None,
+            };
+            let mut index_by_branch: HashMap<&str, usize> = HashMap::new();
+            let mut items = Vec::new();
+            for entry in &entries {
+                let idx = index_by_branch.entry(&entry.branch).or_insert(0);
+                let local = *idx;
+                *idx += 1;
+                if branch
+                    .as_deref()
+                    .map(|b| b != entry.branch)
+                    .unwrap_or(false)
+                {
+                    continue;
+                }
+                items.push((local, entry));
+            }
+            if ctx.json {
+                let out: Vec<_> = items
+                    .iter()
+                    .map(|(idx, e)| {
+                        serde_json::json!({
+                            "index": idx,
+                            "ref": e.reference,
+                            "branch": e.branch,
+                            "subject": e.subject,
+                        })
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+                );
+            } else {
+                for (idx, e) in items {
+                    if branch.is_some() {
+                        println!("{}  {}", idx, e.subject);
+                    } else {
+                        println!("{}  {:<20} {}", e.reference, e.branch, e.subject);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn worktree_branch(wt: &crate::git::Worktree) -> String {
+    wt.branch
+        .as_ref()
+        .map(|b| super::short_branch(b))
+        .unwrap_or_else(|| "(no branch)".to_string())
+}
+
+pub(crate) fn stash_entries(git: &crate::git::Git) -> Vec<StashEntry> {
+    git.run(&["stash", "list", "--format=%gd%x09%gs"])
+        .map(|out| out.lines().filter_map(parse_stash_line).collect())
+        .unwrap_or_default()
+}
+
+/// Number of stash entries per branch name.
+pub(crate) fn stash_counts(git: &crate::git::Git) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for entry in stash_entries(git) {
+        *counts.entry(entry.branch).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn parse_stash_line(line: &str) -> Option<StashEntry> {
+    let (reference, subject) = line.split_once('\t')?;
+    // "WIP on <branch>: <sha> <msg>" or "On <branch>: <msg>"
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))?;
+    let (branch, _) = rest.split_once(": ")?;
+    Some(StashEntry {
+        reference: reference.to_string(),
+        branch: branch.to_string(),
+        subject: subject.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stash_line_wip_and_message() {
+        let wip = parse_stash_line("stash@{0}\tWIP on wt/foo: 1a2b3c4 fix").unwrap();
+        assert_eq!(wip.reference, "stash@{0}");
+        assert_eq!(wip.branch, "wt/foo");
+        let msg = parse_stash_line("stash@{1}\tOn wt/bar: half done").unwrap();
+        assert_eq!(msg.branch, "wt/bar");
+        assert!(parse_stash_line("garbage").is_none());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::cli::StatsArgs;
+use crate::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+struct WorktreeStats {
+    name: String,
+    branch: String,
+    base: String,
+    ahead: usize,
+    behind: usize,
+    added: usize,
+    removed: usize,
+    created_at: i64,
+    last_activity: i64,
+    verify_runs: u32,
+    verify_rate: Option<f64>,
+}
+
+pub fn stats(ctx: &Context, _args: StatsArgs) -> Result<()> {
+    let worktrees = ctx.worktrees()?;
+    let mut items = Vec::new();
+
+    for wt in worktrees {
+        let name = match super::worktree_name_with_config(ctx, &wt.path) {
+            Some(n) => n,
+            None => continue,
+        };
+        let base = match super::worktree_base(ctx, &name) {
+            Ok(base) => base,
+            Err(err) => {
+                if !ctx.quiet {
+                    eprintln!("stats: skipping {}: {}", name, err.message);
+                }
+                continue;
+            }
+        };
+        let (behind, ahead) = ahead_behind(&ctx.git, &wt.path, &base);
+        let (added, removed) = line_changes(&ctx.git, &wt.path, &base);
+        let meta = ctx.meta.get(&name);
+        let created_at = meta
+            .and_then(|m| m.created_at.as_deref())
+            .and_then(parse_ts)
+            .unwrap_or(0);
+        let last_commit = super::last_commit_unix(&ctx.git, &wt.path).unwrap_or(0);
+        let last_activity = meta
+            .and_then(|m| m.last_activity_at.as_deref())
+            .and_then(parse_ts)
+            .unwrap_or(0)
+            .max(last_commit);
+        let verify = meta.and_then(|m| m.verify.clone()).unwrap_or_default();
+        items.push(WorktreeStats {
+            name,
+            branch: wt
+                .branch
+                .as_ref()
+                .map(|b| super::short_branch(b))
+                .unwrap_or_default(),
+            base,
+            ahead,
+            behind,
+            added,
+            removed,
+            created_at,
+            last_activity,
+            verify_runs: verify.runs,
+            verify_rate: verify.pass_rate(),
+        });
+    }
+
+    if ctx.json {
+        let out: Vec<_> = items
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "name": s.name,
+                    "branch": s.branch,
+                    "base": s.base,
+                    "ahead": s.ahead,
+                    "behind": s.behind,
+                    "lines_added": s.added,
+                    "lines_removed": s.removed,
+                    "created_at": iso_time(s.created_at),
+                    "last_activity_at": iso_time(s.last_activity),
+                    "verify_runs": s.verify_runs,
+                    "verify_pass_rate": s.verify_rate,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&out).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    if items.is_empty() {
+        if !ctx.quiet {
+            println!("stats: no worktrees");
+        }
+        return Ok(());
+    }
+
+    let headers = vec![
+        "NAME".to_string(),
+        "BASE".to_string(),
+        "AHEAD/BEHIND".to_string(),
+        "LINES (+/-)".to_string(),
+        "CREATED".to_string(),
+        "LAST ACTIVITY".to_string(),
+        "VERIFY".to_string(),
+    ];
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|s| {
+            vec![
+                s.name.clone(),
+                s.base.clone(),
+                format!("{}/{}", s.ahead, s.behind),
+                format!("+{}/-{}", s.added, s.removed),
+                super::pretty_time(s.created_at),
+                super::pretty_time(s.last_activity),
+                format_verify(s.verify_runs, s.verify_rate),
+            ]
+        })
+        .collect();
+    super::print_table(&headers, &rows);
+    Ok(())
+}
+
+/// Returns (behind, ahead) of HEAD relative to `base`.
+pub(super) fn ahead_behind(git: &crate::git::Git, path: &Path, base: &str) -> (usize, usize) {
+    let range = format!("{}...HEAD", base);
+    let out = match git.run_in(path, &["rev-list", "--left-right", "--count", &range]) {
+        Ok(out) => out,
+        Err(_) => return (0, 0),
+    };
+    let mut parts = out.split_whitespace();
+    let behind = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+    let ahead = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+    (behind, ahead)
+}
+
+/// Sums added/removed lines of HEAD against its merge-base with `base`.
+fn line_changes(git: &crate::git::Git, path: &Path, base: &str) -> (usize, usize) {
+    let range = format!("{}...HEAD", base);
+    let out = match git.run_in(path, &["diff", "--numstat", &range]) {
+        Ok(out) => out,
+        Err(_) => return (0, 0),
+    };
+    parse_numstat(&out)
+}
+
+fn parse_numstat(out: &str) -> (usize, usize) {
+    let mut added = 0;
+    let mut removed = 0;
+    for line in out.lines() {
+        let mut parts = line.split('\t');
+        // Binary files report "-" for both counts
+        added += parts
+            .next()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        removed += parts
+            .next()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+    }
+    (added, removed)
+}
+
+fn format_verify(runs: u32, rate: Option<f64>) -> String {
+    match rate {
+        Some(rate) => format!("{:.0}% ({} runs)", rate * 100.0, runs),
+        None => "-".to_string(),
+    }
+}
+
+fn parse_ts(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+fn iso_time(ts: i64) -> Option<String> {
+    if ts <= 0 {
+        return None;
+    }
+    DateTime::<Utc>::from_timestamp(ts, 0).map(|dt| dt.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_numstat_sums_text_and_skips_binary() {
+        let out = "3\t1\tsrc/a.rs\n10\t0\tsrc/b.rs\n-\t-\tlogo.png\n";
+        assert_eq!(parse_numstat(out), (13, 1));
+    }
+
+    #[test]
+    fn format_verify_without_runs() {
+        assert_eq!(format_verify(0, None), "-");
+        assert_eq!(format_verify(4, Some(0.75)), "75% (4 runs)");
+    }
+}
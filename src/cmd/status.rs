@@ -0,0 +1,473 @@
+use super::{
+    format_changes, has_other_creators, has_owners, owner_matches, parallel_map, porcelain_line,
+    pretty_time, print_table_box, short_branch, stash, stash_count, truncate_text,
+    worktree_changes, worktree_creator, worktree_display_name, worktree_owner, DirtyInfo,
+};
+use crate::cli::StatusArgs;
+use crate::git::{git_error, BranchInfo};
+use crate::{Context, ErrorKind, GwError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Cached results older than this are ignored and status is computed live.
+const CACHE_MAX_AGE_SECS: i64 = 5 * 60;
+
+/// Everything `gw status` reports for one worktree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StatusEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub dirty: DirtyInfo,
+    pub stashes: usize,
+    /// Commits (ahead, behind) relative to @{upstream}, if the branch has one
+    pub upstream: Option<(usize, usize)>,
+    /// The branch tracks an upstream that was deleted on the remote
+    #[serde(default)]
+    pub upstream_gone: bool,
+    /// Directory missing or marked prunable by git; no other fields were collected
+    #[serde(default)]
+    pub broken: bool,
+    #[serde(default)]
+    pub locked: bool,
+    /// In-progress merge/rebase/cherry-pick, e.g. "rebase 3/7, conflicts"
+    #[serde(default)]
+    pub operation: Option<String>,
+    pub commit_time: i64,
+    pub commit_subject: String,
+    pub recent: Vec<(String, char, i64)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusCache {
+    updated_at: i64,
+    entries: Vec<StatusEntry>,
+}
+
+pub fn status(ctx: &Context, args: StatusArgs) -> Result<()> {
+    let recent = args.recent.unwrap_or_else(|| ctx.config.recent_files());
+    if args.fetch {
+        super::fetch_remotes(ctx);
+    }
+    let entries = if args.cached {
+        match load_cache(ctx) {
+            Some(entries) => entries,
+            None => {
+                if ctx.verbose {
+                    eprintln!("status: no fresh cache (is `gw daemon` running?), computing live");
+                }
+                collect_status(ctx, recent)?
+            }
+        }
+    } else {
+        collect_status(ctx, recent)?
+    };
+    let mut entries: Vec<StatusEntry> = entries
+        .into_iter()
+        .filter(|e| owner_matches(ctx, &e.name, &args.filter))
+        .collect();
+    // Porcelain output keeps `git worktree list` order regardless of [ui] settings
+    let sort = if args.porcelain {
+        "git".to_string()
+    } else {
+        ctx.config.status_sort()
+    };
+    match sort.as_str() {
+        "name" => entries.sort_by(|a, b| a.name.cmp(&b.name)),
+        "git" => {}
+        _ => entries.sort_by_key(|e| std::cmp::Reverse(last_activity(ctx, e))),
+    }
+
+    if args.porcelain {
+        for entry in &entries {
+            let dirty = &entry.dirty;
+            let (ahead, behind) = match entry.upstream {
+                Some((ahead, behind)) => (ahead.to_string(), behind.to_string()),
+                None => (String::new(), String::new()),
+            };
+            porcelain_line(&[
+                &entry.name,
+                entry.branch.as_deref().unwrap_or(""),
+                &dirty.total.to_string(),
+                &dirty.staged.to_string(),
+                &dirty.unstaged.to_string(),
+                &dirty.untracked.to_string(),
+                &entry.stashes.to_string(),
+                &ahead,
+                &behind,
+                &entry.commit_time.to_string(),
+                &entry.path.to_string_lossy(),
+                if entry.broken { "1" } else { "0" },
+                entry.operation.as_deref().unwrap_or(""),
+                worktree_creator(ctx, &entry.name).unwrap_or(""),
+                &dirty.submodules.to_string(),
+            ]);
+        }
+        return Ok(());
+    }
+
+    if args.short && !ctx.json {
+        let now = Utc::now().timestamp();
+        let width = entries
+            .iter()
+            .map(|e| e.name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for entry in &entries {
+            let activity = last_activity(ctx, entry);
+            let line = short_line(entry, (activity > 0).then(|| now - activity));
+            println!("{:<width$} {}", entry.name, line, width = width);
+        }
+        return Ok(());
+    }
+
+    if ctx.json {
+        let mut items = Vec::new();
+        for entry in &entries {
+            let dirty = &entry.dirty;
+            let last_change_time = entry.recent.first().map(|(_, _, ts)| *ts).unwrap_or(0);
+            let recent = recent_slice(entry, recent);
+            let recent_items: Vec<_> = recent
+                .iter()
+                .map(|(file, status, ts)| {
+                    serde_json::json!({
+                        "file": file,
+                        "status": status.to_string(),
+                        "time": pretty_time(*ts),
+                    })
+                })
+                .collect();
+            items.push(serde_json::json!({
+                "name": entry.name,
+                "branch": entry.branch,
+                "broken": entry.broken,
+                "operation": entry.operation,
+                "owner": worktree_owner(ctx, &entry.name),
+                "created_by": ctx.meta.get(&entry.name).and_then(|m| m.created_by.clone()),
+                "changes": if args.changes_detail {
+                    format!("{} ({}/{}/{})", dirty.total, dirty.staged, dirty.unstaged, dirty.untracked)
+                } else {
+                    dirty.total.to_string()
+                },
+                "submodules": dirty.submodules,
+                "stashes": entry.stashes,
+                "upstream_gone": entry.upstream_gone,
+                "ahead": entry.upstream.map(|(ahead, _)| ahead),
+                "behind": entry.upstream.map(|(_, behind)| behind),
+                "last_commit_time": pretty_time(entry.commit_time),
+                "last_commit_subject": entry.commit_subject,
+                "last_change_time": pretty_time(last_change_time),
+                "recent_files": recent_items,
+            }));
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&items).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    // Another terminal may be rewriting these worktrees right now
+    if let Some(holder) = crate::oplock::current(&ctx.repo_root) {
+        eprintln!("note: {}", holder.describe());
+    }
+    let show_owner = has_owners(ctx);
+    let show_creator = has_other_creators(ctx);
+    let show_operation = entries.iter().any(|e| e.operation.is_some());
+    let mut rows = Vec::new();
+    let mut recent_map = Vec::new();
+    let mut broken = Vec::new();
+    for entry in entries {
+        if entry.broken {
+            broken.push(entry.locked);
+        }
+        let owner = worktree_owner(ctx, &entry.name).unwrap_or("-").to_string();
+        let creator = worktree_creator(ctx, &entry.name)
+            .unwrap_or("-")
+            .to_string();
+        let commit_display = if entry.commit_time == 0 {
+            String::new()
+        } else {
+            let subject = truncate_text(&entry.commit_subject, ctx.config.subject_width());
+            format!("{} ({})", subject, pretty_time(entry.commit_time))
+        };
+        let last_change_time = entry.recent.first().map(|(_, _, ts)| *ts).unwrap_or(0);
+        let recent = recent_slice(&entry, recent).to_vec();
+        let last_change_display = if last_change_time == 0 {
+            "-".to_string()
+        } else {
+            pretty_time(last_change_time)
+        };
+        let branch = entry.branch.unwrap_or_else(|| "(detached)".to_string());
+        let mut row = vec![entry.name, branch];
+        if show_owner {
+            row.push(owner);
+        }
+        if show_creator {
+            row.push(creator);
+        }
+        if show_operation {
+            row.push(entry.operation.clone().unwrap_or_else(|| "-".to_string()));
+        }
+        row.extend([
+            if entry.broken {
+                "BROKEN".to_string()
+            } else {
+                format_changes(&entry.dirty, args.changes_detail)
+            },
+            if entry.stashes == 0 {
+                "-".to_string()
+            } else {
+                entry.stashes.to_string()
+            },
+            match entry.upstream {
+                Some((ahead, _)) => ahead.to_string(),
+                None if entry.upstream_gone => "gone".to_string(),
+                None => "no upstream".to_string(),
+            },
+            last_change_display,
+            commit_display,
+            String::new(),
+        ]);
+        rows.push(row);
+        recent_map.push(recent);
+    }
+
+    let mut headers = vec!["NAME".to_string(), "BRANCH".to_string()];
+    if show_owner {
+        headers.push("OWNER".to_string());
+    }
+    if show_creator {
+        headers.push("CREATED BY".to_string());
+    }
+    if show_operation {
+        headers.push("OPERATION".to_string());
+    }
+    headers.extend([
+        if args.changes_detail {
+            "CHANGES (ST/UN/??)".to_string()
+        } else {
+            "CHANGES".to_string()
+        },
+        "STASH".to_string(),
+        "PUSH".to_string(),
+        "LAST CHANGE".to_string(),
+        "LAST COMMIT".to_string(),
+        "RECENT FILES".to_string(),
+    ]);
+    if let Some(fetched) = ctx.git.last_fetch_time(&ctx.repo_root) {
+        println!("last fetch: {}", pretty_time(fetched));
+    }
+    if recent == 0 {
+        // `--recent 0` / `recent_files = 0` drop the RECENT FILES column
+        headers.pop();
+        for row in &mut rows {
+            row.pop();
+        }
+        super::print_table(&headers, &rows);
+    } else {
+        print_table_box(headers, rows, recent_map);
+    }
+    super::broken_hint(broken.len(), broken.iter().any(|locked| *locked));
+    Ok(())
+}
+
+/// Gather status for every worktree (root included), keeping up to `recent` files each.
+pub(crate) fn collect_status(ctx: &Context, recent: usize) -> Result<Vec<StatusEntry>> {
+    let worktrees = ctx.worktrees()?;
+    let root = crate::paths::canonical(&ctx.repo_root);
+    let stashes = stash::stash_counts(&ctx.git);
+    let branches = ctx.git.branch_infos();
+    // Only `git status` itself has to run per worktree; do those side by side
+    let changes = parallel_map(&worktrees, |wt| {
+        if wt.is_broken() {
+            return Ok(Default::default());
+        }
+        // One file is still needed for LAST CHANGE when RECENT FILES is hidden
+        worktree_changes(
+            &ctx.git,
+            &wt.path,
+            recent.max(1),
+            ctx.config.recent_files_max_scan(),
+        )
+    });
+    let mut entries = Vec::new();
+    for (wt, changes) in worktrees.into_iter().zip(changes) {
+        let name = worktree_display_name(ctx, &wt.path, &root);
+        let branch = wt.branch.as_ref().map(|b| short_branch(b));
+        if wt.is_broken() {
+            entries.push(StatusEntry {
+                name,
+                branch,
+                dirty: DirtyInfo::default(),
+                stashes: 0,
+                upstream: None,
+                upstream_gone: false,
+                broken: true,
+                locked: wt.locked,
+                operation: None,
+                commit_time: 0,
+                commit_subject: String::new(),
+                recent: Vec::new(),
+                path: wt.path,
+            });
+            continue;
+        }
+        let (dirty, recent) = changes.map_err(git_error)?;
+        let info = match branch.as_ref().and_then(|b| branches.get(b)) {
+            Some(info) => info.clone(),
+            // Detached HEAD: not covered by for-each-ref
+            None => {
+                let (commit_time, subject) = ctx.git.last_commit(&wt.path).unwrap_or_default();
+                BranchInfo {
+                    commit_time,
+                    subject,
+                    ..Default::default()
+                }
+            }
+        };
+        entries.push(StatusEntry {
+            name,
+            broken: false,
+            locked: wt.locked,
+            operation: super::operation::pending_operation(&ctx.git, &wt.path),
+            upstream_gone: info.upstream_gone,
+            branch,
+            dirty,
+            stashes: stash_count(&stashes, &wt),
+            upstream: info.upstream,
+            commit_time: info.commit_time,
+            commit_subject: info.subject,
+            recent,
+            path: wt.path,
+        });
+    }
+    Ok(entries)
+}
+
+/// Most recent of the last commit, the newest uncommitted change, and the
+/// activity recorded in meta.
+fn last_activity(ctx: &Context, entry: &StatusEntry) -> i64 {
+    let recorded = ctx
+        .meta
+        .get(&entry.name)
+        .and_then(|m| m.last_activity_at.as_deref())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0);
+    let changed = entry.recent.first().map(|(_, _, ts)| *ts).unwrap_or(0);
+    entry.commit_time.max(changed).max(recorded)
+}
+
+/// Dense summary for `--short`: `✚3 ≡1 ↑2↓0 rebase 2/5 2h`, omitting empty parts.
+fn short_line(entry: &StatusEntry, age_secs: Option<i64>) -> String {
+    if entry.broken {
+        return "BROKEN".to_string();
+    }
+    let mut parts = Vec::new();
+    if entry.dirty.total > 0 {
+        parts.push(format!("✚{}", entry.dirty.total));
+    }
+    if entry.dirty.submodules > 0 {
+        parts.push(format!("sub{}", entry.dirty.submodules));
+    }
+    if entry.stashes > 0 {
+        parts.push(format!("≡{}", entry.stashes));
+    }
+    match entry.upstream {
+        Some((ahead, behind)) => parts.push(format!("↑{}↓{}", ahead, behind)),
+        None if entry.upstream_gone => parts.push("gone".to_string()),
+        None => {}
+    }
+    if let Some(ref op) = entry.operation {
+        parts.push(op.replace(", conflicts", "!"));
+    }
+    parts.push(age_secs.map(short_age).unwrap_or_else(|| "-".to_string()));
+    parts.join(" ")
+}
+
+/// Compact age: `now`, `5m`, `2h`, `3d`, `6w`.
+fn short_age(secs: i64) -> String {
+    match secs {
+        s if s < 60 => "now".to_string(),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s if s < 86400 * 7 * 2 => format!("{}d", s / 86400),
+        s => format!("{}w", s / (86400 * 7)),
+    }
+}
+
+fn recent_slice(entry: &StatusEntry, max: usize) -> &[(String, char, i64)] {
+    &entry.recent[..entry.recent.len().min(max)]
+}
+
+fn cache_path(ctx: &Context) -> PathBuf {
+    ctx.repo_root.join(".gw").join("status-cache.json")
+}
+
+pub(crate) fn save_cache(ctx: &Context, entries: Vec<StatusEntry>) -> Result<()> {
+    let cache = StatusCache {
+        updated_at: Utc::now().timestamp(),
+        entries,
+    };
+    let data = serde_json::to_string(&cache)
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let path = cache_path(ctx);
+    // Write then rename so readers never see a partial file
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    fs::rename(&tmp, &path).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    Ok(())
+}
+
+fn load_cache(ctx: &Context) -> Option<Vec<StatusEntry>> {
+    let raw = fs::read_to_string(cache_path(ctx)).ok()?;
+    let cache: StatusCache = serde_json::from_str(&raw).ok()?;
+    if Utc::now().timestamp() - cache.updated_at > CACHE_MAX_AGE_SECS {
+        return None;
+    }
+    Some(cache.entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> StatusEntry {
+        StatusEntry {
+            name: "payment".to_string(),
+            path: PathBuf::from("/repo/.worktrees/payment"),
+            branch: Some("wt/payment".to_string()),
+            dirty: DirtyInfo::default(),
+            stashes: 0,
+            upstream: None,
+            upstream_gone: false,
+            broken: false,
+            locked: false,
+            operation: None,
+            commit_time: 0,
+            commit_subject: String::new(),
+            recent: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn short_line_omits_empty_parts() {
+        assert_eq!(short_line(&entry(), None), "-");
+        let mut e = entry();
+        e.dirty.total = 3;
+        e.upstream = Some((2, 0));
+        e.operation = Some("rebase 2/5, conflicts".to_string());
+        assert_eq!(short_line(&e, Some(2 * 3600 + 5)), "✚3 ↑2↓0 rebase 2/5! 2h");
+    }
+
+    #[test]
+    fn short_age_units() {
+        assert_eq!(short_age(30), "now");
+        assert_eq!(short_age(5 * 60), "5m");
+        assert_eq!(short_age(3 * 86400), "3d");
+        assert_eq!(short_age(30 * 86400), "4w");
+    }
+}
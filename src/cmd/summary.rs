@@ -0,0 +1,194 @@
+use crate::cli::SummaryArgs;
+use crate::git::Worktree;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fmt::Write as _;
+use std::fs;
+
+/// Everything `gw summary` reports for one worktree.
+#[derive(serde::Serialize)]
+struct Summary {
+    name: String,
+    branch: Option<String>,
+    base: String,
+    ahead: usize,
+    behind: usize,
+    commits: Vec<String>,
+    changes: super::DirtyInfo,
+    notes: Vec<String>,
+    verify: Option<String>,
+    issue_url: Option<String>,
+}
+
+/// Print (or with `--write`, save under `.gw/summaries/`) a markdown summary
+/// of a worktree, or a digest of all of them with `--all`.
+pub fn summary(ctx: &Context, args: SummaryArgs) -> Result<()> {
+    let mut summaries = Vec::new();
+    match args.name {
+        Some(ref name) if !args.all => {
+            let name = super::recent::resolve_ref(ctx, name)?;
+            let wt = super::require_worktree(ctx, &name)?;
+            summaries.push(collect(ctx, &name, &wt)?);
+        }
+        _ => {
+            for wt in ctx.worktrees()? {
+                let Some(name) = super::worktree_name_with_config(ctx, &wt.path) else {
+                    continue;
+                };
+                if wt.is_broken() || !super::owner_matches(ctx, &name, &args.filter) {
+                    continue;
+                }
+                summaries.push(collect(ctx, &name, &wt)?);
+            }
+        }
+    }
+    if summaries.is_empty() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            "no worktrees to summarize",
+        ));
+    }
+
+    if ctx.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summaries).unwrap_or("[]".to_string())
+        );
+        return Ok(());
+    }
+
+    let markdown = if args.all {
+        let date = chrono::Local::now().format("%Y-%m-%d");
+        let mut out = format!("# Worktree digest ({})\n", date);
+        for summary in &summaries {
+            out.push('\n');
+            out.push_str(&render(summary, "##"));
+        }
+        out
+    } else {
+        render(&summaries[0], "#")
+    };
+
+    if args.write {
+        let dir = ctx.repo_root.join(".gw").join("summaries");
+        let file = if args.all {
+            "digest.md".to_string()
+        } else {
+            format!("{}.md", summaries[0].name)
+        };
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(dir.join(&file), &markdown))
+            .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        if !ctx.quiet {
+            println!("wrote: {}", super::display_path(ctx, &dir.join(&file)));
+        }
+    } else {
+        print!("{}", markdown);
+    }
+    Ok(())
+}
+
+fn collect(ctx: &Context, name: &str, wt: &Worktree) -> Result<Summary> {
+    let base = super::worktree_base(ctx, name)?;
+    let (behind, ahead) = super::stats::ahead_behind(&ctx.git, &wt.path, &base);
+    let commits = ctx
+        .git
+        .run_in(
+            &wt.path,
+            &["log", "--format=%h %s", &format!("{}..HEAD", base)],
+        )
+        .map(|out| out.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default();
+    let changes = super::dirty_files(&ctx.git, &wt.path).unwrap_or_default();
+    let meta = ctx.meta.get(name);
+    let verify = meta.and_then(|m| m.verify.as_ref()).map(|v| {
+        let last = match v.last_passed {
+            Some(true) => "passed",
+            Some(false) => "failed",
+            None => "not run",
+        };
+        format!("{} ({}/{} runs passed)", last, v.passed, v.runs)
+    });
+    Ok(Summary {
+        name: name.to_string(),
+        branch: wt.branch.as_ref().map(|b| super::short_branch(b)),
+        base,
+        ahead,
+        behind,
+        commits,
+        changes,
+        notes: meta.map(|m| m.notes.clone()).unwrap_or_default(),
+        verify,
+        issue_url: meta.and_then(|m| m.issue_url.clone()),
+    })
+}
+
+/// Markdown for one worktree, headed at `level` (`#` alone, `##` in a digest).
+fn render(s: &Summary, level: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{} {}\n", level, s.name);
+    match s.branch {
+        Some(ref branch) => {
+            let _ = writeln!(out, "- Branch: `{}` (base `{}`)", branch, s.base);
+        }
+        None => {
+            let _ = writeln!(out, "- Branch: detached (base `{}`)", s.base);
+        }
+    }
+    let _ = writeln!(out, "- Commits: {} ahead, {} behind", s.ahead, s.behind);
+    let changes = if s.changes.total == 0 {
+        "clean".to_string()
+    } else {
+        format!(
+            "{} staged, {} unstaged, {} untracked",
+            s.changes.staged, s.changes.unstaged, s.changes.untracked
+        )
+    };
+    let _ = writeln!(out, "- Uncommitted: {}", changes);
+    let _ = writeln!(
+        out,
+        "- Verify: {}",
+        s.verify.as_deref().unwrap_or("not run")
+    );
+    if let Some(ref url) = s.issue_url {
+        let _ = writeln!(out, "- Issue: {}", url);
+    }
+    if !s.commits.is_empty() {
+        let _ = writeln!(out, "\n{}# Commits\n", level);
+        for commit in &s.commits {
+            let _ = writeln!(out, "- {}", commit);
+        }
+    }
+    if !s.notes.is_empty() {
+        let _ = writeln!(out, "\n{}# Notes\n", level);
+        for note in &s.notes {
+            let _ = writeln!(out, "- {}", note);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_lists_commits_and_skips_empty_sections() {
+        let summary = Summary {
+            name: "payments".into(),
+            branch: Some("wt/payments".into()),
+            base: "main".into(),
+            ahead: 2,
+            behind: 0,
+            commits: vec!["abc1234 Add retry".into()],
+            changes: super::super::DirtyInfo::default(),
+            notes: Vec::new(),
+            verify: None,
+            issue_url: None,
+        };
+        let out = render(&summary, "##");
+        assert!(out.starts_with("## payments\n\n- Branch: `wt/payments` (base `main`)\n"));
+        assert!(out.contains("- Uncommitted: clean\n- Verify: not run\n"));
+        assert!(out.contains("\n### Commits\n\n- abc1234 Add retry\n"));
+        assert!(!out.contains("Notes"));
+    }
+}
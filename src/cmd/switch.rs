@@ -0,0 +1,199 @@
+use super::{
+    format_changes, pretty_time, recent::recent_worktrees, require_worktree, short_branch,
+};
+use crate::cli::SwitchArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+pub fn switch(ctx: &Context, args: SwitchArgs) -> Result<()> {
+    if let Some(name) = args.preview {
+        return preview(ctx, &name);
+    }
+    if !can_pick() {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            "gw switch needs a terminal; use `gw cd <name>`",
+        ));
+    }
+
+    let name = match pick_worktree(ctx)? {
+        Some(name) => name,
+        // Cancelled: print nothing so the shell wrapper stays put
+        None => return Ok(()),
+    };
+
+    let target = super::resolve_cd_target(ctx, Some(&name), false, None)?;
+    if name != "root" {
+        let mut meta = ctx.meta.clone();
+        meta.set_last_activity(&name);
+        let _ = meta.save();
+    }
+    println!("{}", target.display());
+    Ok(())
+}
+
+/// Whether an interactive picker can run: stdout may be captured by the shell
+/// wrapper, but stdin and stderr must be a terminal.
+pub(crate) fn can_pick() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Let the user choose a worktree (or `root`), most recently active first,
+/// with fzf when installed. Entries show the branch so either can be searched.
+pub(crate) fn pick_worktree(ctx: &Context) -> Result<Option<String>> {
+    let worktrees = ctx.worktrees()?;
+    let mut items: Vec<(String, String)> = Vec::new();
+    for (name, path, ts) in recent_worktrees(ctx)? {
+        let branch = worktrees
+            .iter()
+            .find(|wt| wt.path == path)
+            .and_then(|wt| wt.branch.as_deref())
+            .map(short_branch)
+            .unwrap_or_else(|| "(detached)".to_string());
+        items.push((name, format!("{:<24} {}", branch, pretty_time(ts))));
+    }
+    let root_branch = ctx.git.current_branch(&ctx.repo_root).unwrap_or_default();
+    items.push(("root".to_string(), root_branch));
+
+    if fzf_available() {
+        pick_with_fzf(ctx, &items)
+    } else {
+        pick_with_prompt(ctx, &items)
+    }
+}
+
+fn fzf_available() -> bool {
+    Command::new("fzf")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+fn pick_with_fzf(ctx: &Context, items: &[(String, String)]) -> Result<Option<String>> {
+    let exe =
+        std::env::current_exe().map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let preview = format!(
+        "{} -C {} switch --preview {{1}}",
+        super::env::sh_quote(&exe.to_string_lossy()),
+        super::env::sh_quote(&ctx.repo_root.to_string_lossy()),
+    );
+    let mut child = Command::new("fzf")
+        .args([
+            "--delimiter",
+            "\t",
+            "--height",
+            "60%",
+            "--reverse",
+            "--preview-window",
+            "right:60%",
+        ])
+        .arg("--preview")
+        .arg(preview)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| GwError::new(ErrorKind::General, format!("failed to run fzf: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        for (name, when) in items {
+            let _ = writeln!(stdin, "{}\t{}", name, when);
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let selected = String::from_utf8_lossy(&output.stdout);
+    Ok(selected
+        .lines()
+        .next()
+        .and_then(|line| line.split('\t').next())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string()))
+}
+
+/// Numbered menu on stderr: a number selects, `p<N>` previews, empty input cancels.
+fn pick_with_prompt(ctx: &Context, items: &[(String, String)]) -> Result<Option<String>> {
+    let stdin = std::io::stdin();
+    loop {
+        for (i, (name, when)) in items.iter().enumerate() {
+            eprintln!("{:>3}) {:<16} {}", i + 1, name, when);
+        }
+        eprint!(
+            "switch to [1-{}, p<N> to preview, enter to cancel]: ",
+            items.len()
+        );
+        let _ = std::io::stderr().flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            return Ok(None);
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let (show, index) = match line.strip_prefix('p') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, line),
+        };
+        match index
+            .parse::<usize>()
+            .ok()
+            .filter(|i| *i >= 1 && *i <= items.len())
+        {
+            Some(i) if show => {
+                eprintln!();
+                eprint!("{}", preview_text(ctx, &items[i - 1].0)?);
+                eprintln!();
+            }
+            Some(i) => return Ok(Some(items[i - 1].0.clone())),
+            None => eprintln!("invalid choice: {}", line),
+        }
+    }
+}
+
+fn preview(ctx: &Context, name: &str) -> Result<()> {
+    print!("{}", preview_text(ctx, name)?);
+    Ok(())
+}
+
+/// Branch, change summary, diffstat and last commits of a worktree.
+fn preview_text(ctx: &Context, name: &str) -> Result<String> {
+    let (path, branch) = if name == "root" {
+        let branch = ctx.git.current_branch(&ctx.repo_root).unwrap_or_default();
+        (ctx.repo_root.clone(), branch)
+    } else {
+        let wt = require_worktree(ctx, name)?;
+        let branch = wt
+            .branch
+            .as_deref()
+            .map(short_branch)
+            .unwrap_or_else(|| "(detached)".to_string());
+        (wt.path, branch)
+    };
+    let dirty = super::dirty_files(&ctx.git, &path).map_err(git_error)?;
+    let diffstat = ctx
+        .git
+        .run_in(&path, &["diff", "--stat", "HEAD"])
+        .unwrap_or_default();
+    let log = ctx
+        .git
+        .run_in(&path, &["log", "--format=%h %s (%cr)", "-5"])
+        .unwrap_or_default();
+
+    let mut out = format!(
+        "{}  [{}]\n{}\n\nchanges: {}\n",
+        name,
+        branch,
+        super::display_path(ctx, &path),
+        format_changes(&dirty, true)
+    );
+    if !diffstat.trim().is_empty() {
+        out.push_str(&format!("\n{}", diffstat));
+    }
+    out.push_str(&format!("\nrecent commits:\n{}", log));
+    Ok(out)
+}
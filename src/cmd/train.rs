@@ -0,0 +1,220 @@
+use crate::cli::{ApplyArgs, TrainArgs, VerifyArgs};
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const STATE_FILE: &str = "train.json";
+
+/// A train stopped at a failed step, saved so `--continue` can pick it up.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+struct TrainState {
+    target: String,
+    mode: String,
+    /// Worktree applied (or mid-apply) whose verify has not passed yet
+    current: Option<String>,
+    pending: Vec<String>,
+    landed: Vec<String>,
+}
+
+/// Land worktrees into the target one at a time, verifying the target after
+/// each apply and stopping at the first conflict or failed verify.
+pub fn train(ctx: &Context, args: TrainArgs) -> Result<()> {
+    let path = state_path(ctx);
+    if args.abort {
+        let state = load_state(ctx)?;
+        fs::remove_file(&path).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        if !ctx.quiet {
+            println!(
+                "train: aborted; {} stays applied to {}",
+                describe(&state.landed),
+                state.target
+            );
+        }
+        return Ok(());
+    }
+
+    let mut state = if args.resume {
+        load_state(ctx)?
+    } else {
+        if path.exists() {
+            return Err(GwError::new(
+                ErrorKind::InvalidInput,
+                "a train is already in progress; use `gw train --continue` or `gw train --abort`",
+            ));
+        }
+        // Check every worktree first so a typo never stops the train halfway
+        for name in &args.names {
+            super::require_worktree(ctx, name)?;
+        }
+        let target = match args.target {
+            Some(target) => target,
+            None => ctx.git.current_branch(&ctx.repo_root).map_err(git_error)?,
+        };
+        super::check_protected(ctx, &target, args.allow_protected)?;
+        let state = TrainState {
+            target,
+            mode: super::merge_mode(
+                args.merge,
+                args.squash,
+                args.rebase,
+                ctx.config.apply_mode(),
+            ),
+            pending: args.names,
+            ..Default::default()
+        };
+        let summary = format!(
+            "land {} into {} ({}), verifying after each?",
+            state.pending.join(", "),
+            state.target,
+            state.mode
+        );
+        super::require_confirmation(ctx, "train", &summary)?;
+        state
+    };
+
+    let total = state.landed.len() + state.current.iter().count() + state.pending.len();
+    loop {
+        if let Some(name) = state.current.clone() {
+            super::operation::ensure_idle(&ctx.git, "root", &ctx.repo_root, "continue the train")?;
+            if let Err(err) = verify_target(ctx) {
+                save_state(ctx, &state)?;
+                stopped(ctx, &name, "verify failed; fix it on the target and commit");
+                return Err(err);
+            }
+            state.landed.push(name);
+            state.current = None;
+        }
+        if state.pending.is_empty() {
+            break;
+        }
+        let name = state.pending.remove(0);
+        if !ctx.quiet {
+            println!(
+                "train: applying {} into {} ({}/{})",
+                name,
+                state.target,
+                state.landed.len() + 1,
+                total
+            );
+        }
+        state.current = Some(name.clone());
+        save_state(ctx, &state)?;
+        if let Err(err) = super::apply(ctx, apply_args(&state, &name)) {
+            if err.kind == ErrorKind::Conflict {
+                stopped(
+                    ctx,
+                    &name,
+                    "apply conflicted; resolve and commit on the target",
+                );
+            } else {
+                // Nothing was applied, so --continue retries this worktree
+                state.current = None;
+                state.pending.insert(0, name.clone());
+                save_state(ctx, &state)?;
+                stopped(ctx, &name, "apply failed");
+            }
+            return Err(err);
+        }
+    }
+
+    fs::remove_file(&path).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    if !ctx.quiet {
+        println!(
+            "train: landed {} into {}",
+            describe(&state.landed),
+            state.target
+        );
+    }
+    Ok(())
+}
+
+fn apply_args(state: &TrainState, name: &str) -> ApplyArgs {
+    ApplyArgs {
+        name: name.to_string(),
+        target: Some(state.target.clone()),
+        merge: state.mode == "merge",
+        squash: state.mode == "squash",
+        rebase: state.mode == "rebase",
+        cleanup: false,
+        message: None,
+        // Checked once when the train started
+        allow_protected: true,
+    }
+}
+
+/// Run the verify pipeline at the repo root, where the target is checked out.
+fn verify_target(ctx: &Context) -> Result<()> {
+    super::verify(
+        ctx,
+        VerifyArgs {
+            name: "root".to_string(),
+            subdir: None,
+            root: true,
+            changed_only: false,
+            fix: false,
+            commit: false,
+        },
+    )
+}
+
+fn stopped(ctx: &Context, name: &str, reason: &str) {
+    if !ctx.quiet {
+        eprintln!(
+            "train: stopped at {}: {}, then run `gw train --continue` (or `gw train --abort`)",
+            name, reason
+        );
+    }
+}
+
+fn describe(names: &[String]) -> String {
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+fn state_path(ctx: &Context) -> PathBuf {
+    ctx.repo_root.join(".gw").join(STATE_FILE)
+}
+
+fn load_state(ctx: &Context) -> Result<TrainState> {
+    let raw = fs::read_to_string(state_path(ctx))
+        .map_err(|_| GwError::new(ErrorKind::InvalidInput, "no train in progress"))?;
+    serde_json::from_str(&raw).map_err(|e| {
+        GwError::new(
+            ErrorKind::General,
+            format!("corrupt .gw/{}: {}", STATE_FILE, e),
+        )
+    })
+}
+
+fn save_state(ctx: &Context, state: &TrainState) -> Result<()> {
+    let path = state_path(ctx);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    }
+    let data = serde_json::to_string_pretty(state)
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    fs::write(&path, data).map_err(|e| GwError::new(ErrorKind::General, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_round_trips() {
+        let state = TrainState {
+            target: "main".to_string(),
+            mode: "merge".to_string(),
+            current: Some("pay".to_string()),
+            pending: vec!["auth".to_string()],
+            landed: vec!["ui".to_string()],
+        };
+        let raw = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<TrainState>(&raw).unwrap(), state);
+    }
+}
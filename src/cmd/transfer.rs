@@ -0,0 +1,58 @@
+use crate::cli::TransferArgs;
+use crate::git::git_error;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::fs;
+
+pub fn transfer(ctx: &Context, args: TransferArgs) -> Result<()> {
+    if args.from == args.to {
+        return Err(GwError::new(
+            ErrorKind::InvalidInput,
+            "source and destination are the same worktree",
+        ));
+    }
+    let from = super::require_worktree(ctx, &args.from)?;
+    let to = super::require_worktree(ctx, &args.to)?;
+
+    let patch = super::patch::scratch_path(ctx, "transfer.patch")?;
+    let result = transfer_patch(ctx, &args, &from.path, &to.path, &patch);
+    let _ = fs::remove_file(&patch);
+    let moved = result?;
+
+    if !moved {
+        if !ctx.quiet {
+            println!("transfer: no changes in '{}'", args.from);
+        }
+        return Ok(());
+    }
+
+    if !args.keep {
+        ctx.git
+            .run_in(&from.path, &["reset", "--hard", "HEAD"])
+            .map_err(git_error)?;
+        if args.include_untracked {
+            ctx.git
+                .run_in(&from.path, &["clean", "-fd"])
+                .map_err(git_error)?;
+        }
+    }
+
+    if !ctx.quiet {
+        let verb = if args.keep { "copied" } else { "moved" };
+        println!("{} changes: {} -> {}", verb, args.from, args.to);
+    }
+    Ok(())
+}
+
+fn transfer_patch(
+    ctx: &Context,
+    args: &TransferArgs,
+    from: &std::path::Path,
+    to: &std::path::Path,
+    patch: &std::path::Path,
+) -> Result<bool> {
+    if !super::patch::write_uncommitted_patch(ctx, from, args.include_untracked, patch)? {
+        return Ok(false);
+    }
+    super::patch::apply_patch(ctx, to, patch)?;
+    Ok(true)
+}
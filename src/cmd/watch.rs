@@ -0,0 +1,254 @@
+use crate::cli::WatchArgs;
+use crate::{Context, ErrorKind, GwError, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// (name, directory, injected env) for one watched worktree.
+type Target = (String, PathBuf, Vec<(String, String)>);
+
+/// Size and mtime of every tracked or untracked, non-ignored file under a directory.
+type Snapshot = HashMap<String, (u64, Option<SystemTime>)>;
+
+/// The non-ignored files under a watched directory, as last listed by
+/// `git ls-files`, and the mtimes of the directories holding them.
+struct Listing {
+    files: Vec<String>,
+    dirs: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+/// Rerun a command in each target worktree whenever its files change. There is
+/// no native watcher dependency: each poll stats the files `git ls-files`
+/// listed, and git runs again only when a directory's mtime shows files were
+/// added or removed. Ignored build output never triggers a run.
+pub fn watch(ctx: &Context, args: WatchArgs) -> Result<()> {
+    let cmd = args.cmd.join(" ");
+    let mut targets: Vec<Target> = Vec::new();
+    match args.name {
+        Some(ref name) if !args.all => {
+            let name = super::recent::resolve_ref(ctx, name)?;
+            let wt = super::require_worktree(ctx, &name)?;
+            let dir = super::resolve_worktree_dir(
+                ctx,
+                &wt.path,
+                &name,
+                args.root,
+                args.subdir.as_deref(),
+            );
+            let envs = super::env::worktree_env(ctx, &name, &wt);
+            targets.push((name, dir, envs));
+        }
+        _ => {
+            for wt in ctx.worktrees()? {
+                let Some(name) = super::worktree_name_with_config(ctx, &wt.path) else {
+                    continue;
+                };
+                if wt.is_broken() || !super::owner_matches(ctx, &name, &args.filter) {
+                    continue;
+                }
+                let dir = super::resolve_worktree_dir(
+                    ctx,
+                    &wt.path,
+                    &name,
+                    args.root,
+                    args.subdir.as_deref(),
+                );
+                let envs = super::env::worktree_env(ctx, &name, &wt);
+                targets.push((name, dir, envs));
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Err(GwError::new(ErrorKind::NotFound, "no worktrees to watch"));
+    }
+
+    let shell = ctx.config.exec_shell();
+    let prefixed = targets.len() > 1;
+    let interval = Duration::from_millis(args.interval.max(50));
+    let debounce = Duration::from_millis(args.debounce);
+    if !ctx.quiet {
+        let names: Vec<&str> = targets.iter().map(|t| t.0.as_str()).collect();
+        eprintln!("watch: {} (Ctrl-C to stop)", names.join(", "));
+    }
+    thread::scope(|scope| {
+        for (name, dir, envs) in &targets {
+            let cmd = super::exec::target_cmd(ctx, name, &cmd);
+            let shell = &shell;
+            scope.spawn(move || {
+                let prefix = prefixed.then(|| format!("[{}] ", name));
+                let prefix = prefix.as_deref().unwrap_or("");
+                let mut listing = None;
+                let mut seen = match snapshot(ctx, dir, &mut listing) {
+                    Ok(snap) => snap,
+                    Err(e) => {
+                        eprintln!("{}watch stopped: {}", prefix, e);
+                        return;
+                    }
+                };
+                run(ctx, shell, &cmd, dir, envs, prefix);
+                loop {
+                    thread::sleep(interval);
+                    let Ok(mut current) = snapshot(ctx, dir, &mut listing) else {
+                        eprintln!("{}watch stopped: {} is gone", prefix, dir.display());
+                        return;
+                    };
+                    if current == seen {
+                        continue;
+                    }
+                    // Wait for the burst of writes (save, format, checkout) to settle
+                    loop {
+                        thread::sleep(debounce);
+                        match snapshot(ctx, dir, &mut listing) {
+                            Ok(next) if next != current => current = next,
+                            _ => break,
+                        }
+                    }
+                    // Changes made while the command runs trigger the next run
+                    seen = current;
+                    run(ctx, shell, &cmd, dir, envs, prefix);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Stat the listed files, listing them again first when `listing` is empty
+/// or one of its directories changed.
+fn snapshot(
+    ctx: &Context,
+    dir: &Path,
+    listing: &mut Option<Listing>,
+) -> std::result::Result<Snapshot, String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is gone", dir.display()));
+    }
+    let stale = listing.as_ref().is_none_or(|listing| {
+        listing
+            .dirs
+            .iter()
+            .any(|(path, mtime)| modified(path) != *mtime)
+    });
+    if stale {
+        *listing = Some(list(ctx, dir)?);
+    }
+    let files = listing.as_ref().map(|l| l.files.as_slice()).unwrap_or(&[]);
+    Ok(files
+        .iter()
+        .map(|file| {
+            let meta = std::fs::metadata(dir.join(file)).ok();
+            let stamp = meta
+                .as_ref()
+                .map(|m| (m.len(), m.modified().ok()))
+                .unwrap_or((0, None));
+            (file.clone(), stamp)
+        })
+        .collect())
+}
+
+fn list(ctx: &Context, dir: &Path) -> std::result::Result<Listing, String> {
+    let out = ctx.git.run_in_env(
+        dir,
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ],
+        &[],
+    )?;
+    let files: Vec<String> = out
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+        .collect();
+    let dirs: BTreeSet<PathBuf> = files
+        .iter()
+        .flat_map(|file| Path::new(file).ancestors().skip(1))
+        .map(|parent| dir.join(parent))
+        .chain([dir.to_path_buf()])
+        .collect();
+    Ok(Listing {
+        files,
+        dirs: dirs
+            .into_iter()
+            .map(|d| {
+                let mtime = modified(&d);
+                (d, mtime)
+            })
+            .collect(),
+    })
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Run `cmd` once, prefixing each line of its output when several worktrees
+/// are watched at the same time.
+fn run(
+    ctx: &Context,
+    shell: &[String],
+    cmd: &str,
+    dir: &Path,
+    envs: &[(String, String)],
+    prefix: &str,
+) {
+    if !ctx.quiet {
+        eprintln!("{}watch: running {}", prefix, cmd);
+    }
+    let Some((program, shell_args)) = shell.split_first() else {
+        eprintln!("{}watch: empty [exec] shell", prefix);
+        return;
+    };
+    let mut command = Command::new(program);
+    command
+        .args(shell_args)
+        .arg(cmd)
+        .current_dir(dir)
+        .envs(envs.iter().map(|(k, v)| (k, v)));
+    let status = if prefix.is_empty() {
+        command.status()
+    } else {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        command.spawn().and_then(|mut child| {
+            let out = child.stdout.take();
+            let err = child.stderr.take();
+            thread::scope(|scope| {
+                if let Some(out) = out {
+                    scope.spawn(|| relay(out, prefix, false));
+                }
+                if let Some(err) = err {
+                    scope.spawn(|| relay(err, prefix, true));
+                }
+            });
+            child.wait()
+        })
+    };
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!(
+            "{}watch: command failed ({})",
+            prefix,
+            status
+                .code()
+                .map(|c| format!("exit {}", c))
+                .unwrap_or_else(|| "signal".to_string())
+        ),
+        Err(e) => eprintln!("{}watch: command failed: {}: {}", prefix, program, e),
+    }
+}
+
+fn relay(stream: impl Read, prefix: &str, stderr: bool) {
+    for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+        if stderr {
+            eprintln!("{}{}", prefix, line);
+        } else {
+            println!("{}{}", prefix, line);
+        }
+    }
+}
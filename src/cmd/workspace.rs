@@ -0,0 +1,149 @@
+use crate::{ErrorKind, GwError, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn registered() -> Result<BTreeMap<String, PathBuf>> {
+    crate::config::load_workspaces()
+        .map_err(|e| GwError::new(ErrorKind::Config, format!("workspaces.toml: {}", e)))
+}
+
+/// Path of a repository registered in `~/.gw/workspaces.toml`.
+pub fn workspace_repo(name: &str) -> Result<PathBuf> {
+    let repos = registered()?;
+    repos.get(name).cloned().ok_or_else(|| {
+        let known: Vec<&str> = repos.keys().map(|k| k.as_str()).collect();
+        GwError::new(
+            ErrorKind::NotFound,
+            format!(
+                "repo '{}' is not registered in ~/.gw/workspaces.toml (known: {})",
+                name,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            ),
+        )
+    })
+}
+
+/// Re-run the current list/status command in each selected repository
+/// (`all` or a comma-separated list of names) and combine the output.
+pub fn workspace(spec: &str, json: bool) -> Result<()> {
+    let repos = registered()?;
+    let selected: Vec<(String, PathBuf)> = if spec == "all" {
+        repos.into_iter().collect()
+    } else {
+        spec.split(',')
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| workspace_repo(name).map(|path| (name.to_string(), path)))
+            .collect::<Result<_>>()?
+    };
+    if selected.is_empty() {
+        return Err(GwError::new(
+            ErrorKind::NotFound,
+            "no repos registered in ~/.gw/workspaces.toml",
+        ));
+    }
+
+    let exe =
+        std::env::current_exe().map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+    let args = forwarded_args(std::env::args().skip(1));
+    let mut failed = Vec::new();
+    let mut combined = Vec::new();
+    for (index, (name, path)) in selected.iter().enumerate() {
+        let mut cmd = Command::new(&exe);
+        cmd.arg("-C").arg(path).args(&args);
+        if json {
+            let output = cmd
+                .stderr(Stdio::inherit())
+                .output()
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+            if !output.status.success() {
+                failed.push(name.clone());
+                continue;
+            }
+            let items: serde_json::Value =
+                serde_json::from_slice(&output.stdout).unwrap_or(serde_json::Value::Null);
+            combined.push(serde_json::json!({
+                "repo": name,
+                "path": path.to_string_lossy(),
+                "worktrees": items,
+            }));
+        } else {
+            if index > 0 {
+                println!();
+            }
+            println!("== {} ({}) ==", name, path.display());
+            let status = cmd
+                .status()
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+            if !status.success() {
+                failed.push(name.clone());
+            }
+        }
+    }
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&combined).unwrap_or("[]".to_string())
+        );
+    }
+    if !failed.is_empty() {
+        return Err(GwError::new(
+            ErrorKind::General,
+            format!("failed in: {}", failed.join(", ")),
+        ));
+    }
+    Ok(())
+}
+
+/// The original arguments minus `--workspace` and any repo selection.
+fn forwarded_args(mut args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut out = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--workspace" || arg == "-C" || arg == "--repo" {
+            args.next();
+            continue;
+        }
+        if arg.starts_with("--workspace=")
+            || arg.starts_with("--repo=")
+            || (arg.starts_with("-C") && arg.len() > 2)
+        {
+            continue;
+        }
+        out.push(arg);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_args_drop_repo_selection() {
+        let args = [
+            "--json",
+            "-C",
+            "/x",
+            "status",
+            "--workspace",
+            "all",
+            "--recent",
+            "5",
+        ]
+        .iter()
+        .map(|s| s.to_string());
+        assert_eq!(
+            forwarded_args(args),
+            vec!["--json", "status", "--recent", "5"]
+        );
+        let args = ["--repo=api", "list", "--workspace=api,web"]
+            .iter()
+            .map(|s| s.to_string());
+        assert_eq!(forwarded_args(args), vec!["list"]);
+    }
+}
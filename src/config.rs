@@ -1,8 +1,10 @@
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct Config {
@@ -14,6 +16,137 @@ pub struct Config {
     pub verify: VerifyConfig,
     #[serde(default)]
     pub worktree: WorktreeConfig,
+    #[serde(default)]
+    pub apply: ApplyConfig,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub exec: ExecConfig,
+    #[serde(default)]
+    pub meta: MetaConfig,
+    #[serde(default)]
+    pub open: OpenConfig,
+    #[serde(default)]
+    pub issue: IssueConfig,
+    #[serde(default)]
+    pub forge: ForgeConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// `[subdirs]` presets: short name -> path inside the worktree
+    #[serde(default)]
+    pub subdirs: BTreeMap<String, String>,
+    /// `[command_defaults]`: command name -> flags inserted before its arguments
+    #[serde(default)]
+    pub command_defaults: BTreeMap<String, Vec<String>>,
+    /// `[template.<name>]` presets selected with `gw add --template <name>`
+    #[serde(default)]
+    pub template: BTreeMap<String, TemplateConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExecConfig {
+    pub shell: Option<String>,
+}
+
+/// `[meta]` section: where worktree metadata is stored.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetaConfig {
+    pub backend: Option<String>,
+    pub remote: Option<String>,
+}
+
+/// `[hooks]` section: shell commands run inside a worktree around gw commands.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HooksConfig {
+    pub post_add: Option<String>,
+    pub pre_del: Option<String>,
+    pub post_sync: Option<String>,
+    pub post_apply: Option<String>,
+}
+
+/// A `[template.<name>]` section: settings `gw add --template <name>` applies
+/// unless the matching flag is given.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TemplateConfig {
+    pub base: Option<String>,
+    pub branch_prefix: Option<String>,
+    pub subdir: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub copy_files: Vec<String>,
+    /// Shell command run inside the new worktree once it is set up
+    pub bootstrap: Option<String>,
+}
+
+/// Keys allowed inside a `[template.<name>]` section.
+const TEMPLATE_KEYS: &[&str] = &[
+    "base",
+    "branch_prefix",
+    "subdir",
+    "tags",
+    "copy_files",
+    "bootstrap",
+];
+
+/// `[forge]` section: how gw queries the code host.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ForgeConfig {
+    pub provider: Option<String>,
+    pub cache_ttl: Option<u64>,
+    pub min_interval: Option<u64>,
+}
+
+/// `[issue]` section: where `gw add --from-issue` looks issues up.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IssueConfig {
+    pub provider: Option<String>,
+    pub jira_url: Option<String>,
+}
+
+/// `[open]` section: editor integration for new worktrees.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OpenConfig {
+    /// `[open.generate]`: file inside the worktree -> template rendered on add
+    pub generate: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UiConfig {
+    pub status_sort: Option<String>,
+    pub time_format: Option<String>,
+    pub subject_width: Option<usize>,
+    pub recent_files: Option<usize>,
+    pub recent_files_max_scan: Option<usize>,
+    pub path_style: Option<String>,
+    pub pager: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ArchiveConfig {
+    pub enabled: Option<bool>,
+    pub retention_days: Option<i64>,
+}
+
+/// `[sync]` section: default strategy when no mode flag is passed, and
+/// whether `sync --all` leaves locked worktrees alone.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SyncConfig {
+    pub mode: Option<String>,
+    pub skip_locked: Option<bool>,
+}
+
+/// `[apply]` section: default strategy, `Gw-*` trailers on apply commits, and
+/// branches that need `--allow-protected`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApplyConfig {
+    pub mode: Option<String>,
+    pub trailers: Option<bool>,
+    pub protected: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -25,13 +158,24 @@ pub struct WorktreeConfig {
 pub struct Defaults {
     pub base: Option<String>,
     pub worktrees_dir: Option<String>,
+    pub tmp_dir: Option<String>,
     pub branch_prefix: Option<String>,
     pub subdir: Option<String>,
+    pub lock_timeout: Option<u64>,
+    pub branch_name_template: Option<String>,
+    pub name_template: Option<String>,
+    pub fetch_before_resolve: Option<bool>,
+    pub auto_subdir: Option<bool>,
+    pub copy_files: Option<Vec<String>>,
+    pub git_backend: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct GcConfig {
     pub stale_days: Option<i64>,
+    pub auto: Option<bool>,
+    pub auto_interval: Option<u64>,
+    pub max_total_size: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -39,8 +183,71 @@ pub struct VerifyConfig {
     pub rust: Option<String>,
     pub node: Option<String>,
     pub python: Option<String>,
+    pub pre: Option<Vec<String>>,
+    pub post: Option<Vec<String>>,
+    pub fix_rust: Option<String>,
+    pub fix_node: Option<String>,
+    pub fix_python: Option<String>,
+    pub fix_commit: Option<bool>,
 }
 
+/// Every `section.key` gw reads from config files.
+pub const KNOWN_KEYS: &[&str] = &[
+    "defaults.base",
+    "defaults.worktrees_dir",
+    "defaults.tmp_dir",
+    "defaults.branch_prefix",
+    "defaults.subdir",
+    "defaults.lock_timeout",
+    "defaults.branch_name_template",
+    "defaults.name_template",
+    "defaults.fetch_before_resolve",
+    "defaults.auto_subdir",
+    "defaults.copy_files",
+    "defaults.git_backend",
+    "gc.stale_days",
+    "gc.auto",
+    "gc.auto_interval",
+    "gc.max_total_size",
+    "verify.rust",
+    "verify.node",
+    "verify.python",
+    "verify.pre",
+    "verify.post",
+    "verify.fix_rust",
+    "verify.fix_node",
+    "verify.fix_python",
+    "verify.fix_commit",
+    "worktree.link",
+    "open.generate",
+    "issue.provider",
+    "issue.jira_url",
+    "forge.provider",
+    "forge.cache_ttl",
+    "forge.min_interval",
+    "hooks.post_add",
+    "hooks.pre_del",
+    "hooks.post_sync",
+    "hooks.post_apply",
+    "apply.mode",
+    "apply.trailers",
+    "apply.protected",
+    "sync.mode",
+    "sync.skip_locked",
+    "archive.enabled",
+    "archive.retention_days",
+    "ui.status_sort",
+    "ui.time_format",
+    "ui.subject_width",
+    "ui.recent_files",
+    "ui.recent_files_max_scan",
+    "ui.path_style",
+    "ui.pager",
+    "exec.shell",
+    "meta.backend",
+    "meta.remote",
+];
+
 impl Config {
     pub fn load(repo_root: &Path) -> anyhow::Result<Self> {
         let mut config = Config::default();
@@ -73,11 +280,46 @@ impl Config {
             .unwrap_or_else(|| ".worktrees".to_string())
     }
 
+    /// Scratch root for `gw add --tmp` worktrees (`$TMPDIR/gw` by default).
+    pub fn tmp_dir(&self) -> PathBuf {
+        if let Ok(value) = env::var("GW_TMP_DIR") {
+            return PathBuf::from(value);
+        }
+        self.defaults
+            .tmp_dir
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| env::temp_dir().join("gw"))
+    }
+
     pub fn branch_prefix(&self) -> String {
         self.defaults
             .branch_prefix
             .clone()
-            .unwrap_or_else(|| "logan/".to_string())
+            .unwrap_or_else(|| default_branch_prefix().to_string())
+    }
+
+    /// Template for names derived from `gw add --branch <branch>` ({branch}, {leaf}).
+    pub fn branch_name_template(&self) -> String {
+        self.defaults
+            .branch_name_template
+            .clone()
+            .unwrap_or_else(|| "{leaf}".to_string())
+    }
+
+    /// Template applied to new worktree names ({name}, {date}, {user}, {base}).
+    pub fn name_template(&self) -> Option<String> {
+        self.defaults.name_template.clone()
+    }
+
+    /// Whether add/sync fetch origin and refresh origin/HEAD before resolving the base.
+    pub fn fetch_before_resolve(&self) -> bool {
+        self.defaults.fetch_before_resolve.unwrap_or(false)
+    }
+
+    /// Whether a worktree without an explicit subdir uses the one inferred from its changes.
+    pub fn auto_subdir(&self) -> bool {
+        self.defaults.auto_subdir.unwrap_or(false)
     }
 
     pub fn default_base(&self) -> Option<String> {
@@ -91,13 +333,177 @@ impl Config {
         if let Ok(value) = env::var("GW_SUBDIR") {
             return Some(value);
         }
-        self.defaults.subdir.clone()
+        self.defaults
+            .subdir
+            .as_deref()
+            .map(|s| self.expand_subdir(s))
+    }
+
+    /// Resolve a `[subdirs]` preset name to its path; other values pass through.
+    pub fn expand_subdir(&self, value: &str) -> String {
+        self.subdirs
+            .get(value)
+            .cloned()
+            .unwrap_or_else(|| value.to_string())
+    }
+
+    /// Seconds to wait for another gw process to release the repository lock.
+    pub fn lock_timeout(&self) -> u64 {
+        if let Some(value) = env::var("GW_LOCK_TIMEOUT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            return value;
+        }
+        self.defaults.lock_timeout.unwrap_or(30)
+    }
+
+    /// Default `gw apply` strategy: merge, squash or rebase.
+    pub fn apply_mode(&self) -> String {
+        self.apply
+            .mode
+            .clone()
+            .unwrap_or_else(|| "merge".to_string())
+    }
+
+    /// Record `Gw-Worktree` / `Gw-Base` trailers on commits made by `gw apply`.
+    pub fn apply_trailers(&self) -> bool {
+        self.apply.trailers.unwrap_or(true)
+    }
+
+    /// Whether `branch` matches an `[apply] protected` glob such as `release/*`.
+    pub fn apply_protected(&self, branch: &str) -> bool {
+        self.apply
+            .protected
+            .iter()
+            .flatten()
+            .filter_map(|p| glob::Pattern::new(p).ok())
+            .any(|p| p.matches(branch))
+    }
+
+    /// Default `gw sync` strategy: rebase or merge.
+    pub fn sync_mode(&self) -> String {
+        self.sync
+            .mode
+            .clone()
+            .unwrap_or_else(|| "rebase".to_string())
+    }
+
+    /// Whether `sync --all` skips locked worktrees.
+    pub fn sync_skip_locked(&self) -> bool {
+        self.sync.skip_locked.unwrap_or(true)
+    }
+
+    /// Whether deleted branches are first saved under `refs/gw/archive/`.
+    pub fn archive_enabled(&self) -> bool {
+        self.archive.enabled.unwrap_or(false)
+    }
+
+    /// Days to keep archive refs; `None` (or 0) keeps them forever.
+    pub fn archive_retention_days(&self) -> Option<i64> {
+        self.archive.retention_days.filter(|d| *d > 0)
+    }
+
+    /// Order of `gw status` rows: activity (default), name, or git.
+    pub fn status_sort(&self) -> String {
+        self.ui
+            .status_sort
+            .clone()
+            .unwrap_or_else(|| "activity".to_string())
+    }
+
+    /// How times are shown: relative (default, "3d ago"), absolute or iso.
+    pub fn time_format(&self) -> String {
+        self.ui
+            .time_format
+            .clone()
+            .unwrap_or_else(|| "relative".to_string())
+    }
+
+    /// Characters of the commit subject shown in `gw status`.
+    pub fn subject_width(&self) -> usize {
+        self.ui.subject_width.filter(|w| *w > 0).unwrap_or(20)
+    }
+
+    /// How list/recent print worktree paths: absolute (default), relative or home.
+    pub fn path_style(&self) -> String {
+        self.ui
+            .path_style
+            .clone()
+            .unwrap_or_else(|| "absolute".to_string())
+    }
+
+    /// Pager for `gw review`; unset leaves git's own (`core.pager`, `$PAGER`).
+    pub fn pager(&self) -> Option<String> {
+        self.ui.pager.clone().filter(|p| !p.trim().is_empty())
+    }
+
+    /// Default for `gw status --recent`.
+    pub fn recent_files(&self) -> usize {
+        self.ui.recent_files.unwrap_or(3)
+    }
+
+    /// Most changed files whose mtime status looks at per worktree; 0 skips the scan.
+    pub fn recent_files_max_scan(&self) -> usize {
+        self.ui.recent_files_max_scan.unwrap_or(1000)
+    }
+
+    /// Shell used by exec/verify as program + args; the command is appended as the
+    /// last argument. Defaults to `sh -c` (`cmd /C` on Windows).
+    pub fn exec_shell(&self) -> Vec<String> {
+        match self.exec.shell.as_deref() {
+            Some(shell) if !shell.trim().is_empty() => {
+                shell.split_whitespace().map(|s| s.to_string()).collect()
+            }
+            _ if cfg!(windows) => vec!["cmd".to_string(), "/C".to_string()],
+            _ => vec!["sh".to_string(), "-c".to_string()],
+        }
+    }
+
+    /// How worktree, branch, status and log queries run: git (the git CLI,
+    /// default) or libgit2 (in-process, when gw is built with that feature).
+    pub fn git_backend(&self) -> String {
+        self.defaults
+            .git_backend
+            .clone()
+            .unwrap_or_else(|| "git".to_string())
+    }
+
+    /// Meta store backend: json (`.gw/meta.json`, default) or sqlite (`.gw/meta.db`).
+    pub fn meta_backend(&self) -> String {
+        self.meta
+            .backend
+            .clone()
+            .unwrap_or_else(|| "json".to_string())
+    }
+
+    /// Remote that `gw meta push` / `gw meta pull` share `refs/gw/meta` with.
+    pub fn meta_remote(&self) -> String {
+        self.meta
+            .remote
+            .clone()
+            .unwrap_or_else(|| "origin".to_string())
     }
 
     pub fn gc_stale_days(&self) -> i64 {
         self.gc.stale_days.unwrap_or(7)
     }
 
+    /// Whether gw runs light maintenance on its own every few invocations.
+    pub fn gc_auto(&self) -> bool {
+        self.gc.auto.unwrap_or(false)
+    }
+
+    /// Disk budget for the worktrees dir in bytes; unset or unparsable means none.
+    pub fn gc_max_total_size(&self) -> Option<u64> {
+        self.gc.max_total_size.as_deref().and_then(parse_size)
+    }
+
+    /// Invocations between automatic maintenance runs.
+    pub fn gc_auto_interval(&self) -> u64 {
+        self.gc.auto_interval.filter(|n| *n > 0).unwrap_or(50)
+    }
+
     pub fn verify_rust(&self) -> String {
         self.verify
             .rust
@@ -119,6 +525,89 @@ impl Config {
             .unwrap_or_else(|| "pytest".to_string())
     }
 
+    /// Commands run before the verify pipeline; a failure aborts verify.
+    pub fn verify_pre(&self) -> Vec<String> {
+        self.verify.pre.clone().unwrap_or_default()
+    }
+
+    /// Commands run after the verify pipeline, whether or not it passed.
+    pub fn verify_post(&self) -> Vec<String> {
+        self.verify.post.clone().unwrap_or_default()
+    }
+
+    /// Autofix commands for `verify --fix`; unset ecosystems have no fixer.
+    pub fn verify_fix_rust(&self) -> Option<String> {
+        self.verify.fix_rust.clone()
+    }
+
+    pub fn verify_fix_node(&self) -> Option<String> {
+        self.verify.fix_node.clone()
+    }
+
+    pub fn verify_fix_python(&self) -> Option<String> {
+        self.verify.fix_python.clone()
+    }
+
+    /// Commit the changes made by `verify --fix`.
+    pub fn verify_fix_commit(&self) -> bool {
+        self.verify.fix_commit.unwrap_or(false)
+    }
+
+    /// Command configured for a `[hooks]` entry such as `post_add`.
+    pub fn hook(&self, hook: &str) -> Option<String> {
+        let cmd = match hook {
+            "post_add" => &self.hooks.post_add,
+            "pre_del" => &self.hooks.pre_del,
+            "post_sync" => &self.hooks.post_sync,
+            "post_apply" => &self.hooks.post_apply,
+            _ => return None,
+        };
+        cmd.clone().filter(|c| !c.trim().is_empty())
+    }
+
+    /// The `[template.<name>]` section called `name`.
+    pub fn template(&self, name: &str) -> Option<&TemplateConfig> {
+        self.template.get(name)
+    }
+
+    pub fn template_names(&self) -> Vec<String> {
+        self.template.keys().cloned().collect()
+    }
+
+    /// gh, glab or rest; `None` picks gh or glab from origin's host.
+    pub fn forge_provider(&self) -> Option<String> {
+        self.forge.provider.clone().filter(|p| !p.trim().is_empty())
+    }
+
+    /// Seconds a forge response is reused from `.gw/cache` (default: 60).
+    pub fn forge_cache_ttl(&self) -> u64 {
+        self.forge.cache_ttl.unwrap_or(60)
+    }
+
+    /// Minimum seconds between forge API calls (default: 1).
+    pub fn forge_min_interval(&self) -> u64 {
+        self.forge.min_interval.unwrap_or(1)
+    }
+
+    /// Issue tracker: jira or a forge provider; `None` uses `[forge] provider`.
+    pub fn issue_provider(&self) -> Option<String> {
+        self.issue.provider.clone().filter(|p| !p.trim().is_empty())
+    }
+
+    /// Jira site for issue links, e.g. `https://acme.atlassian.net`.
+    pub fn issue_jira_url(&self) -> Option<String> {
+        self.issue.jira_url.clone()
+    }
+
+    pub fn open_generate(&self) -> BTreeMap<String, String> {
+        self.open.generate.clone().unwrap_or_default()
+    }
+
+    /// Globs of untracked/ignored files copied from the repo root into new worktrees.
+    pub fn copy_files(&self) -> Vec<String> {
+        self.defaults.copy_files.clone().unwrap_or_default()
+    }
+
     pub fn worktree_link_patterns(&self) -> Vec<String> {
         self.worktree.link.clone().unwrap_or_default()
     }
@@ -126,22 +615,29 @@ impl Config {
     pub fn validate(repo_root: &Path) -> Vec<String> {
         let mut warnings = Vec::new();
 
-        let known_sections: HashSet<&str> =
-            ["defaults", "gc", "verify", "worktree"].iter().copied().collect();
-        let known_keys: HashSet<&str> = [
-            "defaults.base",
-            "defaults.worktrees_dir",
-            "defaults.branch_prefix",
-            "defaults.subdir",
-            "gc.stale_days",
-            "verify.rust",
-            "verify.node",
-            "verify.python",
-            "worktree.link",
+        let known_sections: HashSet<&str> = [
+            "defaults",
+            "gc",
+            "verify",
+            "worktree",
+            "apply",
+            "sync",
+            "archive",
+            "ui",
+            "exec",
+            "meta",
+            "open",
+            "issue",
+            "forge",
+            "hooks",
+            "subdirs",
+            "command_defaults",
+            "template",
         ]
         .iter()
         .copied()
         .collect();
+        let known_keys: HashSet<&str> = KNOWN_KEYS.iter().copied().collect();
 
         let all_key_names: Vec<&str> = known_keys
             .iter()
@@ -167,10 +663,59 @@ impl Config {
         if let Some(table) = value.as_table() {
             for (section, val) in table {
                 if !known_sections.contains(section.as_str()) {
-                    warnings.push(format!(
-                        ".gw/config.toml: unknown section '{}'",
-                        section
-                    ));
+                    warnings.push(format!(".gw/config.toml: unknown section '{}'", section));
+                    continue;
+                }
+                if section == "command_defaults" {
+                    // Keyed by command name; each value is a list of arguments
+                    for (key, flags) in val.as_table().into_iter().flatten() {
+                        let is_list = flags
+                            .as_array()
+                            .is_some_and(|a| a.iter().all(|f| f.is_str()));
+                        if !is_list {
+                            warnings.push(format!(
+                                ".gw/config.toml: 'command_defaults.{}' should be a list of strings",
+                                key
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                if section == "template" {
+                    for (name, body) in val.as_table().into_iter().flatten() {
+                        let Some(body) = body.as_table() else {
+                            warnings.push(format!(
+                                ".gw/config.toml: 'template.{}' should be a table",
+                                name
+                            ));
+                            continue;
+                        };
+                        for key in body.keys() {
+                            if !TEMPLATE_KEYS.contains(&key.as_str()) {
+                                warnings.push(format!(
+                                    ".gw/config.toml: unknown key 'template.{}.{}'",
+                                    name, key
+                                ));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if section == "subdirs" {
+                    // Preset names are free-form; only the paths are checked
+                    for (key, path) in val.as_table().into_iter().flatten() {
+                        match path.as_str() {
+                            Some(p) if p.starts_with('/') => warnings.push(format!(
+                                ".gw/config.toml: 'subdirs.{}' should not start with '/'",
+                                key
+                            )),
+                            Some(_) => {}
+                            None => warnings.push(format!(
+                                ".gw/config.toml: 'subdirs.{}' should be a path string",
+                                key
+                            )),
+                        }
+                    }
                     continue;
                 }
                 if let Some(inner) = val.as_table() {
@@ -184,10 +729,7 @@ impl Config {
                                     full, s
                                 ));
                             } else {
-                                warnings.push(format!(
-                                    ".gw/config.toml: unknown key '{}'",
-                                    full
-                                ));
+                                warnings.push(format!(".gw/config.toml: unknown key '{}'", full));
                             }
                         }
                     }
@@ -195,7 +737,45 @@ impl Config {
             }
 
             // Value validation
+            if let Some(generate) = table
+                .get("open")
+                .and_then(|v| v.get("generate"))
+                .and_then(|v| v.as_table())
+            {
+                for file in generate.keys() {
+                    if Path::new(file).is_absolute() || file.split(['/', '\\']).any(|p| p == "..") {
+                        warnings.push(format!(
+                            ".gw/config.toml: 'open.generate' path '{}' must stay inside the worktree",
+                            file
+                        ));
+                    }
+                }
+            }
+            if let Some(copy_files) = table
+                .get("defaults")
+                .and_then(|v| v.get("copy_files"))
+                .and_then(|v| v.as_array())
+            {
+                for pattern in copy_files.iter().filter_map(|v| v.as_str()) {
+                    if Path::new(pattern).is_absolute()
+                        || pattern.split(['/', '\\']).any(|p| p == "..")
+                    {
+                        warnings.push(format!(
+                            ".gw/config.toml: 'defaults.copy_files' pattern '{}' must stay inside the repository; it is ignored",
+                            pattern
+                        ));
+                    }
+                }
+            }
             if let Some(gc) = table.get("gc").and_then(|v| v.as_table()) {
+                if let Some(size) = gc.get("max_total_size").and_then(|v| v.as_str()) {
+                    if parse_size(size).is_none() {
+                        warnings.push(format!(
+                            ".gw/config.toml: 'gc.max_total_size' '{}' is not a size (e.g. 500M, 50G)",
+                            size
+                        ));
+                    }
+                }
                 if let Some(days) = gc.get("stale_days").and_then(|v| v.as_integer()) {
                     if days <= 0 {
                         warnings.push(
@@ -204,11 +784,95 @@ impl Config {
                     }
                 }
             }
+            for (section, allowed) in [
+                ("apply", &["merge", "squash", "rebase"][..]),
+                ("sync", &["rebase", "merge"][..]),
+            ] {
+                if let Some(mode) = table
+                    .get(section)
+                    .and_then(|v| v.get("mode"))
+                    .and_then(|v| v.as_str())
+                {
+                    if !allowed.contains(&mode) {
+                        warnings.push(format!(
+                            ".gw/config.toml: '{}.mode' should be one of: {}",
+                            section,
+                            allowed.join(", ")
+                        ));
+                    }
+                }
+            }
+            if let Some(backend) = table
+                .get("defaults")
+                .and_then(|v| v.get("git_backend"))
+                .and_then(|v| v.as_str())
+            {
+                if !["git", "libgit2"].contains(&backend) {
+                    warnings.push(
+                        ".gw/config.toml: 'defaults.git_backend' should be one of: git, libgit2"
+                            .to_string(),
+                    );
+                } else if backend == "libgit2" && !cfg!(feature = "libgit2") {
+                    warnings.push(
+                        ".gw/config.toml: 'defaults.git_backend' is libgit2, but gw was built without the libgit2 feature; using git"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(sort) = table
+                .get("ui")
+                .and_then(|v| v.get("status_sort"))
+                .and_then(|v| v.as_str())
+            {
+                if !["activity", "name", "git"].contains(&sort) {
+                    warnings.push(
+                        ".gw/config.toml: 'ui.status_sort' should be one of: activity, name, git"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(format) = table
+                .get("ui")
+                .and_then(|v| v.get("time_format"))
+                .and_then(|v| v.as_str())
+            {
+                if !["relative", "absolute", "iso"].contains(&format) {
+                    warnings.push(
+                        ".gw/config.toml: 'ui.time_format' should be one of: relative, absolute, iso"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(style) = table
+                .get("ui")
+                .and_then(|v| v.get("path_style"))
+                .and_then(|v| v.as_str())
+            {
+                if !["absolute", "relative", "home"].contains(&style) {
+                    warnings.push(
+                        ".gw/config.toml: 'ui.path_style' should be one of: absolute, relative, home"
+                            .to_string(),
+                    );
+                }
+            }
+            if let Some(backend) = table
+                .get("meta")
+                .and_then(|v| v.get("backend"))
+                .and_then(|v| v.as_str())
+            {
+                if !["json", "sqlite"].contains(&backend) {
+                    warnings.push(
+                        ".gw/config.toml: 'meta.backend' should be one of: json, sqlite"
+                            .to_string(),
+                    );
+                }
+            }
             if let Some(defaults) = table.get("defaults").and_then(|v| v.as_table()) {
                 if let Some(subdir) = defaults.get("subdir").and_then(|v| v.as_str()) {
                     if subdir.starts_with('/') {
                         warnings.push(
-                            ".gw/config.toml: 'defaults.subdir' should not start with '/'".to_string(),
+                            ".gw/config.toml: 'defaults.subdir' should not start with '/'"
+                                .to_string(),
                         );
                     }
                 }
@@ -227,26 +891,179 @@ fn merge(base: Config, override_cfg: Config) -> Config {
                 .defaults
                 .worktrees_dir
                 .or(base.defaults.worktrees_dir),
+            tmp_dir: override_cfg.defaults.tmp_dir.or(base.defaults.tmp_dir),
             branch_prefix: override_cfg
                 .defaults
                 .branch_prefix
                 .or(base.defaults.branch_prefix),
             subdir: override_cfg.defaults.subdir.or(base.defaults.subdir),
+            lock_timeout: override_cfg
+                .defaults
+                .lock_timeout
+                .or(base.defaults.lock_timeout),
+            branch_name_template: override_cfg
+                .defaults
+                .branch_name_template
+                .or(base.defaults.branch_name_template),
+            name_template: override_cfg
+                .defaults
+                .name_template
+                .or(base.defaults.name_template),
+            fetch_before_resolve: override_cfg
+                .defaults
+                .fetch_before_resolve
+                .or(base.defaults.fetch_before_resolve),
+            auto_subdir: override_cfg
+                .defaults
+                .auto_subdir
+                .or(base.defaults.auto_subdir),
+            copy_files: override_cfg
+                .defaults
+                .copy_files
+                .or(base.defaults.copy_files),
+            git_backend: override_cfg
+                .defaults
+                .git_backend
+                .or(base.defaults.git_backend),
         },
         gc: GcConfig {
             stale_days: override_cfg.gc.stale_days.or(base.gc.stale_days),
+            auto: override_cfg.gc.auto.or(base.gc.auto),
+            auto_interval: override_cfg.gc.auto_interval.or(base.gc.auto_interval),
+            max_total_size: override_cfg.gc.max_total_size.or(base.gc.max_total_size),
         },
         verify: VerifyConfig {
             rust: override_cfg.verify.rust.or(base.verify.rust),
             node: override_cfg.verify.node.or(base.verify.node),
             python: override_cfg.verify.python.or(base.verify.python),
+            pre: override_cfg.verify.pre.or(base.verify.pre),
+            post: override_cfg.verify.post.or(base.verify.post),
+            fix_rust: override_cfg.verify.fix_rust.or(base.verify.fix_rust),
+            fix_node: override_cfg.verify.fix_node.or(base.verify.fix_node),
+            fix_python: override_cfg.verify.fix_python.or(base.verify.fix_python),
+            fix_commit: override_cfg.verify.fix_commit.or(base.verify.fix_commit),
         },
         worktree: WorktreeConfig {
             link: override_cfg.worktree.link.or(base.worktree.link),
         },
+        apply: ApplyConfig {
+            mode: override_cfg.apply.mode.or(base.apply.mode),
+            trailers: override_cfg.apply.trailers.or(base.apply.trailers),
+            protected: override_cfg.apply.protected.or(base.apply.protected),
+        },
+        sync: SyncConfig {
+            mode: override_cfg.sync.mode.or(base.sync.mode),
+            skip_locked: override_cfg.sync.skip_locked.or(base.sync.skip_locked),
+        },
+        archive: ArchiveConfig {
+            enabled: override_cfg.archive.enabled.or(base.archive.enabled),
+            retention_days: override_cfg
+                .archive
+                .retention_days
+                .or(base.archive.retention_days),
+        },
+        ui: UiConfig {
+            status_sort: override_cfg.ui.status_sort.or(base.ui.status_sort),
+            time_format: override_cfg.ui.time_format.or(base.ui.time_format),
+            subject_width: override_cfg.ui.subject_width.or(base.ui.subject_width),
+            recent_files: override_cfg.ui.recent_files.or(base.ui.recent_files),
+            recent_files_max_scan: override_cfg
+                .ui
+                .recent_files_max_scan
+                .or(base.ui.recent_files_max_scan),
+            path_style: override_cfg.ui.path_style.or(base.ui.path_style),
+            pager: override_cfg.ui.pager.or(base.ui.pager),
+        },
+        exec: ExecConfig {
+            shell: override_cfg.exec.shell.or(base.exec.shell),
+        },
+        meta: MetaConfig {
+            backend: override_cfg.meta.backend.or(base.meta.backend),
+            remote: override_cfg.meta.remote.or(base.meta.remote),
+        },
+        open: OpenConfig {
+            generate: override_cfg.open.generate.or(base.open.generate),
+        },
+        issue: IssueConfig {
+            provider: override_cfg.issue.provider.or(base.issue.provider),
+            jira_url: override_cfg.issue.jira_url.or(base.issue.jira_url),
+        },
+        forge: ForgeConfig {
+            provider: override_cfg.forge.provider.or(base.forge.provider),
+            cache_ttl: override_cfg.forge.cache_ttl.or(base.forge.cache_ttl),
+            min_interval: override_cfg.forge.min_interval.or(base.forge.min_interval),
+        },
+        hooks: HooksConfig {
+            post_add: override_cfg.hooks.post_add.or(base.hooks.post_add),
+            pre_del: override_cfg.hooks.pre_del.or(base.hooks.pre_del),
+            post_sync: override_cfg.hooks.post_sync.or(base.hooks.post_sync),
+            post_apply: override_cfg.hooks.post_apply.or(base.hooks.post_apply),
+        },
+        subdirs: {
+            let mut subdirs = base.subdirs;
+            subdirs.extend(override_cfg.subdirs);
+            subdirs
+        },
+        command_defaults: {
+            let mut defaults = base.command_defaults;
+            defaults.extend(override_cfg.command_defaults);
+            defaults
+        },
+        template: {
+            let mut templates = base.template;
+            templates.extend(override_cfg.template);
+            templates
+        },
     }
 }
 
+/// Branch prefix used when none is configured: the slugified git `user.name`, the
+/// local part of `user.email`, or `$USER`, falling back to `wt/`.
+fn default_branch_prefix() -> &'static str {
+    static PREFIX: OnceLock<String> = OnceLock::new();
+    PREFIX.get_or_init(|| {
+        let git_config = |key: &str| {
+            Command::new("git")
+                .args(["config", "--get", key])
+                .output()
+                .ok()
+                .filter(|out| out.status.success())
+                .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        };
+        let candidates = [
+            git_config("user.name"),
+            git_config("user.email").map(|email| email.split('@').next().unwrap_or("").to_string()),
+            env::var("USER").or_else(|_| env::var("USERNAME")).ok(),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .map(|value| crate::names::slugify(&value))
+            .find(|slug| !slug.is_empty())
+            .map(|slug| format!("{}/", slug))
+            .unwrap_or_else(|| "wt/".to_string())
+    })
+}
+
+/// Parse `"50G"`, `"500M"`, `"1.5T"` or plain bytes (powers of 1024).
+pub fn parse_size(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (num, unit) = input.split_at(split);
+    let value: f64 = num.parse().ok()?;
+    let scale: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" | "KI" => 1 << 10,
+        "M" | "MI" => 1 << 20,
+        "G" | "GI" => 1 << 30,
+        "T" | "TI" => 1 << 40,
+        _ => return None,
+    };
+    Some((value * scale as f64) as u64)
+}
+
 fn suggest_key(input: &str, candidates: &[&str]) -> Option<String> {
     candidates
         .iter()
@@ -264,12 +1081,43 @@ pub fn gw_home() -> Option<PathBuf> {
     Some(home.join(".gw"))
 }
 
+/// `~/.gw/workspaces.toml`: `[repos] name = "path"` entries for `--repo` and `--workspace`.
+#[derive(Debug, Default, Deserialize)]
+struct WorkspacesFile {
+    #[serde(default)]
+    repos: BTreeMap<String, String>,
+}
+
+/// Registered repositories by name, with `~/` expanded. Empty if the file is missing.
+pub fn load_workspaces() -> anyhow::Result<BTreeMap<String, PathBuf>> {
+    let path = match gw_home() {
+        Some(home) => home.join("workspaces.toml"),
+        None => return Ok(BTreeMap::new()),
+    };
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let parsed: WorkspacesFile = toml::from_str(&fs::read_to_string(&path)?)?;
+    Ok(parsed
+        .repos
+        .into_iter()
+        .map(|(name, repo)| (name, expand_home(&repo)))
+        .collect())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
 fn global_config_path() -> Option<PathBuf> {
     let home = gw_home()?;
     Some(home.join("config.toml"))
 }
 
-fn home_dir() -> Option<PathBuf> {
+pub fn home_dir() -> Option<PathBuf> {
     if let Ok(home) = env::var("HOME") {
         return Some(PathBuf::from(home));
     }
@@ -284,6 +1132,27 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("500M"), Some(500 << 20));
+        assert_eq!(parse_size("50G"), Some(50 << 30));
+        assert_eq!(parse_size("1.5 GiB"), Some(3 << 29));
+        assert_eq!(parse_size("2x"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn workspaces_file_parses_repos() {
+        let parsed: WorkspacesFile =
+            toml::from_str("[repos]\napi = \"/src/api\"\nweb = \"~/src/web\"\n").unwrap();
+        assert_eq!(parsed.repos.len(), 2);
+        assert_eq!(expand_home("/src/api"), PathBuf::from("/src/api"));
+        if let Some(home) = home_dir() {
+            assert_eq!(expand_home("~/src/web"), home.join("src/web"));
+        }
+    }
+
     #[test]
     fn validate_empty_config_no_warnings() {
         let dir = tempfile::tempdir().unwrap();
@@ -311,6 +1180,8 @@ stale_days = 14
 
 [verify]
 rust = "cargo check"
+fix_rust = "cargo fmt"
+fix_commit = true
 "#,
         )
         .unwrap();
@@ -391,6 +1262,47 @@ subdir = "/services/app"
         assert!(warnings[0].contains("should not start with '/'"));
     }
 
+    #[test]
+    fn validate_mode_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let gw_dir = dir.path().join(".gw");
+        fs::create_dir_all(&gw_dir).unwrap();
+        fs::write(
+            gw_dir.join("config.toml"),
+            r#"
+[apply]
+mode = "squash"
+
+[sync]
+mode = "squash"
+"#,
+        )
+        .unwrap();
+        let warnings = Config::validate(dir.path());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'sync.mode'"));
+    }
+
+    #[test]
+    fn validate_template_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let gw_dir = dir.path().join(".gw");
+        fs::create_dir_all(&gw_dir).unwrap();
+        fs::write(
+            gw_dir.join("config.toml"),
+            r#"
+[template.hotfix]
+base = "release"
+tags = ["hotfix"]
+bootstrp = "npm ci"
+"#,
+        )
+        .unwrap();
+        let warnings = Config::validate(dir.path());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'template.hotfix.bootstrp'"));
+    }
+
     #[test]
     fn validate_no_config_file_no_warnings() {
         let dir = tempfile::tempdir().unwrap();
@@ -398,6 +1310,21 @@ subdir = "/services/app"
         assert!(warnings.is_empty());
     }
 
+    #[test]
+    fn apply_protected_matches_globs() {
+        let config = Config {
+            apply: ApplyConfig {
+                protected: Some(vec!["main".to_string(), "release/*".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.apply_protected("main"));
+        assert!(config.apply_protected("release/1.2"));
+        assert!(!config.apply_protected("maintenance"));
+        assert!(!Config::default().apply_protected("main"));
+    }
+
     #[test]
     fn merge_subdir_override() {
         let base = Config {
@@ -1,28 +1,68 @@
-use crate::GwError;
+use crate::{ErrorKind, GwError};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 
-#[derive(Clone)]
-pub struct Git;
+#[cfg(feature = "libgit2")]
+mod libgit2;
+
+#[derive(Clone, Default)]
+pub struct Git {
+    /// Repository root whose worktree, branch, status and log queries go
+    /// through libgit2 (`[defaults] git_backend = "libgit2"`); `None` runs git.
+    #[cfg(feature = "libgit2")]
+    libgit2: Option<PathBuf>,
+}
 
 #[derive(Debug, Clone)]
 pub struct Worktree {
     pub path: PathBuf,
     pub branch: Option<String>,
     pub head: Option<String>,
+    /// Locked with `git worktree lock`
+    pub locked: bool,
+    /// Reported `prunable` by git (gitdir points at a missing location)
+    pub prunable: bool,
+}
+
+/// Per-branch data that `status` and `gc` would otherwise query per worktree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BranchInfo {
+    pub commit_time: i64,
+    pub subject: String,
+    /// Commits (ahead, behind) relative to the upstream, if one is configured and exists
+    pub upstream: Option<(usize, usize)>,
+    /// The configured upstream was deleted on the remote
+    pub upstream_gone: bool,
+}
+
+impl Worktree {
+    /// The worktree directory is gone or git considers it prunable.
+    pub fn is_broken(&self) -> bool {
+        self.prunable || !self.path.exists()
+    }
 }
 
 impl Git {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Answer queries about the repository at `repo_root` through libgit2
+    /// where it can, falling back to git.
+    #[cfg(feature = "libgit2")]
+    pub fn with_libgit2(repo_root: &Path) -> Self {
+        Self {
+            libgit2: Some(repo_root.to_path_buf()),
+        }
     }
 
     pub fn repo_root(&self) -> Result<PathBuf, String> {
         let toplevel = self.run(&["rev-parse", "--show-toplevel"])?;
-        let toplevel_path = PathBuf::from(toplevel.trim());
+        let toplevel_path = crate::paths::from_git(&toplevel);
         let common = self.run(&["rev-parse", "--git-common-dir"])?;
-        let mut common_path = PathBuf::from(common.trim());
+        let mut common_path = crate::paths::from_git(&common);
         if common_path.is_relative() {
             // --git-common-dir returns a path relative to CWD, not toplevel
             let cwd = cwd_fallback()
@@ -30,36 +70,61 @@ impl Git {
                 .ok_or_else(|| "failed to get current directory".to_string())?;
             common_path = cwd.join(common_path);
         }
-        common_path = common_path.canonicalize().unwrap_or(common_path);
+        common_path = crate::paths::canonical(&common_path);
         let root = root_from_common_dir(&common_path).unwrap_or(toplevel_path);
         Ok(root)
     }
 
     pub fn current_toplevel(&self) -> Result<PathBuf, String> {
         let out = self.run(&["rev-parse", "--show-toplevel"])?;
-        Ok(PathBuf::from(out.trim()))
+        Ok(crate::paths::from_git(&out))
     }
 
     pub fn run(&self, args: &[&str]) -> Result<String, String> {
-        let mut cmd = Command::new("git");
         // If CWD doesn't exist (deleted worktree), run from a valid ancestor
-        if let Some(dir) = cwd_fallback() {
-            cmd.current_dir(dir);
+        let dir = cwd_fallback().or_else(|| std::env::current_dir().ok());
+        self.run_cached(dir.as_deref(), args)
+    }
+
+    pub fn run_in(&self, dir: &Path, args: &[&str]) -> Result<String, String> {
+        self.run_cached(Some(dir), args)
+    }
+
+    /// Read-only queries are answered from the per-invocation cache; any other
+    /// command may change the repository, so it runs and clears the cache.
+    fn run_cached(&self, dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+        if !is_query(args) {
+            self.clear_cache();
+            return spawn(dir, args);
         }
-        let output = cmd
-            .args(args)
-            .output()
-            .map_err(|e| format!("git execution failed: {}", e))?;
-        if !output.status.success() {
-            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        let key = (
+            dir.map(Path::to_path_buf).unwrap_or_default(),
+            args.iter().map(|a| a.to_string()).collect(),
+        );
+        if let Some(hit) = query_cache().lock().unwrap().get(&key) {
+            return hit.clone();
         }
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        let result = spawn(dir, args);
+        query_cache().lock().unwrap().insert(key, result.clone());
+        result
     }
 
-    pub fn run_in(&self, dir: &Path, args: &[&str]) -> Result<String, String> {
+    /// Forget cached query results, e.g. before a long-running command polls again.
+    pub fn clear_cache(&self) {
+        query_cache().lock().unwrap().clear();
+    }
+
+    pub fn run_in_env(
+        &self,
+        dir: &Path,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Result<String, String> {
+        self.clear_cache();
         let output = Command::new("git")
             .current_dir(dir)
             .args(args)
+            .envs(envs.iter().copied())
             .output()
             .map_err(|e| format!("git execution failed: {}", e))?;
         if !output.status.success() {
@@ -68,57 +133,236 @@ impl Git {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Runs git in `dir` with inherited stdio so pagers and colors work.
+    pub fn run_passthrough(&self, dir: &Path, args: &[&str]) -> Result<bool, String> {
+        self.clear_cache();
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .status()
+            .map_err(|e| format!("git execution failed: {}", e))?;
+        Ok(status.success())
+    }
+
     pub fn worktrees(&self) -> Result<Vec<Worktree>, String> {
-        let out = self.run(&["worktree", "list", "--porcelain"])?;
-        let mut result = Vec::new();
-        let mut current: Option<Worktree> = None;
-        for line in out.lines() {
-            if line.starts_with("worktree ") {
-                if let Some(wt) = current.take() {
-                    result.push(wt);
-                }
-                let path = line.trim_start_matches("worktree ");
-                current = Some(Worktree {
-                    path: PathBuf::from(path),
-                    branch: None,
-                    head: None,
-                });
-            } else if line.starts_with("branch ") {
-                if let Some(ref mut wt) = current {
-                    let branch = line.trim_start_matches("branch ");
-                    wt.branch = Some(branch.trim().to_string());
-                }
-            } else if line.starts_with("HEAD ") {
-                if let Some(ref mut wt) = current {
-                    wt.head = Some(line.trim_start_matches("HEAD ").trim().to_string());
-                }
-            }
+        #[cfg(feature = "libgit2")]
+        if let Some(worktrees) = self.libgit2.as_deref().and_then(libgit2::worktrees) {
+            return Ok(worktrees);
         }
-        if let Some(wt) = current {
-            result.push(wt);
-        }
-        Ok(result)
+        let out = self.run(&["worktree", "list", "--porcelain"])?;
+        Ok(parse_worktree_list(&out))
     }
 
     pub fn branch_exists(&self, branch: &str) -> bool {
+        #[cfg(feature = "libgit2")]
+        if let Some(exists) = self
+            .libgit2
+            .as_deref()
+            .and_then(|root| libgit2::branch_exists(root, branch))
+        {
+            return exists;
+        }
         self.run(&["show-ref", "--verify", &format!("refs/heads/{}", branch)])
             .is_ok()
     }
 
+    /// `git status --porcelain` of the worktree at `dir`.
+    pub fn status_porcelain(&self, dir: &Path) -> Result<String, String> {
+        #[cfg(feature = "libgit2")]
+        if let Some(out) = self
+            .libgit2
+            .as_ref()
+            .and_then(|_| libgit2::status_porcelain(dir))
+        {
+            return Ok(out);
+        }
+        self.run_in(dir, &["status", "--porcelain"])
+    }
+
+    /// Committer time (unix) and subject of the commit checked out at `dir`.
+    pub fn last_commit(&self, dir: &Path) -> Option<(i64, String)> {
+        #[cfg(feature = "libgit2")]
+        if let Some(commit) = self
+            .libgit2
+            .as_ref()
+            .and_then(|_| libgit2::last_commit(dir))
+        {
+            return Some(commit);
+        }
+        let out = self.run_in(dir, &["log", "-1", "--format=%ct|%s"]).ok()?;
+        let mut parts = out.trim().splitn(2, '|');
+        let ts = parts.next().unwrap_or("").trim();
+        let subject = parts.next().unwrap_or("").trim();
+        Some((ts.parse().unwrap_or(0), subject.to_string()))
+    }
+
     pub fn current_branch(&self, repo_root: &Path) -> Result<String, String> {
         let out = self.run_in(repo_root, &["rev-parse", "--abbrev-ref", "HEAD"])?;
         Ok(out.trim().to_string())
     }
 
-    pub fn resolve_base(&self, repo_root: &Path, default_base: Option<String>) -> Result<String, String> {
+    /// Fetch and prune all remotes quietly. Does nothing without remotes.
+    pub fn fetch_all(&self) -> Result<(), String> {
+        if self.run(&["remote"])?.trim().is_empty() {
+            return Ok(());
+        }
+        self.run(&["fetch", "--all", "--prune", "--quiet"])
+            .map(|_| ())
+    }
+
+    /// The git directory shared by all worktrees (`.git` of the main checkout).
+    pub fn common_dir(&self, repo_root: &Path) -> Result<PathBuf, String> {
+        let out = self.run_in(repo_root, &["rev-parse", "--git-common-dir"])?;
+        Ok(crate::paths::canonical(
+            &repo_root.join(crate::paths::from_git(&out)),
+        ))
+    }
+
+    /// Append the `patterns` not yet listed to the shared `info/exclude`, which
+    /// applies to every worktree of the repository.
+    pub fn add_excludes(&self, repo_root: &Path, patterns: &[String]) -> Result<(), String> {
+        let path = self.common_dir(repo_root)?.join("info").join("exclude");
+        let mut data = std::fs::read_to_string(&path).unwrap_or_default();
+        let missing: Vec<&String> = patterns
+            .iter()
+            .filter(|p| !data.lines().any(|line| line.trim() == p.as_str()))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if !data.is_empty() && !data.ends_with('\n') {
+            data.push('\n');
+        }
+        for pattern in missing {
+            data.push_str(pattern);
+            data.push('\n');
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, data).map_err(|e| e.to_string())
+    }
+
+    /// Unix time of the last fetch, from the mtime of FETCH_HEAD.
+    pub fn last_fetch_time(&self, repo_root: &Path) -> Option<i64> {
+        let out = self
+            .run_in(repo_root, &["rev-parse", "--git-common-dir"])
+            .ok()?;
+        let common = repo_root.join(crate::paths::from_git(&out));
+        let modified = std::fs::metadata(common.join("FETCH_HEAD"))
+            .ok()?
+            .modified()
+            .ok()?;
+        let secs = modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(secs as i64)
+    }
+
+    /// Tip commit and upstream state of every local branch, from one `for-each-ref`.
+    pub fn branch_infos(&self) -> HashMap<String, BranchInfo> {
+        #[cfg(feature = "libgit2")]
+        if let Some(infos) = self.libgit2.as_deref().and_then(libgit2::branch_infos) {
+            return infos;
+        }
+        self.run(&[
+            "for-each-ref",
+            "--format=%(refname:short)%00%(committerdate:unix)%00%(upstream)%00%(upstream:track,nobracket)%00%(subject)",
+            "refs/heads",
+        ])
+        .map(|out| parse_branch_infos(&out))
+        .unwrap_or_default()
+    }
+
+    /// Paths that would conflict when merging `ours` and `theirs`, computed with
+    /// `git merge-tree` without touching any worktree; empty when they merge cleanly.
+    pub fn merge_conflicts(
+        &self,
+        dir: &Path,
+        ours: &str,
+        theirs: &str,
+    ) -> Result<Vec<String>, String> {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args([
+                "merge-tree",
+                "--write-tree",
+                "--name-only",
+                "--no-messages",
+                ours,
+                theirs,
+            ])
+            .output()
+            .map_err(|e| format!("git execution failed: {}", e))?;
+        // Exit 1 means conflicts: the tree id comes first, then one path per line
+        match output.status.code() {
+            Some(0) => Ok(Vec::new()),
+            Some(1) => {
+                let mut paths: Vec<String> = Vec::new();
+                for line in String::from_utf8_lossy(&output.stdout).lines().skip(1) {
+                    // A path is listed once per conflicting stage
+                    if !line.is_empty() && !paths.iter().any(|p| p == line) {
+                        paths.push(line.to_string());
+                    }
+                }
+                Ok(paths)
+            }
+            _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        }
+    }
+
+    /// Short name of the branch `refs/remotes/origin/HEAD` points at.
+    pub fn origin_head(&self) -> Option<String> {
+        let out = self
+            .run(&["symbolic-ref", "refs/remotes/origin/HEAD"])
+            .ok()?;
+        let branch = out.trim().trim_start_matches("refs/remotes/origin/");
+        (!branch.is_empty()).then(|| branch.to_string())
+    }
+
+    /// Fetch origin and re-detect its default branch. Returns (old, new) when
+    /// origin/HEAD moved; does nothing without an `origin` remote.
+    pub fn refresh_origin(&self) -> Result<Option<(String, String)>, String> {
+        if self.run(&["remote", "get-url", "origin"]).is_err() {
+            return Ok(None);
+        }
+        let before = self.origin_head();
+        self.run(&["fetch", "origin", "--prune"])?;
+        self.run(&["remote", "set-head", "origin", "--auto"])?;
+        let after = self.origin_head();
+        match (before, after) {
+            (Some(old), Some(new)) if old != new => Ok(Some((old, new))),
+            _ => Ok(None),
+        }
+    }
+
+    /// After a fetch, `origin/<base>` when `base` is a local branch with a
+    /// remote-tracking counterpart: the fetch updated that ref, not the branch.
+    pub fn fetched_start_point(&self, base: &str) -> Option<String> {
+        if !self.branch_exists(base) {
+            return None;
+        }
+        let remote = format!("refs/remotes/origin/{}", base);
+        self.run(&["show-ref", "--verify", &remote])
+            .ok()
+            .map(|_| format!("origin/{}", base))
+    }
+
+    pub fn resolve_base(
+        &self,
+        repo_root: &Path,
+        default_base: Option<String>,
+    ) -> Result<String, String> {
         if let Some(base) = default_base {
             return Ok(base);
         }
-        if let Ok(out) = self.run(&["symbolic-ref", "refs/remotes/origin/HEAD"]) {
-            let branch = out.trim().trim_start_matches("refs/remotes/origin/");
-            if !branch.is_empty() {
-                return Ok(branch.to_string());
+        if let Some(branch) = self.origin_head() {
+            // Fall back to the remote-tracking ref when there is no local branch yet
+            if self.branch_exists(&branch) {
+                return Ok(branch);
             }
+            return Ok(format!("origin/{}", branch));
         }
         if self
             .run(&["show-ref", "--verify", "refs/heads/main"])
@@ -142,25 +386,138 @@ impl Git {
     }
 }
 
+/// Parse `git worktree list --porcelain`.
+fn parse_worktree_list(out: &str) -> Vec<Worktree> {
+    let mut result = Vec::new();
+    let mut current: Option<Worktree> = None;
+    for line in out.lines() {
+        if line.starts_with("worktree ") {
+            if let Some(wt) = current.take() {
+                result.push(wt);
+            }
+            let path = line.trim_start_matches("worktree ");
+            current = Some(Worktree {
+                path: crate::paths::from_git(path),
+                branch: None,
+                head: None,
+                locked: false,
+                prunable: false,
+            });
+        } else if line.starts_with("branch ") {
+            if let Some(ref mut wt) = current {
+                let branch = line.trim_start_matches("branch ");
+                wt.branch = Some(branch.trim().to_string());
+            }
+        } else if line.starts_with("HEAD ") {
+            if let Some(ref mut wt) = current {
+                wt.head = Some(line.trim_start_matches("HEAD ").trim().to_string());
+            }
+        } else if line == "locked" || line.starts_with("locked ") {
+            if let Some(ref mut wt) = current {
+                wt.locked = true;
+            }
+        } else if line == "prunable" || line.starts_with("prunable ") {
+            if let Some(ref mut wt) = current {
+                wt.prunable = true;
+            }
+        }
+    }
+    if let Some(wt) = current {
+        result.push(wt);
+    }
+    result
+}
+
+fn parse_branch_infos(out: &str) -> HashMap<String, BranchInfo> {
+    let mut result = HashMap::new();
+    for line in out.lines() {
+        let fields: Vec<&str> = line.splitn(5, '\0').collect();
+        let [name, time, upstream, track, subject] = fields[..] else {
+            continue;
+        };
+        let upstream_gone = track == "gone";
+        let counts = if upstream.is_empty() || upstream_gone {
+            None
+        } else {
+            // "ahead 2, behind 1", "ahead 2", "behind 1", or empty when in sync
+            let mut ahead = 0;
+            let mut behind = 0;
+            for part in track.split(", ") {
+                match part.split_once(' ') {
+                    Some(("ahead", n)) => ahead = n.parse().unwrap_or(0),
+                    Some(("behind", n)) => behind = n.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+            Some((ahead, behind))
+        };
+        result.insert(
+            name.to_string(),
+            BranchInfo {
+                commit_time: time.parse().unwrap_or(0),
+                subject: subject.to_string(),
+                upstream: counts,
+                upstream_gone,
+            },
+        );
+    }
+    result
+}
+
+type QueryCache = HashMap<(PathBuf, Vec<String>), Result<String, String>>;
+
+fn query_cache() -> &'static Mutex<QueryCache> {
+    static CACHE: OnceLock<Mutex<QueryCache>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Git invocations that only read repository state and are safe to memoize.
+fn is_query(args: &[&str]) -> bool {
+    matches!(
+        args,
+        ["rev-parse" | "for-each-ref" | "show-ref" | "merge-base", ..]
+            | ["worktree", "list", ..]
+            | ["branch", "--merged", ..]
+            | ["remote"]
+    )
+}
+
+fn spawn(dir: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let output = cmd
+        .args(args)
+        .output()
+        .map_err(|e| format!("git execution failed: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 pub fn git_error(msg: impl Into<String>) -> GwError {
-    GwError::new(2, msg)
+    GwError::new(ErrorKind::Git, msg)
 }
 
 /// Returns a valid ancestor directory when CWD is deleted, or None if CWD is fine.
 /// Cached via OnceLock since CWD won't change during a single process invocation.
 fn cwd_fallback() -> Option<PathBuf> {
     static FALLBACK: OnceLock<Option<PathBuf>> = OnceLock::new();
-    FALLBACK.get_or_init(|| {
-        match std::env::current_dir() {
-            Ok(cwd) if cwd.exists() => None,
-            Ok(cwd) => walk_up_to_existing(&cwd),
-            Err(_) => {
-                // macOS: current_dir() fails when CWD is deleted; use PWD env var
-                let pwd = PathBuf::from(std::env::var("PWD").ok()?);
-                walk_up_to_existing(&pwd)
+    FALLBACK
+        .get_or_init(|| {
+            match std::env::current_dir() {
+                Ok(cwd) if cwd.exists() => None,
+                Ok(cwd) => walk_up_to_existing(&cwd),
+                Err(_) => {
+                    // macOS: current_dir() fails when CWD is deleted; use PWD env var
+                    let pwd = PathBuf::from(std::env::var("PWD").ok()?);
+                    walk_up_to_existing(&pwd)
+                }
             }
-        }
-    }).clone()
+        })
+        .clone()
 }
 
 fn walk_up_to_existing(path: &Path) -> Option<PathBuf> {
@@ -197,7 +554,8 @@ mod tests {
 
         let git = Git::new();
         git.run_in(&tmp, &["init"]).unwrap();
-        git.run_in(&tmp, &["commit", "--allow-empty", "-m", "init"]).unwrap();
+        git.run_in(&tmp, &["commit", "--allow-empty", "-m", "init"])
+            .unwrap();
 
         let subdir = tmp.join("services").join("app");
         fs::create_dir_all(&subdir).unwrap();
@@ -216,24 +574,113 @@ mod tests {
         let wrong = toplevel.join(&common_path);
         let wrong_resolved = root_from_common_dir(&wrong);
         // The wrong path has unresolved '..' so it won't match the repo root
-        assert_ne!(wrong_resolved, Some(toplevel.clone()),
-            "joining to toplevel should NOT produce the correct root (this is the bug)");
+        assert_ne!(
+            wrong_resolved,
+            Some(toplevel.clone()),
+            "joining to toplevel should NOT produce the correct root (this is the bug)"
+        );
 
         // FIX: joining relative common_path with CWD (subdir) gives correct result
         let correct = subdir.join(&common_path);
         let correct = correct.canonicalize().unwrap();
         let correct_root = root_from_common_dir(&correct).unwrap();
-        assert_eq!(correct_root, toplevel,
-            "joining to CWD + canonicalize should produce the correct repo root");
+        assert_eq!(
+            correct_root, toplevel,
+            "joining to CWD + canonicalize should produce the correct repo root"
+        );
 
         let _ = fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn merge_conflicts_lists_conflicting_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let git = Git::new();
+        let commit = |file: &str, text: &str, msg: &str| {
+            fs::write(dir.join(file), text).unwrap();
+            git.run_in(dir, &["add", file]).unwrap();
+            git.run_in(dir, &["commit", "-m", msg]).unwrap();
+        };
+        git.run_in(dir, &["init", "-b", "main"]).unwrap();
+        commit("a.txt", "base\n", "init");
+        git.run_in(dir, &["branch", "left"]).unwrap();
+        git.run_in(dir, &["branch", "other"]).unwrap();
+        commit("a.txt", "main\n", "main");
+        git.run_in(dir, &["checkout", "-q", "left"]).unwrap();
+        commit("a.txt", "left\n", "left");
+        git.run_in(dir, &["checkout", "-q", "other"]).unwrap();
+        commit("b.txt", "other\n", "other");
+
+        assert_eq!(
+            git.merge_conflicts(dir, "main", "left").unwrap(),
+            vec!["a.txt".to_string()]
+        );
+        assert!(git
+            .merge_conflicts(dir, "main", "other")
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn root_from_common_dir_canonical_path() {
         // .git path without any '..' should work
         let path = PathBuf::from("/home/user/project/.git");
-        assert_eq!(root_from_common_dir(&path), Some(PathBuf::from("/home/user/project")));
+        assert_eq!(
+            root_from_common_dir(&path),
+            Some(PathBuf::from("/home/user/project"))
+        );
+    }
+
+    #[test]
+    fn parse_branch_infos_reads_tracking() {
+        let out: String = [
+            [
+                "main",
+                "1700000000",
+                "refs/remotes/origin/main",
+                "",
+                "Initial commit",
+            ],
+            [
+                "wt/a",
+                "1700000100",
+                "refs/remotes/origin/wt/a",
+                "ahead 2, behind 1",
+                "Fix: a, b",
+            ],
+            [
+                "wt/b",
+                "1700000200",
+                "refs/remotes/origin/wt/b",
+                "gone",
+                "Old",
+            ],
+            ["wt/c", "1700000300", "", "", "Local only"],
+        ]
+        .iter()
+        .map(|fields| fields.join("\0") + "\n")
+        .collect();
+        let infos = parse_branch_infos(&out);
+        assert_eq!(infos["main"].upstream, Some((0, 0)));
+        assert_eq!(infos["main"].commit_time, 1700000000);
+        assert_eq!(infos["wt/a"].upstream, Some((2, 1)));
+        assert_eq!(infos["wt/a"].subject, "Fix: a, b");
+        assert!(infos["wt/b"].upstream_gone);
+        assert_eq!(infos["wt/b"].upstream, None);
+        assert_eq!(infos["wt/c"].upstream, None);
+        assert!(!infos["wt/c"].upstream_gone);
+    }
+
+    #[test]
+    fn only_read_only_commands_are_queries() {
+        assert!(is_query(&["worktree", "list", "--porcelain"]));
+        assert!(is_query(&["rev-parse", "--show-toplevel"]));
+        assert!(is_query(&["branch", "--merged", "main"]));
+        assert!(!is_query(&["worktree", "add", "x"]));
+        assert!(!is_query(&["branch", "-D", "x"]));
+        assert!(!is_query(&["remote", "add", "origin", "url"]));
+        assert!(!is_query(&["status", "--porcelain"]));
     }
 
     #[test]
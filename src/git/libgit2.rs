@@ -0,0 +1,264 @@
+//! `[defaults] git_backend = "libgit2"`: worktree, branch, status and log
+//! queries answered in-process through libgit2 instead of spawning git. Every
+//! function returns `None` when libgit2 can't answer, and the caller runs git.
+
+use super::{BranchInfo, Worktree};
+use git2::{BranchType, Repository, Status, StatusOptions, WorktreeLockStatus};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The main checkout first, then linked worktrees, like `git worktree list`.
+/// Bare repositories keep using git.
+pub fn worktrees(repo_root: &Path) -> Option<Vec<Worktree>> {
+    let repo = Repository::open(repo_root).ok()?;
+    if repo.is_bare() {
+        return None;
+    }
+    let mut result = vec![entry(&repo, normalize(repo.workdir()?), false, false)];
+    for name in repo.worktrees().ok()?.iter().flatten().flatten() {
+        let wt = repo.find_worktree(name).ok()?;
+        let path = normalize(wt.path());
+        let locked = matches!(wt.is_locked(), Ok(WorktreeLockStatus::Locked(_)));
+        let prunable = wt.validate().is_err();
+        result.push(match Repository::open_from_worktree(&wt) {
+            Ok(wt_repo) => entry(&wt_repo, path, locked, prunable),
+            Err(_) => Worktree {
+                path,
+                branch: None,
+                head: None,
+                locked,
+                prunable,
+            },
+        });
+    }
+    Some(result)
+}
+
+fn entry(repo: &Repository, path: PathBuf, locked: bool, prunable: bool) -> Worktree {
+    // An unborn branch has no HEAD commit but still names its branch
+    let branch = repo
+        .find_reference("HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().ok().flatten().map(str::to_string));
+    Worktree {
+        path,
+        branch,
+        head: repo.refname_to_id("HEAD").ok().map(|oid| oid.to_string()),
+        locked,
+        prunable,
+    }
+}
+
+/// libgit2 reports directories with a trailing separator.
+fn normalize(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+pub fn branch_exists(repo_root: &Path, branch: &str) -> Option<bool> {
+    let repo = Repository::open(repo_root).ok()?;
+    let exists = repo.find_branch(branch, BranchType::Local).is_ok();
+    Some(exists)
+}
+
+/// Working tree changes of the worktree at `dir`, formatted like
+/// `git status --porcelain` (untracked directories collapsed to `dir/`).
+pub fn status_porcelain(dir: &Path) -> Option<String> {
+    let repo = Repository::open(dir).ok()?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .include_ignored(false)
+        .renames_head_to_index(true);
+    let statuses = repo.statuses(Some(&mut opts)).ok()?;
+    let mut out = String::new();
+    for entry in statuses.iter() {
+        let Some((x, y)) = porcelain_code(entry.status()) else {
+            continue;
+        };
+        // Non-UTF-8 paths are left to git
+        let path = entry.path().ok()?;
+        let renamed_from = entry
+            .head_to_index()
+            .filter(|_| x == 'R')
+            .and_then(|delta| delta.old_file().path().map(Path::to_path_buf));
+        match renamed_from {
+            Some(from) => out.push_str(&format!("{}{} {} -> {}\n", x, y, from.display(), path)),
+            None => out.push_str(&format!("{}{} {}\n", x, y, path)),
+        }
+    }
+    Some(out)
+}
+
+/// The two-letter `XY` code `git status --porcelain` prints for `status`.
+fn porcelain_code(status: Status) -> Option<(char, char)> {
+    if status.is_ignored() {
+        return None;
+    }
+    if status.is_conflicted() {
+        return Some(('U', 'U'));
+    }
+    if status == Status::WT_NEW {
+        return Some(('?', '?'));
+    }
+    let x = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        ' '
+    };
+    let y = if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else {
+        ' '
+    };
+    (x != ' ' || y != ' ').then_some((x, y))
+}
+
+/// Committer time and subject of the commit checked out at `dir`.
+pub fn last_commit(dir: &Path) -> Option<(i64, String)> {
+    let repo = Repository::open(dir).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    let subject = commit
+        .summary()
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+        .to_string();
+    Some((commit.time().seconds(), subject))
+}
+
+/// Tip commit and upstream state of every local branch.
+pub fn branch_infos(repo_root: &Path) -> Option<HashMap<String, BranchInfo>> {
+    let repo = Repository::open(repo_root).ok()?;
+    let mut result = HashMap::new();
+    for branch in repo.branches(Some(BranchType::Local)).ok()? {
+        let Ok((branch, _)) = branch else {
+            continue;
+        };
+        let (Ok(Some(name)), Ok(refname)) = (branch.name(), branch.get().name()) else {
+            continue;
+        };
+        let Ok(commit) = branch.get().peel_to_commit() else {
+            continue;
+        };
+        let mut info = BranchInfo {
+            commit_time: commit.time().seconds(),
+            subject: commit
+                .summary()
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+                .to_string(),
+            ..Default::default()
+        };
+        // A configured upstream whose ref is missing was deleted on the remote
+        if let Ok(upstream) = repo.branch_upstream_name(refname) {
+            match upstream
+                .as_str()
+                .ok()
+                .and_then(|upstream| repo.refname_to_id(upstream).ok())
+            {
+                Some(upstream) => {
+                    info.upstream = repo.graph_ahead_behind(commit.id(), upstream).ok();
+                }
+                None => info.upstream_gone = true,
+            }
+        }
+        result.insert(name.to_string(), info);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::Git;
+    use std::fs;
+
+    #[test]
+    fn porcelain_codes_match_git() {
+        assert_eq!(porcelain_code(Status::WT_NEW), Some(('?', '?')));
+        assert_eq!(porcelain_code(Status::INDEX_NEW), Some(('A', ' ')));
+        assert_eq!(
+            porcelain_code(Status::INDEX_MODIFIED | Status::WT_MODIFIED),
+            Some(('M', 'M'))
+        );
+        assert_eq!(porcelain_code(Status::WT_DELETED), Some((' ', 'D')));
+        assert_eq!(porcelain_code(Status::CONFLICTED), Some(('U', 'U')));
+        assert_eq!(porcelain_code(Status::IGNORED), None);
+        assert_eq!(porcelain_code(Status::CURRENT), None);
+    }
+
+    #[test]
+    fn answers_match_the_git_cli() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = crate::paths::canonical(tmp.path());
+        let git = Git::new();
+        git.run_in(&dir, &["init", "-q", "-b", "main"]).unwrap();
+        fs::write(dir.join("a.txt"), "a\n").unwrap();
+        git.run_in(&dir, &["add", "a.txt"]).unwrap();
+        git.run_in(&dir, &["commit", "-q", "-m", "init: a"])
+            .unwrap();
+        let linked = dir.join("wt");
+        let linked_str = linked.to_string_lossy().to_string();
+        git.run_in(&dir, &["worktree", "add", "-q", "-b", "wt/x", &linked_str])
+            .unwrap();
+        git.run_in(&dir, &["worktree", "lock", &linked_str])
+            .unwrap();
+        git.run_in(&dir, &["branch", "-q", "--set-upstream-to=main", "wt/x"])
+            .unwrap();
+        fs::write(linked.join("a.txt"), "changed\n").unwrap();
+        fs::write(linked.join("new.txt"), "new\n").unwrap();
+
+        let cli = crate::git::parse_worktree_list(
+            &git.run_in(&dir, &["worktree", "list", "--porcelain"])
+                .unwrap(),
+        );
+        let native = worktrees(&dir).unwrap();
+        let summary = |list: &[Worktree]| -> Vec<_> {
+            list.iter()
+                .map(|wt| {
+                    (
+                        wt.path.clone(),
+                        wt.branch.clone(),
+                        wt.head.clone(),
+                        wt.locked,
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(summary(&native), summary(&cli));
+
+        assert_eq!(branch_exists(&dir, "wt/x"), Some(true));
+        assert_eq!(branch_exists(&dir, "wt/missing"), Some(false));
+
+        let mut lines: Vec<String> = status_porcelain(&linked)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        lines.sort();
+        assert_eq!(lines, [" M a.txt", "?? new.txt"]);
+
+        let (time, subject) = last_commit(&linked).unwrap();
+        assert_eq!(subject, "init: a");
+        assert!(time > 0);
+        let infos = branch_infos(&dir).unwrap();
+        assert_eq!(infos["wt/x"].upstream, Some((0, 0)));
+        assert_eq!(infos["main"].upstream, None);
+        assert_eq!(infos["main"].subject, "init: a");
+    }
+}
@@ -1,29 +1,86 @@
 mod cli;
+mod cmd;
 mod config;
 mod git;
 mod meta;
-mod cmd;
+mod names;
+mod oplock;
+mod paths;
 
 use crate::cli::{Cli, Commands};
 use crate::config::Config;
-use crate::git::Git;
+use crate::git::{git_error, Git, Worktree};
 use crate::meta::MetaStore;
+use crate::oplock::OpLock;
 use clap::Parser;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
+/// Category of a gw failure; each maps to a documented process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Anything else, including I/O errors (exit 1)
+    General,
+    /// A git command failed (exit 2)
+    Git,
+    /// A verify command failed (exit 3)
+    Verify,
+    /// Merge, rebase or patch conflict (exit 4)
+    Conflict,
+    /// Unknown worktree, stash or metadata (exit 5)
+    NotFound,
+    /// Uncommitted or unpushed work blocks the operation (exit 6)
+    Dirty,
+    /// Worktree, repository or daemon lock is held (exit 7)
+    Locked,
+    /// Config file could not be loaded (exit 8)
+    Config,
+    /// Invalid name, argument or option value (exit 9)
+    InvalidInput,
+    /// Worktree path or branch already exists (exit 10)
+    AlreadyExists,
+    /// A `[hooks]` command failed (exit 11)
+    Hook,
+    /// Explicit code: plugin exit status passthrough, JSON-RPC error codes
+    Custom(i32),
+}
+
+impl ErrorKind {
+    pub fn code(self) -> i32 {
+        match self {
+            ErrorKind::General => 1,
+            ErrorKind::Git => 2,
+            ErrorKind::Verify => 3,
+            ErrorKind::Conflict => 4,
+            ErrorKind::NotFound => 5,
+            ErrorKind::Dirty => 6,
+            ErrorKind::Locked => 7,
+            ErrorKind::Config => 8,
+            ErrorKind::InvalidInput => 9,
+            ErrorKind::AlreadyExists => 10,
+            ErrorKind::Hook => 11,
+            ErrorKind::Custom(code) => code,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct GwError {
-    pub code: i32,
+    pub kind: ErrorKind,
     pub message: String,
 }
 
 impl GwError {
-    pub fn new(code: i32, message: impl Into<String>) -> Self {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
-            code,
+            kind,
             message: message.into(),
         }
     }
+
+    pub fn code(&self) -> i32 {
+        self.kind.code()
+    }
 }
 
 pub type Result<T> = std::result::Result<T, GwError>;
@@ -38,6 +95,20 @@ pub struct Context {
     pub quiet: bool,
     pub json: bool,
     pub color: String,
+    /// `--yes`: confirmation prompts are answered yes
+    pub yes: bool,
+    /// Prompts can be shown: stdin is a terminal and `--no-input` was not given
+    pub interactive: bool,
+    /// `--no-hooks`: `[hooks]` commands are skipped
+    pub no_hooks: bool,
+}
+
+impl Context {
+    /// All worktrees, root included. `git worktree list` runs once until
+    /// something changes the repository (see `Git::run`).
+    pub fn worktrees(&self) -> Result<Vec<Worktree>> {
+        self.git.worktrees().map_err(git_error)
+    }
 }
 
 fn main() {
@@ -47,21 +118,104 @@ fn main() {
             if !err.message.is_empty() {
                 eprintln!("{}", err.message);
             }
-            err.code
+            err.code()
         }
     };
     std::process::exit(code);
 }
 
+/// Parses the command line. Usage errors exit with `InvalidInput` so they don't
+/// collide with git failures; `--help` and `--version` exit through clap as usual.
+fn parse_cli<I, T>(args: I) -> Result<Cli>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    Cli::try_parse_from(args).map_err(|err| {
+        if !err.use_stderr() {
+            err.exit();
+        }
+        let _ = err.print();
+        GwError::new(ErrorKind::InvalidInput, "")
+    })
+}
+
 fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = parse_cli(std::env::args_os())?;
+
+    if let Some(spec) = cli.command.workspace() {
+        return cmd::workspace(spec, cli.json);
+    }
+    let chdir = match cli.repo.as_deref() {
+        Some(name) => Some(cmd::workspace_repo(name)?),
+        None => paths::chdir_target(&cli.chdir),
+    };
+    if let Some(dir) = chdir {
+        std::env::set_current_dir(&dir).map_err(|e| {
+            GwError::new(
+                ErrorKind::InvalidInput,
+                format!("cannot change to {}: {}", dir.display(), e),
+            )
+        })?;
+    }
+
+    // Shell setup runs from rc files, usually outside any repository
+    match cli.command {
+        Commands::Completion(args) => return cmd::completion(args),
+        Commands::ShellInit(args) => return cmd::shell_init(args),
+        _ => {}
+    }
 
     let git = Git::new();
-    let repo_root = git.repo_root().map_err(|e| GwError::new(2, e))?;
-    let config = Config::load(&repo_root).map_err(|e| GwError::new(1, e.to_string()))?;
-    let meta = MetaStore::new(&repo_root).map_err(|e| GwError::new(1, e.to_string()))?;
+    let repo_root = git
+        .repo_root()
+        .map_err(|e| GwError::new(ErrorKind::Git, e))?;
+    let config =
+        Config::load(&repo_root).map_err(|e| GwError::new(ErrorKind::Config, e.to_string()))?;
+    #[cfg(feature = "libgit2")]
+    let git = match config.git_backend().as_str() {
+        "libgit2" => Git::with_libgit2(&repo_root),
+        _ => git,
+    };
+    if let Some(args) =
+        cli::with_command_defaults(std::env::args_os().collect(), &config.command_defaults)
+    {
+        cli = parse_cli(args)?;
+    }
+    // The lock is held before metadata is loaded, so a save never starts from
+    // a copy another gw process has since changed
+    let _repo_lock = match cli.command.takes_repo_lock() {
+        // `touch` runs from the shell hook; a busy repository skips the timestamp
+        Some(name) if matches!(cli.command, Commands::Touch(_)) => {
+            match OpLock::try_acquire(&repo_root, &name) {
+                Some(lock) => Some(lock),
+                None => return Ok(()),
+            }
+        }
+        Some(name) => Some(OpLock::acquire(&repo_root, &name, config.lock_timeout())?),
+        None => None,
+    };
+    // `touch` and `root` run from the shell hook on every directory change and must stay cheap
+    let housekeeping = !matches!(
+        cli.command,
+        Commands::Completion(_)
+            | Commands::ShellInit(_)
+            | Commands::Complete_(_)
+            | Commands::Touch(_)
+            | Commands::Root(_)
+    );
+    // Read-only commands reap only when no other gw process is mutating state
+    let reap_lock = if housekeeping && _repo_lock.is_none() {
+        OpLock::try_acquire(&repo_root, "reap")
+    } else {
+        None
+    };
+    let meta = MetaStore::new(&repo_root, &config.meta_backend())
+        .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+
+    cmd::set_time_format(config.time_format());
 
-    let ctx = Context {
+    let mut ctx = Context {
         repo_root,
         git,
         config,
@@ -70,27 +224,65 @@ fn run() -> Result<()> {
         quiet: cli.quiet,
         json: cli.json,
         color: cli.color,
+        yes: cli.yes,
+        interactive: !cli.no_input && std::io::stdin().is_terminal(),
+        no_hooks: cli.no_hooks,
     };
 
+    if housekeeping && (_repo_lock.is_some() || reap_lock.is_some()) {
+        let check_merged = matches!(cli.command, Commands::List(_) | Commands::Gc(_));
+        cmd::reap_ephemeral(&mut ctx, check_merged);
+        cmd::auto_maintenance(&mut ctx);
+    }
+    drop(reap_lock);
+
     match cli.command {
         Commands::Add(args) => cmd::add(&ctx, args),
         Commands::Del(args) => cmd::del(&ctx, args),
+        Commands::Rename(args) => cmd::rename(&ctx, args),
         Commands::List(args) => cmd::list(&ctx, args),
         Commands::Status(args) => cmd::status(&ctx, args),
         Commands::Apply(args) => cmd::apply(&ctx, args),
         Commands::Sync(args) => cmd::sync(&ctx, args),
+        Commands::Train(args) => cmd::train(&ctx, args),
+        Commands::Conflicts(args) => cmd::conflicts(&ctx, args),
         Commands::Verify(args) => cmd::verify(&ctx, args),
         Commands::Note(args) => cmd::note(&ctx, args),
+        Commands::Meta(args) => cmd::meta_cmd(&ctx, args),
         Commands::Info(args) => cmd::info(&ctx, args),
         Commands::Lock(args) => cmd::lock(&ctx, args),
         Commands::Unlock(args) => cmd::unlock(&ctx, args),
         Commands::Gc(args) => cmd::gc(&ctx, args),
         Commands::Cd(args) => cmd::cd(&ctx, args),
         Commands::Exec(args) => cmd::exec_cmd(&ctx, args),
+        Commands::Watch(args) => cmd::watch(&ctx, args),
+        Commands::Git(args) => cmd::git_cmd(&ctx, args),
         Commands::Subdir(args) => cmd::subdir(&ctx, args),
+        Commands::Stats(args) => cmd::stats(&ctx, args),
+        Commands::Summary(args) => cmd::summary(&ctx, args),
+        Commands::Log(args) => cmd::log(&ctx, args),
+        Commands::Review(args) => cmd::review(&ctx, args),
+        Commands::Search(args) => cmd::search(&ctx, args),
+        Commands::Scan(args) => cmd::scan(&ctx, args),
+        Commands::Copy(args) => cmd::copy(&ctx, args),
+        Commands::Transfer(args) => cmd::transfer(&ctx, args),
+        Commands::Stash(args) => cmd::stash(&ctx, args),
+        Commands::Backup(args) => cmd::backup(&ctx, args),
+        Commands::Restore(args) => cmd::restore(&ctx, args),
+        Commands::Assign(args) => cmd::assign(&ctx, args),
+        Commands::Daemon(args) => cmd::daemon(&ctx, args),
+        Commands::Serve(args) => cmd::serve(&ctx, args),
+        Commands::Code(args) => cmd::code(&ctx, args),
+        Commands::Env(args) => cmd::env(&ctx, args),
+        Commands::Recent(args) => cmd::recent(&ctx, args),
+        Commands::Touch(args) => cmd::touch(&ctx, args),
+        Commands::Root(args) => cmd::root(&ctx, args),
+        Commands::Browse(args) => cmd::browse(&ctx, args),
+        Commands::Switch(args) => cmd::switch(&ctx, args),
         Commands::Config(args) => cmd::config(&ctx, args),
         Commands::Completion(args) => cmd::completion(args),
         Commands::ShellInit(args) => cmd::shell_init(args),
         Commands::Complete_(args) => cmd::complete_(&ctx, args),
+        Commands::External(args) => cmd::plugin(&ctx, args),
     }
 }
@@ -0,0 +1,400 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+mod sqlite;
+
+pub use sqlite::SqliteBackend;
+
+/// Where worktree metadata is persisted. `MetaStore` keeps the whole map in
+/// memory; backends only load it and write it back.
+pub trait MetaBackend: fmt::Debug + Send + Sync {
+    fn load(&self) -> anyhow::Result<MetaData>;
+
+    /// Persist `data`. `base` is what `load` returned, so a backend can write
+    /// only the worktrees that changed and leave concurrent edits to others alone.
+    fn save(&self, base: &MetaData, data: &MetaData) -> anyhow::Result<()>;
+
+    /// Past versions of worktree metadata, newest first.
+    fn history(&self, _name: Option<&str>, _limit: usize) -> anyhow::Result<Vec<MetaChange>> {
+        anyhow::bail!("meta history needs `[meta] backend = \"sqlite\"`")
+    }
+}
+
+/// `.gw/meta.json`. A save re-reads the file and applies only the worktrees
+/// that changed, then replaces it in one rename.
+#[derive(Debug)]
+pub struct JsonBackend {
+    path: PathBuf,
+}
+
+impl JsonBackend {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join("meta.json"),
+        }
+    }
+}
+
+impl MetaBackend for JsonBackend {
+    fn load(&self) -> anyhow::Result<MetaData> {
+        if !self.path.exists() {
+            return Ok(MetaData::default());
+        }
+        let raw = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&raw).unwrap_or_default())
+    }
+
+    fn save(&self, base: &MetaData, data: &MetaData) -> anyhow::Result<()> {
+        let changes = changes(base, data);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let mut current = self.load()?;
+        for (name, meta) in changes {
+            match meta {
+                Some(meta) => current.worktrees.insert(name, meta.clone()),
+                None => current.worktrees.remove(&name),
+            };
+        }
+        // Readers never see a half-written file
+        let tmp = self
+            .path
+            .with_extension(format!("json.tmp-{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string_pretty(&current)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// One recorded version of a worktree's metadata; `meta` is `None` once removed.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetaChange {
+    pub name: String,
+    pub at: String,
+    pub meta: Option<WorktreeMeta>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetaStore {
+    backend: Arc<dyn MetaBackend>,
+    base: MetaData,
+    data: MetaData,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetaData {
+    #[serde(default)]
+    pub worktrees: HashMap<String, WorktreeMeta>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorktreeMeta {
+    pub created_at: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub notes: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub last_activity_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<VerifyStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub detached: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ephemeral: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Set once the "expired but has work" warning has been printed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub expiry_warned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_url: Option<String>,
+    /// Where the worktree was created (or adopted by `gw scan` from outside the
+    /// worktrees dir)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Custom key-value fields set with `gw meta set`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Cached outcome of `gw verify` runs for a worktree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VerifyStats {
+    pub runs: u32,
+    pub passed: u32,
+    pub last_passed: Option<bool>,
+    pub last_run_at: Option<String>,
+}
+
+impl VerifyStats {
+    pub fn pass_rate(&self) -> Option<f64> {
+        if self.runs == 0 {
+            return None;
+        }
+        Some(self.passed as f64 / self.runs as f64)
+    }
+}
+
+impl MetaStore {
+    /// Open the store in `<repo_root>/.gw` with the `json` or `sqlite` backend.
+    pub fn new(repo_root: &Path, backend: &str) -> anyhow::Result<Self> {
+        Self::with_backend(open_backend(repo_root, backend)?)
+    }
+
+    pub fn with_backend(backend: Arc<dyn MetaBackend>) -> anyhow::Result<Self> {
+        let data = backend.load()?;
+        Ok(Self {
+            backend,
+            base: data.clone(),
+            data,
+        })
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.backend.save(&self.base, &self.data)
+    }
+
+    pub fn history(&self, name: Option<&str>, limit: usize) -> anyhow::Result<Vec<MetaChange>> {
+        self.backend.history(name, limit)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WorktreeMeta> {
+        self.data.worktrees.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut WorktreeMeta> {
+        self.data.worktrees.get_mut(name)
+    }
+
+    pub fn ensure(&mut self, name: &str) -> &mut WorktreeMeta {
+        self.data.worktrees.entry(name.to_string()).or_default()
+    }
+
+    pub fn set_created(&mut self, name: &str) {
+        let meta = self.ensure(name);
+        if meta.created_at.is_none() {
+            meta.created_at = Some(now());
+        }
+        if meta.created_by.is_none() {
+            meta.created_by = Some(created_by());
+        }
+    }
+
+    pub fn set_last_activity(&mut self, name: &str) {
+        let meta = self.ensure(name);
+        meta.last_activity_at = Some(now());
+    }
+
+    pub fn add_note(&mut self, name: &str, text: String) {
+        let meta = self.ensure(name);
+        meta.notes.push(text);
+    }
+
+    pub fn set_subdir(&mut self, name: &str, subdir: Option<String>) {
+        let meta = self.ensure(name);
+        meta.subdir = subdir;
+    }
+
+    pub fn set_base(&mut self, name: &str, base: Option<String>) {
+        let meta = self.ensure(name);
+        meta.base = base;
+    }
+
+    pub fn set_owner(&mut self, name: &str, owner: Option<String>) {
+        let meta = self.ensure(name);
+        meta.owner = owner;
+    }
+
+    pub fn set_ephemeral(&mut self, name: &str, ttl_secs: Option<i64>) {
+        let meta = self.ensure(name);
+        meta.ephemeral = true;
+        meta.expiry_warned = false;
+        meta.expires_at = ttl_secs.map(|secs| {
+            let at = Utc::now() + chrono::Duration::seconds(secs);
+            at.to_rfc3339()
+        });
+    }
+
+    /// Set a custom field, or remove it when `value` is `None`.
+    pub fn set_field(&mut self, name: &str, key: &str, value: Option<String>) {
+        let meta = self.ensure(name);
+        match value {
+            Some(value) => meta.fields.insert(key.to_string(), value),
+            None => meta.fields.remove(key),
+        };
+    }
+
+    pub fn record_verify(&mut self, name: &str, passed: bool) {
+        let meta = self.ensure(name);
+        let stats = meta.verify.get_or_insert_with(VerifyStats::default);
+        stats.runs += 1;
+        if passed {
+            stats.passed += 1;
+        }
+        stats.last_passed = Some(passed);
+        stats.last_run_at = Some(now());
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.data.worktrees.remove(name);
+    }
+
+    pub fn all(&self) -> &HashMap<String, WorktreeMeta> {
+        &self.data.worktrees
+    }
+}
+
+/// Build the backend named by `[meta] backend`, creating `.gw` if needed.
+pub fn open_backend(repo_root: &Path, backend: &str) -> anyhow::Result<Arc<dyn MetaBackend>> {
+    let dir = repo_root.join(".gw");
+    fs::create_dir_all(&dir)?;
+    match backend {
+        "json" => Ok(Arc::new(JsonBackend::new(&dir))),
+        "sqlite" => Ok(Arc::new(SqliteBackend::new(&dir))),
+        other => anyhow::bail!("unknown meta backend '{}': use json or sqlite", other),
+    }
+}
+
+/// Worktrees whose metadata differs between `base` and `data`: `Some` for
+/// added or changed entries, `None` for removed ones.
+pub fn changes<'a>(
+    base: &MetaData,
+    data: &'a MetaData,
+) -> BTreeMap<String, Option<&'a WorktreeMeta>> {
+    let mut out = BTreeMap::new();
+    for (name, meta) in &data.worktrees {
+        if base.worktrees.get(name) != Some(meta) {
+            out.insert(name.clone(), Some(meta));
+        }
+    }
+    for name in base.worktrees.keys() {
+        if !data.worktrees.contains_key(name) {
+            out.insert(name.clone(), None);
+        }
+    }
+    out
+}
+
+/// Replace `{meta.<key>}` placeholders in `template` with custom fields;
+/// unknown keys expand to an empty string.
+pub fn expand_fields(template: &str, fields: &BTreeMap<String, String>) -> String {
+    const OPEN: &str = "{meta.";
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(OPEN) {
+        let after = &rest[start + OPEN.len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        if let Some(value) = fields.get(&after[..end]) {
+            out.push_str(value);
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn now() -> String {
+    let now: DateTime<Utc> = Utc::now();
+    now.to_rfc3339()
+}
+
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn created_by() -> String {
+    let user = current_user();
+    let host = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    format!("{}@{}", user, host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_fields_replaces_placeholders() {
+        let mut fields = BTreeMap::new();
+        fields.insert("ticket".to_string(), "PAY-12".to_string());
+        assert_eq!(
+            expand_fields("open {meta.ticket} {meta.missing}!", &fields),
+            "open PAY-12 !"
+        );
+        assert_eq!(
+            expand_fields("echo {meta.ticket", &fields),
+            "echo {meta.ticket"
+        );
+        assert_eq!(expand_fields("echo {name}", &fields), "echo {name}");
+    }
+
+    #[test]
+    fn json_saves_keep_concurrent_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend: Arc<dyn MetaBackend> = Arc::new(JsonBackend::new(dir.path()));
+        let mut first = MetaStore::with_backend(backend.clone()).unwrap();
+        let mut second = MetaStore::with_backend(backend.clone()).unwrap();
+        first.add_note("one", "a".into());
+        second.add_note("two", "b".into());
+        first.save().unwrap();
+        second.save().unwrap();
+
+        let mut third = MetaStore::with_backend(backend.clone()).unwrap();
+        assert!(third.get("one").is_some() && third.get("two").is_some());
+        third.remove("one");
+        third.save().unwrap();
+        let names: Vec<_> = backend.load().unwrap().worktrees.into_keys().collect();
+        assert_eq!(names, ["two"]);
+    }
+
+    #[test]
+    fn changes_lists_added_changed_and_removed() {
+        let mut base = MetaData::default();
+        base.worktrees
+            .insert("same".into(), WorktreeMeta::default());
+        base.worktrees
+            .insert("edited".into(), WorktreeMeta::default());
+        base.worktrees
+            .insert("gone".into(), WorktreeMeta::default());
+        let mut data = base.clone();
+        data.worktrees.remove("gone");
+        data.worktrees
+            .get_mut("edited")
+            .unwrap()
+            .notes
+            .push("x".into());
+        data.worktrees.insert("new".into(), WorktreeMeta::default());
+
+        let changed = changes(&base, &data);
+        let summary: Vec<(&str, bool)> = changed
+            .iter()
+            .map(|(name, meta)| (name.as_str(), meta.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("edited", true), ("gone", false), ("new", true)]
+        );
+    }
+}
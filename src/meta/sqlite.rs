@@ -0,0 +1,222 @@
+use super::{MetaBackend, MetaChange, MetaData, WorktreeMeta};
+use serde::Deserialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Milliseconds a writer waits for another gw process to finish its transaction.
+const BUSY_TIMEOUT_MS: u32 = 10_000;
+
+const SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS worktrees (name TEXT PRIMARY KEY, data TEXT NOT NULL, updated_at TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS history (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, at TEXT NOT NULL, data TEXT);
+CREATE INDEX IF NOT EXISTS history_name ON history (name, id);
+CREATE INDEX IF NOT EXISTS worktrees_owner ON worktrees (json_extract(data, '$.owner'));
+CREATE INDEX IF NOT EXISTS worktrees_created_by ON worktrees (json_extract(data, '$.created_by'));
+";
+
+/// `.gw/meta.db`, driven through the `sqlite3` CLI. Each save is one
+/// transaction touching only the worktrees that changed, and every version
+/// is kept in a `history` table.
+#[derive(Debug)]
+pub struct SqliteBackend {
+    path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Row {
+    name: String,
+    #[serde(default)]
+    at: String,
+    data: Option<String>,
+}
+
+impl SqliteBackend {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join("meta.db"),
+        }
+    }
+
+    /// Run `script` after the schema setup; JSON-mode query output is parsed into rows.
+    fn run(&self, script: &str) -> anyhow::Result<Vec<Row>> {
+        let mut child = Command::new("sqlite3")
+            .arg("-bail")
+            .arg(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("failed to run sqlite3 for the meta store: {}", e))?;
+        let input = format!(
+            ".timeout {}\n{}.mode json\n{}",
+            BUSY_TIMEOUT_MS, SCHEMA, script
+        );
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes())?;
+        }
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            anyhow::bail!(
+                "sqlite3 {}: {}",
+                self.path.display(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        // json mode prints one array per statement that returned rows
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let mut rows = Vec::new();
+        for chunk in serde_json::Deserializer::from_str(&stdout).into_iter::<Vec<Row>>() {
+            rows.extend(chunk?);
+        }
+        Ok(rows)
+    }
+}
+
+impl MetaBackend for SqliteBackend {
+    fn load(&self) -> anyhow::Result<MetaData> {
+        let mut data = MetaData::default();
+        for row in self.run("SELECT name, data FROM worktrees;\n")? {
+            let meta = row
+                .data
+                .and_then(|raw| serde_json::from_str(&raw).ok())
+                .unwrap_or_default();
+            data.worktrees.insert(row.name, meta);
+        }
+        Ok(data)
+    }
+
+    fn save(&self, base: &MetaData, data: &MetaData) -> anyhow::Result<()> {
+        let changes = super::changes(base, data);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let mut script = String::from("BEGIN IMMEDIATE;\n");
+        for (name, meta) in changes {
+            let name = quote(&name);
+            match meta {
+                Some(meta) => {
+                    let json = quote(&serde_json::to_string(meta)?);
+                    // Saves from stale copies rewrite identical rows; history
+                    // only records real changes
+                    script.push_str(&format!(
+                        "INSERT INTO history (name, at, data) SELECT {n}, {at}, {d} \
+                         WHERE NOT EXISTS (SELECT 1 FROM worktrees WHERE name = {n} AND data = {d});\n\
+                         INSERT OR REPLACE INTO worktrees (name, data, updated_at) VALUES ({n}, {d}, {at});\n",
+                        n = name,
+                        d = json,
+                        at = "strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+                    ));
+                }
+                None => script.push_str(&format!(
+                    "INSERT INTO history (name, at, data) SELECT name, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), NULL \
+                     FROM worktrees WHERE name = {n};\n\
+                     DELETE FROM worktrees WHERE name = {n};\n",
+                    n = name,
+                )),
+            }
+        }
+        script.push_str("COMMIT;\n");
+        self.run(&script)?;
+        Ok(())
+    }
+
+    fn history(&self, name: Option<&str>, limit: usize) -> anyhow::Result<Vec<MetaChange>> {
+        let filter = name
+            .map(|n| format!("WHERE name = {} ", quote(n)))
+            .unwrap_or_default();
+        let rows = self.run(&format!(
+            "SELECT name, at, data FROM history {}ORDER BY id DESC LIMIT {};\n",
+            filter, limit
+        ))?;
+        Ok(rows
+            .into_iter()
+            .map(|row| MetaChange {
+                name: row.name,
+                at: row.at,
+                meta: row
+                    .data
+                    .and_then(|raw| serde_json::from_str::<WorktreeMeta>(&raw).ok()),
+            })
+            .collect())
+    }
+}
+
+/// SQL string literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_escapes_single_quotes() {
+        assert_eq!(quote("it's"), "'it''s'");
+        assert_eq!(quote(""), "''");
+    }
+
+    fn sqlite_available() -> bool {
+        let found = Command::new("sqlite3").arg("-version").output().is_ok();
+        if !found {
+            eprintln!("sqlite3 not found; skipping");
+        }
+        found
+    }
+
+    fn meta_with_note(note: &str) -> WorktreeMeta {
+        WorktreeMeta {
+            notes: vec![note.to_string()],
+            ..WorktreeMeta::default()
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        if !sqlite_available() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let backend = SqliteBackend::new(dir.path());
+        let base = MetaData::default();
+        let mut data = MetaData::default();
+        data.worktrees
+            .insert("it's".into(), meta_with_note("quote ' inside"));
+        data.worktrees
+            .insert("plain".into(), WorktreeMeta::default());
+        backend.save(&base, &data).unwrap();
+
+        let loaded = backend.load().unwrap();
+        assert_eq!(loaded.worktrees, data.worktrees);
+    }
+
+    #[test]
+    fn history_records_only_real_changes() {
+        if !sqlite_available() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let backend = SqliteBackend::new(dir.path());
+        let empty = MetaData::default();
+        let mut first = MetaData::default();
+        first.worktrees.insert("foo".into(), meta_with_note("one"));
+        backend.save(&empty, &first).unwrap();
+        // A stale copy saving the same row again adds no history
+        backend.save(&empty, &first).unwrap();
+        let mut second = first.clone();
+        second.worktrees.insert("foo".into(), meta_with_note("two"));
+        backend.save(&first, &second).unwrap();
+        backend.save(&second, &empty).unwrap();
+
+        let history = backend.history(Some("foo"), 10).unwrap();
+        let notes: Vec<_> = history
+            .iter()
+            .map(|change| change.meta.as_ref().map(|m| m.notes[0].as_str()))
+            .collect();
+        assert_eq!(notes, [None, Some("two"), Some("one")]);
+        assert!(backend.history(Some("bar"), 10).unwrap().is_empty());
+        assert_eq!(backend.history(None, 1).unwrap().len(), 1);
+        assert!(backend.load().unwrap().worktrees.is_empty());
+    }
+}
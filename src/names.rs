@@ -0,0 +1,160 @@
+use crate::{ErrorKind, GwError, Result};
+
+/// Names gw itself gives meaning to and therefore can't be used for worktrees.
+const RESERVED: [&str; 3] = ["root", ".", ".."];
+
+/// Maximum length of a slugified name.
+const SLUG_MAX_LEN: usize = 48;
+
+/// Check that `name` is usable both as a directory under the worktrees dir and as
+/// the tail of a branch name (see `git check-ref-format`).
+pub fn validate_name(name: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(GwError::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "invalid worktree name '{}': {} (use --slug to sanitize)",
+                name, reason
+            ),
+        ))
+    };
+    if name.is_empty() {
+        return invalid("name is empty");
+    }
+    if RESERVED.contains(&name) {
+        return invalid("name is reserved");
+    }
+    if name.contains('/') || name.contains('\\') {
+        return invalid("path separators are not allowed");
+    }
+    if let Some(ch) = name
+        .chars()
+        .find(|c| c.is_control() || c.is_whitespace() || "~^:?*[".contains(*c))
+    {
+        return invalid(&format!("character {:?} is not allowed", ch));
+    }
+    if name.starts_with('-') || name.starts_with('.') {
+        return invalid("must not start with '-' or '.'");
+    }
+    if name.ends_with('.') || name.ends_with(".lock") {
+        return invalid("must not end with '.' or '.lock'");
+    }
+    if name.contains("..") || name.contains("@{") {
+        return invalid("must not contain '..' or '@{'");
+    }
+    Ok(())
+}
+
+/// Turn an arbitrary string (e.g. an issue title) into a safe worktree name:
+/// lowercase ASCII alphanumerics separated by single dashes.
+pub fn slugify(input: &str) -> String {
+    let mut out = String::new();
+    let mut dash = false;
+    for ch in input.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if dash && !out.is_empty() {
+                out.push('-');
+            }
+            dash = false;
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            dash = true;
+        }
+        if out.len() >= SLUG_MAX_LEN {
+            break;
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+/// Derive a worktree name from a branch using `template`: `{branch}` is the slug of
+/// the whole branch (minus the configured prefix), `{leaf}` the slug of its last segment.
+pub fn name_from_branch(branch: &str, prefix: &str, template: &str) -> String {
+    let trimmed = branch.strip_prefix(prefix).unwrap_or(branch);
+    let leaf = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    let rendered = template
+        .replace("{branch}", &slugify(trimmed))
+        .replace("{leaf}", &slugify(leaf));
+    slugify(&rendered)
+}
+
+/// Expand `{name}`, `{date}`, `{user}` and `{base}` in a `name_template`.
+/// User and base are slugified since they may contain separators (`origin/main`).
+pub fn render_name_template(
+    template: &str,
+    name: &str,
+    date: &str,
+    user: &str,
+    base: &str,
+) -> String {
+    template
+        .replace("{name}", name)
+        .replace("{date}", date)
+        .replace("{user}", &slugify(user))
+        .replace("{base}", &slugify(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_plain_names() {
+        assert!(validate_name("payment").is_ok());
+        assert!(validate_name("fix-123_retry.v2").is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_names() {
+        for bad in [
+            "", "root", ".", "..", "a/b", "a\\b", "a b", "a:b", "a~1", "-x", ".x", "x.", "x.lock",
+            "a..b", "a@{1}",
+        ] {
+            assert!(validate_name(bad).is_err(), "accepted {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn slugify_titles() {
+        assert_eq!(
+            slugify("Fix: payment retry (#512)"),
+            "fix-payment-retry-512"
+        );
+        assert_eq!(slugify("  --Hello   World--  "), "hello-world");
+        assert_eq!(slugify("日本語"), "");
+        assert!(slugify(&"a".repeat(100)).len() <= SLUG_MAX_LEN);
+    }
+
+    #[test]
+    fn name_from_branch_templates() {
+        assert_eq!(
+            name_from_branch("feature/payments-v2", "me/", "{leaf}"),
+            "payments-v2"
+        );
+        assert_eq!(
+            name_from_branch("feature/payments-v2", "me/", "{branch}"),
+            "feature-payments-v2"
+        );
+        assert_eq!(
+            name_from_branch("me/Fix_Login", "me/", "{branch}"),
+            "fix-login"
+        );
+        assert_eq!(name_from_branch("main", "", "wt-{leaf}"), "wt-main");
+    }
+
+    #[test]
+    fn render_name_template_placeholders() {
+        let out = render_name_template(
+            "{date}-{user}-{name}",
+            "login",
+            "2024-05-01",
+            "Jane Doe",
+            "main",
+        );
+        assert_eq!(out, "2024-05-01-jane-doe-login");
+        assert_eq!(
+            render_name_template("{base}_{name}", "x", "", "", "origin/main"),
+            "origin-main_x"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{ErrorKind, GwError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Locks older than this are considered abandoned even if the holder can't be checked.
+const STALE_AFTER_SECS: i64 = 60 * 60;
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Repository-wide advisory lock (`.gw/gw.lock`) serializing mutating gw commands.
+/// Released when dropped.
+#[derive(Debug)]
+pub struct OpLock {
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub command: String,
+    pub user: String,
+    pub started_at: String,
+}
+
+impl LockHolder {
+    /// "sync --all in progress by alice since 12:03 (pid 4242)"
+    pub fn describe(&self) -> String {
+        let since = DateTime::parse_from_rfc3339(&self.started_at)
+            .map(|t| {
+                let local = t.with_timezone(&chrono::Local);
+                if local.date_naive() == chrono::Local::now().date_naive() {
+                    local.format("%H:%M").to_string()
+                } else {
+                    local.format("%Y-%m-%d %H:%M").to_string()
+                }
+            })
+            .unwrap_or_else(|_| self.started_at.clone());
+        format!(
+            "{} in progress by {} since {} (pid {})",
+            self.command, self.user, since, self.pid
+        )
+    }
+}
+
+impl OpLock {
+    /// Wait up to `timeout_secs` for the lock (0 = fail immediately when busy).
+    pub fn acquire(repo_root: &Path, command: &str, timeout_secs: u64) -> Result<Self> {
+        let path = lock_file(repo_root);
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut announced = false;
+        loop {
+            if let Some(lock) = Self::try_create(&path, command)? {
+                return Ok(lock);
+            }
+            let holder = read_holder(&path);
+            // An unreadable lock may be mid-write by its owner; only reclaim it once old
+            let stale = match holder {
+                Some(ref h) => is_stale(h),
+                None => file_age_secs(&path).map(|age| age > 5).unwrap_or(false),
+            };
+            if stale && clear_stale(&path, holder.as_ref()) {
+                continue;
+            }
+            if Instant::now() >= deadline {
+                return Err(GwError::new(
+                    ErrorKind::Locked,
+                    busy_message(holder.as_ref(), timeout_secs),
+                ));
+            }
+            if !announced {
+                if let Some(ref h) = holder {
+                    eprintln!(
+                        "waiting for gw lock held by pid {} (gw {})...",
+                        h.pid, h.command
+                    );
+                }
+                announced = true;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Take the lock only if it is free right now.
+    pub fn try_acquire(repo_root: &Path, command: &str) -> Option<Self> {
+        Self::try_create(&lock_file(repo_root), command)
+            .ok()
+            .flatten()
+    }
+
+    fn try_create(path: &Path, command: &str) -> Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| GwError::new(ErrorKind::General, e.to_string()))?;
+        }
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Ok(None),
+            Err(e) => {
+                return Err(GwError::new(
+                    ErrorKind::General,
+                    format!("failed to create lock: {}", e),
+                ))
+            }
+        };
+        let holder = LockHolder {
+            pid: std::process::id(),
+            command: command.to_string(),
+            user: crate::meta::current_user(),
+            started_at: Utc::now().to_rfc3339(),
+        };
+        let data = serde_json::to_string(&holder).unwrap_or_default();
+        let _ = file.write_all(data.as_bytes());
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+        }))
+    }
+}
+
+impl Drop for OpLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn lock_file(repo_root: &Path) -> PathBuf {
+    repo_root.join(".gw").join("gw.lock")
+}
+
+/// The gw operation holding the repository lock, unless it was abandoned.
+pub fn current(repo_root: &Path) -> Option<LockHolder> {
+    read_holder(&lock_file(repo_root)).filter(|h| !is_stale(h))
+}
+
+pub fn read_holder(path: &Path) -> Option<LockHolder> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn is_stale(holder: &LockHolder) -> bool {
+    if let Some(alive) = pid_alive(holder.pid) {
+        if !alive {
+            return true;
+        }
+    }
+    DateTime::parse_from_rfc3339(&holder.started_at)
+        .map(|t| Utc::now().timestamp() - t.timestamp() > STALE_AFTER_SECS)
+        .unwrap_or(true)
+}
+
+fn file_age_secs(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.elapsed().ok().map(|d| d.as_secs())
+}
+
+/// Remove an abandoned lock. Returns false if it vanished or changed meanwhile.
+///
+/// Two waiters can judge the same lock stale; a plain remove would let the
+/// slower one delete the fresh lock the faster one just took. Instead the file
+/// is renamed aside, so exactly one waiter gets it, and it is only dropped if
+/// it is still the holder that was judged stale. A fresh lock goes back.
+fn clear_stale(path: &Path, judged: Option<&LockHolder>) -> bool {
+    let aside = path.with_extension(format!(
+        "lock.stale-{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    if fs::rename(path, &aside).is_err() {
+        return false;
+    }
+    let taken = read_holder(&aside);
+    let same = match (judged, taken.as_ref()) {
+        (Some(a), Some(b)) => a.pid == b.pid && a.started_at == b.started_at,
+        (None, None) => true,
+        _ => false,
+    };
+    if !same {
+        // hard_link fails rather than overwrite a lock created in the meantime
+        let _ = fs::hard_link(&aside, path);
+    }
+    let _ = fs::remove_file(&aside);
+    same
+}
+
+/// Liveness of a pid where the platform lets us tell cheaply.
+pub(crate) fn pid_alive(pid: u32) -> Option<bool> {
+    let proc_dir = Path::new("/proc");
+    if proc_dir.is_dir() {
+        return Some(proc_dir.join(pid.to_string()).exists());
+    }
+    None
+}
+
+fn busy_message(holder: Option<&LockHolder>, timeout_secs: u64) -> String {
+    match holder {
+        Some(h) => format!(
+            "another gw process holds the repository lock: gw {}; gave up after {}s",
+            h.describe(),
+            timeout_secs
+        ),
+        None => format!(
+            "another gw process holds the repository lock; gave up after {}s",
+            timeout_secs
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_acquire_times_out_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = OpLock::acquire(dir.path(), "add", 0).unwrap();
+        assert!(OpLock::try_acquire(dir.path(), "del").is_none());
+        let err = OpLock::acquire(dir.path(), "del", 0).unwrap_err();
+        assert!(err.message.contains("gw add"));
+        drop(first);
+        assert!(OpLock::try_acquire(dir.path(), "del").is_some());
+    }
+
+    #[test]
+    fn current_operation_is_described() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(current(dir.path()).is_none());
+        let _lock = OpLock::acquire(dir.path(), "sync --all", 0).unwrap();
+        let holder = current(dir.path()).unwrap();
+        assert!(holder
+            .describe()
+            .starts_with(&format!("sync --all in progress by {} since ", holder.user)));
+    }
+
+    #[test]
+    fn stale_check_leaves_a_fresh_lock_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_file(dir.path());
+        let ghost = LockHolder {
+            pid: u32::MAX,
+            command: "sync".to_string(),
+            user: "ghost".to_string(),
+            started_at: "2000-01-01T00:00:00+00:00".to_string(),
+        };
+        // Another waiter already replaced the stale lock with its own
+        let fresh = OpLock::acquire(dir.path(), "gc", 0).unwrap();
+        assert!(!clear_stale(&path, Some(&ghost)));
+        assert_eq!(read_holder(&path).unwrap().command, "gc");
+        drop(fresh);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn abandoned_lock_is_reclaimed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_file(dir.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let holder = LockHolder {
+            pid: u32::MAX,
+            command: "sync".to_string(),
+            user: "ghost".to_string(),
+            started_at: "2000-01-01T00:00:00+00:00".to_string(),
+        };
+        fs::write(&path, serde_json::to_string(&holder).unwrap()).unwrap();
+        assert!(OpLock::acquire(dir.path(), "add", 0).is_ok());
+    }
+}
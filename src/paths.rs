@@ -0,0 +1,194 @@
+//! Path helpers that behave the same on Unix and native Windows checkouts.
+
+use std::path::{Path, PathBuf};
+
+/// Convert a path printed by git (always `/`-separated, even on Windows) into a
+/// native path.
+pub fn from_git(raw: &str) -> PathBuf {
+    let raw = raw.trim();
+    if cfg!(windows) {
+        PathBuf::from(raw.replace('/', "\\"))
+    } else {
+        PathBuf::from(raw)
+    }
+}
+
+/// `canonicalize` without the `\\?\` verbatim prefix Windows adds, so results
+/// compare equal to paths reported by git. Falls back to the input on error.
+pub fn canonical(path: &Path) -> PathBuf {
+    match path.canonicalize() {
+        Ok(canon) => PathBuf::from(strip_verbatim(&canon.to_string_lossy())),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Drop a Windows verbatim prefix: `\\?\C:\x` -> `C:\x`, `\\?\UNC\srv\share` -> `\\srv\share`.
+pub fn strip_verbatim(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Whether two paths name the same location once canonicalized. Windows
+/// comparisons ignore case (drive letters in particular) and separator style.
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    compare_key(&canonical(a).to_string_lossy(), cfg!(windows))
+        == compare_key(&canonical(b).to_string_lossy(), cfg!(windows))
+}
+
+/// `path` relative to `base` as a `/`-separated string, or None when `path` is
+/// not inside `base`.
+pub fn relative_to(path: &Path, base: &Path) -> Option<String> {
+    relative_str(
+        &canonical(path).to_string_lossy(),
+        &canonical(base).to_string_lossy(),
+        cfg!(windows),
+    )
+}
+
+fn relative_str(path: &str, base: &str, ignore_case: bool) -> Option<String> {
+    let path = normalize(path);
+    let base = normalize(base);
+    let (path_key, base_key) = if ignore_case {
+        (path.to_ascii_lowercase(), base.to_ascii_lowercase())
+    } else {
+        (path.clone(), base.clone())
+    };
+    let rest = path_key.strip_prefix(&base_key)?.strip_prefix('/')?;
+    if rest.is_empty() {
+        return None;
+    }
+    // ASCII lowercasing keeps byte offsets, so slice the original for its casing
+    Some(path[path.len() - rest.len()..].to_string())
+}
+
+fn normalize(path: &str) -> String {
+    strip_verbatim(path)
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn compare_key(path: &str, ignore_case: bool) -> String {
+    let path = normalize(path);
+    if ignore_case {
+        path.to_ascii_lowercase()
+    } else {
+        path
+    }
+}
+
+/// Shell command that changes into `path`, quoted so that spaces, `$` and
+/// quotes survive: `Set-Location -LiteralPath '...'` for PowerShell, `cd '...'` otherwise.
+pub fn shell_cd_command(path: &Path, powershell: bool) -> String {
+    let path = path.to_string_lossy();
+    if powershell {
+        format!("Set-Location -LiteralPath '{}'", path.replace('\'', "''"))
+    } else {
+        format!("cd '{}'", path.replace('\'', r"'\''"))
+    }
+}
+
+/// `path` with a leading `home` directory replaced by `~`.
+pub fn home_abbreviated(path: &Path, home: &Path) -> String {
+    match relative_to(path, home) {
+        Some(rest) => format!("~/{}", rest),
+        None => path.to_string_lossy().to_string(),
+    }
+}
+
+/// Combine repeated `-C` options like git: each relative path is taken
+/// relative to the previous one and empty values are ignored.
+pub fn chdir_target(dirs: &[String]) -> Option<PathBuf> {
+    dirs.iter()
+        .filter(|d| !d.is_empty())
+        .fold(None, |acc: Option<PathBuf>, dir| match acc {
+            Some(prev) => Some(prev.join(dir)),
+            None => Some(PathBuf::from(dir)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_verbatim_prefixes() {
+        assert_eq!(strip_verbatim(r"\\?\C:\repo"), r"C:\repo");
+        assert_eq!(
+            strip_verbatim(r"\\?\UNC\srv\share\repo"),
+            r"\\srv\share\repo"
+        );
+        assert_eq!(strip_verbatim("/home/me/repo"), "/home/me/repo");
+    }
+
+    #[test]
+    fn relative_str_handles_windows_forms() {
+        assert_eq!(
+            relative_str(r"\\?\C:\repo\.worktrees\foo", "c:/repo/.worktrees", true),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            relative_str(r"C:\Repo\.worktrees\Foo", r"C:\repo\.worktrees\", true),
+            Some("Foo".to_string())
+        );
+        assert_eq!(
+            relative_str("/repo/.worktrees/foo", "/repo/.worktrees", false),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            relative_str("/Repo/.worktrees/foo", "/repo/.worktrees", false),
+            None
+        );
+        assert_eq!(
+            relative_str("/repo/.worktrees-old/foo", "/repo/.worktrees", false),
+            None
+        );
+        assert_eq!(
+            relative_str("/repo/.worktrees", "/repo/.worktrees", false),
+            None
+        );
+    }
+
+    #[test]
+    fn home_abbreviation() {
+        let home = Path::new("/home/me");
+        assert_eq!(
+            home_abbreviated(Path::new("/home/me/code/app"), home),
+            "~/code/app"
+        );
+        assert_eq!(
+            home_abbreviated(Path::new("/home/meg/app"), home),
+            "/home/meg/app"
+        );
+    }
+
+    #[test]
+    fn chdir_target_combines_like_git() {
+        let dirs = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(chdir_target(&dirs(&[])), None);
+        assert_eq!(chdir_target(&dirs(&[""])), None);
+        assert_eq!(
+            chdir_target(&dirs(&["/src", "api", ""])),
+            Some(PathBuf::from("/src/api"))
+        );
+        assert_eq!(
+            chdir_target(&dirs(&["/src", "/other"])),
+            Some(PathBuf::from("/other"))
+        );
+    }
+
+    #[test]
+    fn shell_cd_quoting() {
+        let path = Path::new("/tmp/it's $HOME");
+        assert_eq!(shell_cd_command(path, false), r"cd '/tmp/it'\''s $HOME'");
+        assert_eq!(
+            shell_cd_command(path, true),
+            "Set-Location -LiteralPath '/tmp/it''s $HOME'"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! `gw add --fetch` must start the worktree from what it just fetched.
+
+use std::path::Path;
+use std::process::Command;
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> String {
+    let out = Command::new(program)
+        .current_dir(dir)
+        .args(args)
+        .env("GW_HOME", dir.join("gw-home"))
+        .env("GIT_AUTHOR_NAME", "t")
+        .env("GIT_AUTHOR_EMAIL", "t@t")
+        .env("GIT_COMMITTER_NAME", "t")
+        .env("GIT_COMMITTER_EMAIL", "t@t")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{} {:?}: {}",
+        program,
+        args,
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+#[test]
+fn add_fetch_starts_from_the_fetched_base() {
+    let tmp = tempfile::tempdir().unwrap();
+    let origin = tmp.path().join("origin");
+    let work = tmp.path().join("work");
+    std::fs::create_dir_all(&origin).unwrap();
+    run(&origin, "git", &["init", "-q", "-b", "main"]);
+    run(
+        &origin,
+        "git",
+        &["commit", "-q", "--allow-empty", "-m", "one"],
+    );
+    run(
+        tmp.path(),
+        "git",
+        &["clone", "-q", origin.to_str().unwrap(), "work"],
+    );
+
+    // The remote moves on after the clone; local main stays behind
+    run(
+        &origin,
+        "git",
+        &["commit", "-q", "--allow-empty", "-m", "two"],
+    );
+    let remote_head = run(&origin, "git", &["rev-parse", "HEAD"]);
+
+    run(
+        &work,
+        env!("CARGO_BIN_EXE_gw"),
+        &["-q", "add", "feat", "-B", "feat", "--fetch"],
+    );
+    let wt = work.join(".worktrees").join("feat");
+    assert_eq!(run(&wt, "git", &["rev-parse", "HEAD"]), remote_head);
+    // origin/main is only the start point, not the new branch's upstream
+    let upstream = Command::new("git")
+        .current_dir(&wt)
+        .args(["rev-parse", "--abbrev-ref", "feat@{upstream}"])
+        .output()
+        .unwrap();
+    assert!(!upstream.status.success());
+}